@@ -0,0 +1,358 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Assertions whose contents are loaded from files (`assertions_from_files`).
+//!
+//! Each entry is a CBOR document, a JSON document or arbitrary bytes wrapped in a CBOR
+//! map with a content type. An entry's `references` names earlier entries; each becomes a
+//! hashed URI (`url`, `alg`, `hash`) in the entry's `references` field. The hash is taken
+//! over the referenced assertion's JUMBF box the way the SDK writes it (unsalted, SHA-256),
+//! so it matches the hash the claim lists for that assertion.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use c2pa::Builder;
+use ciborium::Value as CborValue;
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// JUMBF content type of CBOR assertion boxes
+const CBOR_TYPE: [u8; 16] = [
+    0x63, 0x62, 0x6F, 0x72, 0x00, 0x11, 0x00, 0x10, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// JUMBF content type of JSON assertion boxes
+const JSON_TYPE: [u8; 16] = [
+    0x6A, 0x73, 0x6F, 0x6E, 0x00, 0x11, 0x00, 0x10, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// How the contents of an `assertions_from_files` entry become an assertion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AssertionFileFormat {
+    /// A CBOR document, added as a CBOR assertion
+    Cbor,
+    /// A JSON document, added as a JSON assertion
+    Json,
+    /// Any bytes, added as embedded data with a content type
+    Binary,
+}
+
+impl AssertionFileFormat {
+    /// The entry's `format`, or the format implied by the file extension
+    fn for_entry(format: Option<&str>, file_path: &Path) -> Result<Self> {
+        let extension = file_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        Ok(match (format, extension.as_str()) {
+            (Some("cbor"), _) | (None, "cbor") => Self::Cbor,
+            (Some("json"), _) | (None, "json") => Self::Json,
+            (Some("binary"), _) | (None, _) => Self::Binary,
+            (Some(other), _) => {
+                anyhow::bail!(
+                    "Unknown assertion file format {:?} (use cbor, json or binary)",
+                    other
+                )
+            }
+        })
+    }
+}
+
+/// Assertion data as it is added to the builder
+enum Contents {
+    Cbor(CborValue),
+    Json(JsonValue),
+}
+
+impl Contents {
+    /// Hash of the assertion's JUMBF box contents: the description box, then the content box
+    fn box_hash(&self, label: &str) -> Result<Vec<u8>> {
+        let (content_type, box_type, content) = match self {
+            Self::Cbor(value) => {
+                let mut bytes = Vec::new();
+                ciborium::ser::into_writer(value, &mut bytes)
+                    .context("Failed to encode assertion")?;
+                (CBOR_TYPE, b"cbor", bytes)
+            }
+            Self::Json(value) => (
+                JSON_TYPE,
+                b"json",
+                serde_json::to_vec(value).context("Failed to encode assertion")?,
+            ),
+        };
+        // Requestable and labelled, with no salt
+        let mut description = content_type.to_vec();
+        description.push(0x03);
+        description.extend_from_slice(label.as_bytes());
+        description.push(0);
+
+        let mut payload = jumbf_box(b"jumd", &description);
+        payload.extend(jumbf_box(box_type, &content));
+        Ok(Sha256::digest(&payload).to_vec())
+    }
+
+    /// Set the data's `references` field to these hashed URIs of (label, hash)
+    fn set_references(&mut self, references: &[(String, Vec<u8>)]) -> Result<()> {
+        let url = |label: &str| format!("self#jumbf=c2pa.assertions/{}", label);
+        match self {
+            Self::Cbor(value) => {
+                let map = value
+                    .as_map_mut()
+                    .context("Assertion data with references must be a CBOR map")?;
+                let uris = references
+                    .iter()
+                    .map(|(label, hash)| {
+                        CborValue::Map(vec![
+                            ("url".into(), url(label).into()),
+                            ("alg".into(), "sha256".into()),
+                            ("hash".into(), CborValue::Bytes(hash.clone())),
+                        ])
+                    })
+                    .collect();
+                map.retain(|(k, _)| k.as_text() != Some("references"));
+                map.push(("references".into(), CborValue::Array(uris)));
+            }
+            Self::Json(value) => {
+                let object = value
+                    .as_object_mut()
+                    .context("Assertion data with references must be a JSON object")?;
+                let uris = references
+                    .iter()
+                    .map(|(label, hash)| {
+                        json!({"url": url(label), "alg": "sha256", "hash": STANDARD.encode(hash)})
+                    })
+                    .collect();
+                object.insert("references".into(), JsonValue::Array(uris));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A JUMBF box: 32-bit length, type, contents
+fn jumbf_box(box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(content.len() + 8);
+    data.extend_from_slice(&(content.len() as u32 + 8).to_be_bytes());
+    data.extend_from_slice(box_type);
+    data.extend_from_slice(content);
+    data
+}
+
+/// Read an entry's file into assertion data
+fn load(label: &str, entry: &JsonValue, base_dir: &Path) -> Result<Contents> {
+    let file_path_str = entry
+        .get("file_path")
+        .and_then(|v| v.as_str())
+        .context(format!("Assertion {} must have a file_path field", label))?;
+    let file_path = if Path::new(file_path_str).is_absolute() {
+        PathBuf::from(file_path_str)
+    } else {
+        base_dir.join(file_path_str)
+    };
+    let format = entry.get("format").and_then(|v| v.as_str());
+    let format = AssertionFileFormat::for_entry(format, &file_path)
+        .context(format!("Assertion {}", label))?;
+    let content_type = entry.get("content_type").and_then(|v| v.as_str());
+    if content_type.is_some() && format != AssertionFileFormat::Binary {
+        anyhow::bail!(
+            "Assertion {}: content_type only applies to binary assertions",
+            label
+        );
+    }
+
+    let data =
+        fs::read(&file_path).context(format!("Failed to read assertion file: {:?}", file_path))?;
+    status!(
+        "  Loading assertion {} ({:?}, {} bytes): {:?}",
+        label,
+        format,
+        data.len(),
+        file_path
+    );
+    Ok(match format {
+        AssertionFileFormat::Cbor => Contents::Cbor(
+            ciborium::de::from_reader(data.as_slice())
+                .context(format!("Assertion file is not valid CBOR: {:?}", file_path))?,
+        ),
+        AssertionFileFormat::Json => Contents::Json(
+            serde_json::from_slice(&data)
+                .context(format!("Assertion file is not valid JSON: {:?}", file_path))?,
+        ),
+        AssertionFileFormat::Binary => {
+            let content_type = content_type
+                .or_else(|| {
+                    file_path
+                        .extension()
+                        .and_then(|s| s.to_str())
+                        .and_then(crate::extension_to_mime)
+                })
+                .unwrap_or("application/octet-stream");
+            Contents::Cbor(CborValue::Map(vec![
+                ("content_type".into(), content_type.into()),
+                ("data".into(), CborValue::Bytes(data)),
+            ]))
+        }
+    })
+}
+
+/// Returns the number of assertions added from files
+///
+/// Each entry of `assertions_from_files` names a `label` and a `file_path`, and optionally
+/// the labels of earlier entries it `references`.
+pub fn process_assertion_files(
+    builder: &mut Builder,
+    manifest_json: &str,
+    base_dir: &Path,
+) -> Result<usize> {
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let Some(entries) = manifest
+        .get("assertions_from_files")
+        .and_then(|v| v.as_array())
+    else {
+        return Ok(0);
+    };
+
+    // Box hashes of the entries added so far; None for a label used more than once
+    let mut hashes: HashMap<String, Option<Vec<u8>>> = HashMap::new();
+    for entry in entries {
+        let label = entry
+            .get("label")
+            .and_then(|v| v.as_str())
+            .context("Assertion in assertions_from_files must have a label field")?;
+        let mut contents = load(label, entry, base_dir)?;
+
+        if let Some(references) = entry.get("references") {
+            let references = references
+                .as_array()
+                .and_then(|labels| {
+                    labels
+                        .iter()
+                        .map(|l| l.as_str())
+                        .collect::<Option<Vec<_>>>()
+                })
+                .context(format!(
+                    "Assertion {}: references must be a list of labels",
+                    label
+                ))?;
+            let mut uris = Vec::new();
+            for reference in references {
+                let hash = match hashes.get(reference) {
+                    Some(Some(hash)) => hash.clone(),
+                    Some(None) => anyhow::bail!(
+                        "Assertion {}: more than one assertion is labelled {}",
+                        label,
+                        reference
+                    ),
+                    None => anyhow::bail!(
+                        "Assertion {}: {} is not an earlier entry of assertions_from_files",
+                        label,
+                        reference
+                    ),
+                };
+                uris.push((reference.to_string(), hash));
+            }
+            contents
+                .set_references(&uris)
+                .context(format!("Assertion {}", label))?;
+            status!("    with {} hashed reference(s)", uris.len());
+        }
+
+        let hash = contents.box_hash(label)?;
+        hashes
+            .entry(label.to_string())
+            .and_modify(|existing| *existing = None)
+            .or_insert(Some(hash));
+
+        match &contents {
+            Contents::Cbor(value) => builder.add_assertion(label, value),
+            Contents::Json(value) => builder.add_assertion_json(label, value),
+        }
+        .context(format!("Failed to add assertion {}", label))?;
+    }
+
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assertion_file_format() {
+        let format = |format, path| AssertionFileFormat::for_entry(format, Path::new(path));
+        assert_eq!(
+            format(None, "depth.cbor").unwrap(),
+            AssertionFileFormat::Cbor
+        );
+        assert_eq!(format(None, "a/b.JSON").unwrap(), AssertionFileFormat::Json);
+        assert_eq!(
+            format(None, "depth.png").unwrap(),
+            AssertionFileFormat::Binary
+        );
+        assert_eq!(
+            format(None, "payload").unwrap(),
+            AssertionFileFormat::Binary
+        );
+        assert_eq!(
+            format(Some("binary"), "data.json").unwrap(),
+            AssertionFileFormat::Binary
+        );
+        assert!(format(Some("xml"), "data.xml").is_err());
+    }
+
+    #[test]
+    fn test_references_are_hashed_uris() {
+        let target = Contents::Cbor(CborValue::Map(vec![("a".into(), 1.into())]));
+        let hash = target.box_hash("com.example.target").unwrap();
+
+        // jumd(uuid, toggles, label) followed by cbor({"a": 1})
+        let mut payload = vec![0, 0, 0, 0x2C];
+        payload.extend_from_slice(b"jumd");
+        payload.extend_from_slice(&CBOR_TYPE);
+        payload.push(0x03);
+        payload.extend_from_slice(b"com.example.target\0");
+        payload.extend_from_slice(&[0, 0, 0, 0x0C]);
+        payload.extend_from_slice(b"cbor");
+        payload.extend_from_slice(&[0xA1, 0x61, b'a', 0x01]);
+        assert_eq!(hash, Sha256::digest(&payload).to_vec());
+
+        let references = vec![("com.example.target".to_string(), hash.clone())];
+        let mut cbor = Contents::Cbor(CborValue::Map(vec![]));
+        cbor.set_references(&references).unwrap();
+        let Contents::Cbor(CborValue::Map(map)) = &cbor else {
+            panic!("CBOR map expected");
+        };
+        let uri = &map[0].1.as_array().unwrap()[0];
+        assert_eq!(
+            uri.as_map().unwrap()[0].1.as_text(),
+            Some("self#jumbf=c2pa.assertions/com.example.target")
+        );
+        assert_eq!(uri.as_map().unwrap()[2].1, CborValue::Bytes(hash.clone()));
+
+        let mut json = Contents::Json(json!({"references": "replaced"}));
+        json.set_references(&references).unwrap();
+        let Contents::Json(value) = &json else {
+            panic!("JSON object expected");
+        };
+        assert_eq!(value["references"][0]["hash"], STANDARD.encode(&hash));
+
+        assert!(Contents::Json(json!([]))
+            .set_references(&references)
+            .is_err());
+    }
+}
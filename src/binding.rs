@@ -0,0 +1,535 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Hard binding selection for the manifest's `hard_binding` section.
+//!
+//! By default the SDK picks the hard binding for the format (BMFF hash for BMFF assets,
+//! data hash otherwise). A `hard_binding` section forces the binding type instead:
+//!
+//! - `data_hash`: the manifest is embedded as a placeholder, then the asset is hashed here
+//!   with the manifest's exclusion plus any custom `exclusions`, which are taken as-is so
+//!   overlapping and out-of-range exclusions can be produced on purpose.
+//! - `box_hash`: each box (JPEG segment or PNG chunk) is hashed separately. A custom
+//!   `boxes` list selects and orders the hashed boxes.
+//! - `bmff_hash`: only valid for BMFF assets, which the SDK already binds that way.
+//!
+//! Custom data and box hashes are written for JPEG and PNG, where the manifest goes right
+//! after the SOI marker or the IHDR chunk. Exclusion offsets refer to the signed file.
+
+use anyhow::{Context, Result};
+use c2pa::{assertions::DataHash, Builder, HashRange, Signer};
+use ciborium::Value as CborValue;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::io::Cursor;
+
+/// Name of the box that holds the manifest in box hashes
+pub const C2PA_BOX: &str = "C2PA";
+
+/// MIME types of the BMFF formats the SDK binds with a BMFF hash
+const BMFF_FORMATS: &[&str] = &[
+    "video/mp4",
+    "video/quicktime",
+    "audio/mp4",
+    "application/mp4",
+    "image/heic",
+    "image/heif",
+    "image/avif",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+enum BindingType {
+    #[serde(rename = "data_hash")]
+    Data,
+    #[serde(rename = "box_hash")]
+    Boxes,
+    #[serde(rename = "bmff_hash")]
+    Bmff,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Exclusion {
+    start: u64,
+    length: u64,
+}
+
+/// The manifest's `hard_binding` section
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct HardBinding {
+    #[serde(rename = "type")]
+    kind: BindingType,
+    /// Extra data hash exclusions, in addition to the manifest's own
+    #[serde(default)]
+    exclusions: Vec<Exclusion>,
+    /// Box names to hash, in order; all boxes when absent
+    boxes: Option<Vec<String>>,
+}
+
+/// Parse the `hard_binding` section of a manifest definition, if there is one
+pub fn hard_binding(manifest_json: &str) -> Result<Option<HardBinding>> {
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let Some(section) = manifest.get("hard_binding") else {
+        return Ok(None);
+    };
+    let binding: HardBinding = serde_json::from_value(section.clone())
+        .context("Invalid hard_binding section in manifest")?;
+    if !binding.exclusions.is_empty() && binding.kind != BindingType::Data {
+        anyhow::bail!("hard_binding exclusions only apply to the data_hash type");
+    }
+    if binding.boxes.is_some() && binding.kind != BindingType::Boxes {
+        anyhow::bail!("hard_binding boxes only apply to the box_hash type");
+    }
+    Ok(Some(binding))
+}
+
+/// A box of the asset as (name, start, end)
+pub type AssetBox = (String, usize, usize);
+
+/// Box-hash name of a JPEG marker
+fn jpeg_marker_name(marker: u8) -> String {
+    match marker {
+        0xD8 => "SOI".to_string(),
+        0xD9 => "EOI".to_string(),
+        0xDA => "SOS".to_string(),
+        0xDB => "DQT".to_string(),
+        0xC4 => "DHT".to_string(),
+        0xDD => "DRI".to_string(),
+        0xFE => "COM".to_string(),
+        0xD0..=0xD7 => format!("RST{}", marker - 0xD0),
+        0xE0..=0xEF => format!("APP{}", marker - 0xE0),
+        0xC0..=0xCF => format!("SOF{}", marker - 0xC0),
+        _ => format!("Marker{:02X}", marker),
+    }
+}
+
+/// Whether the JPEG segment at `pos` is an APP11 JUMBF (JPEG XT) segment
+fn jumbf_segment(data: &[u8], pos: usize, marker: u8) -> bool {
+    marker == 0xEB
+        && data.get(pos + 4..pos + 6) == Some(&b"JP"[..])
+        && data.get(pos + 16..pos + 20) == Some(&b"jumb"[..])
+}
+
+/// Split a JPEG into its marker segments; the entropy-coded data belongs to its SOS box and
+/// the APP11 JUMBF segments of a manifest store are `C2PA` boxes
+fn jpeg_boxes(data: &[u8]) -> Result<Vec<AssetBox>> {
+    if !data.starts_with(&[0xFF, 0xD8]) {
+        anyhow::bail!("Not a JPEG file");
+    }
+    let mut boxes = vec![(jpeg_marker_name(0xD8), 0, 2)];
+    let mut pos = 2;
+    while pos < data.len() {
+        if data[pos] != 0xFF || pos + 1 >= data.len() {
+            anyhow::bail!("Malformed JPEG: expected a marker at offset {}", pos);
+        }
+        let marker = data[pos + 1];
+        let end = match marker {
+            // Fill byte before a marker
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            0xD0..=0xD9 | 0x01 => pos + 2,
+            _ => {
+                let length = data
+                    .get(pos + 2..pos + 4)
+                    .map(|bytes| usize::from(u16::from_be_bytes([bytes[0], bytes[1]])))
+                    .context("Malformed JPEG: truncated segment")?;
+                let mut end = pos + 2 + length;
+                if end > data.len() {
+                    anyhow::bail!("Malformed JPEG: segment at offset {} is truncated", pos);
+                }
+                if marker == 0xDA {
+                    // Scan to the next marker that is not a stuffed byte or a restart marker
+                    while end + 1 < data.len()
+                        && !(data[end] == 0xFF
+                            && data[end + 1] != 0
+                            && !(0xD0..=0xD7).contains(&data[end + 1]))
+                    {
+                        end += 1;
+                    }
+                    if end + 1 >= data.len() {
+                        end = data.len();
+                    }
+                }
+                end
+            }
+        };
+        let name = if jumbf_segment(data, pos, marker) {
+            C2PA_BOX.to_string()
+        } else {
+            jpeg_marker_name(marker)
+        };
+        boxes.push((name, pos, end));
+        pos = end;
+        if marker == 0xD9 {
+            break;
+        }
+    }
+    Ok(boxes)
+}
+
+/// Split a PNG into its signature (`PNGh`) and chunks; the `caBX` manifest chunk is the
+/// `C2PA` box
+fn png_boxes(data: &[u8]) -> Result<Vec<AssetBox>> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    if !data.starts_with(SIGNATURE) {
+        anyhow::bail!("Not a PNG file");
+    }
+    let mut boxes = vec![("PNGh".to_string(), 0, SIGNATURE.len())];
+    let mut pos = SIGNATURE.len();
+    while pos < data.len() {
+        let header = data
+            .get(pos..pos + 8)
+            .context("Malformed PNG: truncated chunk header")?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let name = String::from_utf8_lossy(&header[4..8]).to_string();
+        let end = pos + 12 + length;
+        if end > data.len() {
+            anyhow::bail!(
+                "Malformed PNG: chunk {} at offset {} is truncated",
+                name,
+                pos
+            );
+        }
+        let name = if name == "caBX" {
+            C2PA_BOX.to_string()
+        } else {
+            name
+        };
+        boxes.push((name, pos, end));
+        pos = end;
+    }
+    Ok(boxes)
+}
+
+/// Boxes of a JPEG or PNG asset; None for other formats
+pub fn container_boxes(format: &str, data: &[u8]) -> Result<Option<Vec<AssetBox>>> {
+    match format {
+        "image/jpeg" => jpeg_boxes(data).map(Some),
+        "image/png" => png_boxes(data).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Boxes of the asset and the index of the box the manifest is inserted after
+fn asset_boxes(format: &str, data: &[u8]) -> Result<(Vec<AssetBox>, usize)> {
+    let boxes = container_boxes(format, data)?.context(format!(
+        "Custom data and box hashes are supported for JPEG and PNG, not {}",
+        format
+    ))?;
+    if boxes.iter().any(|(name, _, _)| name == C2PA_BOX) {
+        anyhow::bail!("The asset already has a C2PA manifest; remove it with --existing=strip");
+    }
+    let after = if format == "image/jpeg" {
+        "SOI"
+    } else {
+        "IHDR"
+    };
+    let index = boxes
+        .iter()
+        .position(|(name, _, _)| name == after)
+        .context(format!("The asset has no {} box", after))?;
+    Ok((boxes, index))
+}
+
+/// SHA-256 of the bytes outside every exclusion. Exclusions may overlap or run past the
+/// end of the data; only the covered bytes are skipped.
+fn hash_excluding(data: &[u8], exclusions: &[Exclusion]) -> Vec<u8> {
+    let len = data.len() as u64;
+    let mut ranges: Vec<(u64, u64)> = exclusions
+        .iter()
+        .map(|e| (e.start.min(len), e.start.saturating_add(e.length).min(len)))
+        .collect();
+    ranges.sort_unstable();
+
+    let mut hasher = Sha256::new();
+    let mut pos = 0;
+    for (start, end) in ranges {
+        if start > pos {
+            hasher.update(&data[pos as usize..start as usize]);
+        }
+        pos = pos.max(end);
+    }
+    hasher.update(&data[pos as usize..]);
+    hasher.finalize().to_vec()
+}
+
+/// Embed a data hashed manifest after the insertion box
+fn sign_data_hashed(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    data: &[u8],
+    exclusions: &[Exclusion],
+) -> Result<Vec<u8>> {
+    let (boxes, index) = asset_boxes(format, data)?;
+    let offset = boxes[index].2;
+
+    let placeholder = builder
+        .data_hashed_placeholder(signer.reserve_size(), format)
+        .context("Failed to create manifest placeholder")?;
+    let mut asset = Vec::with_capacity(data.len() + placeholder.len());
+    asset.extend_from_slice(&data[..offset]);
+    asset.extend_from_slice(&placeholder);
+    asset.extend_from_slice(&data[offset..]);
+
+    let mut all_exclusions = vec![Exclusion {
+        start: offset as u64,
+        length: placeholder.len() as u64,
+    }];
+    all_exclusions.extend_from_slice(exclusions);
+
+    let mut data_hash = DataHash::new("jumbf manifest", "sha256");
+    for exclusion in &all_exclusions {
+        data_hash.add_exclusion(HashRange::new(exclusion.start, exclusion.length));
+    }
+    data_hash.set_hash(hash_excluding(&asset, &all_exclusions));
+
+    let manifest = builder
+        .sign_data_hashed_embeddable(signer, &data_hash, format)
+        .context("Failed to sign data hashed manifest")?;
+    if manifest.len() != placeholder.len() {
+        anyhow::bail!(
+            "Signed manifest is {} bytes but its placeholder is {} bytes",
+            manifest.len(),
+            placeholder.len()
+        );
+    }
+    asset[offset..offset + manifest.len()].copy_from_slice(&manifest);
+    Ok(asset)
+}
+
+/// The box hash assertion for `boxes` with the manifest box after `index`. Requested names
+/// take the next unused box of that name; names not in the asset hash no data.
+fn box_hash_assertion(
+    data: &[u8],
+    boxes: &[AssetBox],
+    index: usize,
+    names: Option<&[String]>,
+) -> CborValue {
+    let mut layout: Vec<(&str, &[u8])> = boxes
+        .iter()
+        .map(|(name, start, end)| (name.as_str(), &data[*start..*end]))
+        .collect();
+    layout.insert(index + 1, (C2PA_BOX, &[]));
+
+    let selected: Vec<(&str, &[u8])> = match names {
+        None => layout,
+        Some(names) => {
+            let mut used = vec![false; layout.len()];
+            names
+                .iter()
+                .map(|name| {
+                    let found = (0..layout.len()).find(|&i| !used[i] && layout[i].0 == name);
+                    match found {
+                        Some(i) => {
+                            used[i] = true;
+                            layout[i]
+                        }
+                        None => (name.as_str(), &[][..]),
+                    }
+                })
+                .collect()
+        }
+    };
+
+    let text = |s: &str| CborValue::Text(s.to_string());
+    let entries = selected
+        .into_iter()
+        .map(|(name, bytes)| {
+            // The manifest box is not hashed; it only marks the manifest's position
+            let hash = if name == C2PA_BOX {
+                vec![0]
+            } else {
+                Sha256::digest(bytes).to_vec()
+            };
+            CborValue::Map(vec![
+                (text("names"), CborValue::Array(vec![text(name)])),
+                (text("alg"), text("sha256")),
+                (text("hash"), CborValue::Bytes(hash)),
+                (text("pad"), CborValue::Bytes(Vec::new())),
+            ])
+        })
+        .collect();
+    CborValue::Map(vec![(text("boxes"), CborValue::Array(entries))])
+}
+
+/// Embed a box hashed manifest after the insertion box
+fn sign_box_hashed(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    data: &[u8],
+    names: Option<&[String]>,
+) -> Result<Vec<u8>> {
+    let (boxes, index) = asset_boxes(format, data)?;
+    let offset = boxes[index].2;
+
+    builder
+        .add_assertion(
+            "c2pa.hash.boxes",
+            &box_hash_assertion(data, &boxes, index, names),
+        )
+        .context("Failed to add box hash assertion")?;
+    let manifest = builder
+        .sign_box_hashed_embeddable(signer, format)
+        .context("Failed to sign box hashed manifest")?;
+
+    let mut asset = Vec::with_capacity(data.len() + manifest.len());
+    asset.extend_from_slice(&data[..offset]);
+    asset.extend_from_slice(&manifest);
+    asset.extend_from_slice(&data[offset..]);
+    Ok(asset)
+}
+
+/// Sign `data` with the requested hard binding and return the signed asset
+pub fn sign_with_binding(
+    builder: &mut Builder,
+    signer: &dyn Signer,
+    format: &str,
+    data: Vec<u8>,
+    binding: &HardBinding,
+) -> Result<Vec<u8>> {
+    match binding.kind {
+        BindingType::Data => {
+            status!("  Hard binding: data hash");
+            sign_data_hashed(builder, signer, format, &data, &binding.exclusions)
+        }
+        BindingType::Boxes => {
+            status!("  Hard binding: box hash");
+            sign_box_hashed(builder, signer, format, &data, binding.boxes.as_deref())
+        }
+        BindingType::Bmff => {
+            if !BMFF_FORMATS.contains(&format) {
+                anyhow::bail!(
+                    "A BMFF hash needs a BMFF asset (MP4, MOV, HEIC, AVIF), not {}",
+                    format
+                );
+            }
+            status!("  Hard binding: BMFF hash");
+            let mut dest = Cursor::new(Vec::new());
+            builder
+                .sign(signer, format, &mut Cursor::new(data), &mut dest)
+                .context("Failed to sign and embed manifest")?;
+            Ok(dest.into_inner())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exclusion(start: u64, length: u64) -> Exclusion {
+        Exclusion { start, length }
+    }
+
+    #[test]
+    fn test_hash_excluding_overlapping_and_out_of_range() {
+        let data: Vec<u8> = (0..100).collect();
+        let expected = Sha256::digest([&data[..10], &data[40..]].concat()).to_vec();
+        // Overlapping ranges cover 10..40
+        assert_eq!(
+            hash_excluding(&data, &[exclusion(20, 20), exclusion(10, 15)]),
+            expected
+        );
+        // Ranges past the end skip nothing
+        assert_eq!(
+            hash_excluding(&data, &[exclusion(10, 30), exclusion(500, 10)]),
+            expected
+        );
+        let tail = Sha256::digest(&data[..90]).to_vec();
+        assert_eq!(hash_excluding(&data, &[exclusion(90, u64::MAX)]), tail);
+    }
+
+    #[test]
+    fn test_jpeg_and_png_boxes() -> Result<()> {
+        let jpeg = std::fs::read("testfiles/Dog.jpg")?;
+        let (boxes, index) = asset_boxes("image/jpeg", &jpeg)?;
+        assert_eq!(index, 0);
+        assert_eq!(boxes.last().map(|b| b.0.as_str()), Some("EOI"));
+        assert!(boxes.iter().any(|b| b.0 == "SOS"));
+        assert!(
+            boxes.windows(2).all(|w| w[0].2 == w[1].1),
+            "boxes are contiguous"
+        );
+
+        let png = std::fs::read("testfiles/Dog.png")?;
+        let (boxes, index) = asset_boxes("image/png", &png)?;
+        assert_eq!(
+            (boxes[0].0.as_str(), boxes[index].0.as_str()),
+            ("PNGh", "IHDR")
+        );
+        assert_eq!(boxes.last().map(|b| b.0.as_str()), Some("IEND"));
+        assert_eq!(boxes.last().map(|b| b.2), Some(png.len()));
+
+        assert!(asset_boxes("image/webp", &png).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_box_hash_selection() {
+        let data = b"abcdef";
+        let boxes = vec![
+            ("SOI".to_string(), 0, 2),
+            ("APP0".to_string(), 2, 4),
+            ("EOI".to_string(), 4, 6),
+        ];
+        let names = |assertion: &CborValue| -> Vec<String> {
+            let CborValue::Map(fields) = assertion else {
+                panic!()
+            };
+            let CborValue::Array(entries) = &fields[0].1 else {
+                panic!()
+            };
+            entries
+                .iter()
+                .map(|entry| {
+                    let CborValue::Map(entry) = entry else {
+                        panic!()
+                    };
+                    let CborValue::Array(names) = &entry[0].1 else {
+                        panic!()
+                    };
+                    names[0].as_text().unwrap().to_string()
+                })
+                .collect()
+        };
+
+        let all = box_hash_assertion(data, &boxes, 0, None);
+        assert_eq!(names(&all), ["SOI", "C2PA", "APP0", "EOI"]);
+
+        let custom: Vec<String> = ["SOI", "C2PA", "EOI", "XMP"].map(String::from).to_vec();
+        let selected = box_hash_assertion(data, &boxes, 0, Some(&custom));
+        assert_eq!(names(&selected), custom);
+    }
+
+    #[test]
+    fn test_hard_binding_section() -> Result<()> {
+        let parse =
+            |section: JsonValue| hard_binding(&json!({ "hard_binding": section }).to_string());
+        let exclusions = json!([{ "start": 0, "length": 4 }]);
+
+        assert!(hard_binding(r#"{"title": "t"}"#)?.is_none());
+        let binding = parse(json!({ "type": "data_hash", "exclusions": exclusions }))?;
+        assert_eq!(binding.map(|b| b.kind), Some(BindingType::Data));
+        assert!(parse(json!({ "type": "box_hash", "exclusions": exclusions })).is_err());
+        assert!(parse(json!({ "type": "data_hash", "boxes": [] })).is_err());
+        assert!(parse(json!({ "type": "collection_hash" })).is_err());
+        Ok(())
+    }
+}
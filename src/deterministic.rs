@@ -0,0 +1,218 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Reproducible signing support.
+//!
+//! In deterministic mode every value that c2pa-rs would otherwise generate randomly
+//! (manifest label, instance IDs) is derived from a user-provided seed, and actions
+//! without a `when` get a fixed timestamp. Together with a deterministic signature
+//! algorithm this makes repeated runs produce byte-identical files.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+/// Timestamp used for actions that don't specify `when` in deterministic mode
+pub const DETERMINISTIC_WHEN: &str = "2025-01-01T00:00:00Z";
+
+/// Action assertion labels whose actions get a default `when`
+const ACTIONS_LABELS: &[&str] = &["c2pa.actions", "c2pa.actions.v2"];
+
+/// Generator of reproducible identifiers for one output asset
+///
+/// Identifiers are derived from the seed, a per-asset context (typically the input
+/// file name) and a running counter, so the same inputs always produce the same
+/// sequence of identifiers while different assets get different ones.
+pub struct DeterministicIds {
+    seed: String,
+    context: String,
+    counter: u64,
+}
+
+impl DeterministicIds {
+    pub fn new(seed: &str, context: &str) -> Self {
+        Self {
+            seed: seed.to_string(),
+            context: context.to_string(),
+            counter: 0,
+        }
+    }
+
+    /// Derive the next UUID (version 4 layout) for the given purpose
+    pub fn next_uuid(&mut self, purpose: &str) -> String {
+        self.counter += 1;
+
+        let mut hasher = Sha256::new();
+        for part in [
+            self.seed.as_bytes(),
+            self.context.as_bytes(),
+            purpose.as_bytes(),
+            &self.counter.to_be_bytes(),
+        ] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        let digest = hasher.finalize();
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&digest[..16]);
+        bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+        bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+
+        let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    /// Derive a manifest label (URN)
+    pub fn manifest_label(&mut self) -> String {
+        format!("urn:c2pa:{}", self.next_uuid("manifest"))
+    }
+
+    /// Derive an XMP instance ID
+    pub fn instance_id(&mut self) -> String {
+        format!("xmp:iid:{}", self.next_uuid("instance"))
+    }
+}
+
+/// Fill in every randomly generated value of a manifest definition
+///
+/// Sets the manifest label and instance ID, gives inline ingredients without an
+/// instance ID a derived one, and sets `when` on actions that don't have it.
+/// Values already present in the manifest JSON are kept.
+pub fn apply_to_manifest_json(manifest_json: &str, ids: &mut DeterministicIds) -> Result<String> {
+    let mut manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let definition = manifest
+        .as_object_mut()
+        .context("Manifest JSON must be an object")?;
+
+    if !definition.contains_key("label") {
+        definition.insert("label".to_string(), ids.manifest_label().into());
+    }
+    if !definition.contains_key("instance_id") {
+        definition.insert("instance_id".to_string(), ids.instance_id().into());
+    }
+
+    if let Some(ingredients) = definition
+        .get_mut("ingredients")
+        .and_then(|v| v.as_array_mut())
+    {
+        for ingredient in ingredients.iter_mut().filter_map(|i| i.as_object_mut()) {
+            if !ingredient.contains_key("instance_id") {
+                ingredient.insert("instance_id".to_string(), ids.instance_id().into());
+            }
+        }
+    }
+
+    if let Some(assertions) = definition
+        .get_mut("assertions")
+        .and_then(|v| v.as_array_mut())
+    {
+        for assertion in assertions {
+            let is_actions = assertion
+                .get("label")
+                .and_then(|l| l.as_str())
+                .is_some_and(|label| ACTIONS_LABELS.contains(&label));
+            if !is_actions {
+                continue;
+            }
+
+            if let Some(actions) = assertion
+                .pointer_mut("/data/actions")
+                .and_then(|v| v.as_array_mut())
+            {
+                for action in actions.iter_mut().filter_map(|a| a.as_object_mut()) {
+                    action
+                        .entry("when")
+                        .or_insert_with(|| DETERMINISTIC_WHEN.into());
+                }
+            }
+        }
+    }
+
+    serde_json::to_string(&manifest).context("Failed to serialize manifest JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_ids() {
+        let mut first = DeterministicIds::new("seed", "Dog.jpg");
+        let mut second = DeterministicIds::new("seed", "Dog.jpg");
+
+        assert_eq!(first.manifest_label(), second.manifest_label());
+        assert_eq!(first.instance_id(), second.instance_id());
+    }
+
+    #[test]
+    fn test_different_seed_or_context_different_ids() {
+        let label = DeterministicIds::new("seed", "Dog.jpg").manifest_label();
+
+        assert_ne!(
+            label,
+            DeterministicIds::new("other", "Dog.jpg").manifest_label()
+        );
+        assert_ne!(
+            label,
+            DeterministicIds::new("seed", "Dog.png").manifest_label()
+        );
+    }
+
+    #[test]
+    fn test_ids_are_uuid_v4() {
+        let uuid = DeterministicIds::new("seed", "Dog.jpg").next_uuid("test");
+
+        assert_eq!(uuid.len(), 36);
+        assert_eq!(&uuid[14..15], "4");
+        assert!(matches!(&uuid[19..20], "8" | "9" | "a" | "b"));
+    }
+
+    #[test]
+    fn test_apply_fills_missing_values_only() {
+        let manifest = r#"{
+            "title": "Test",
+            "instance_id": "xmp:iid:fixed",
+            "assertions": [{
+                "label": "c2pa.actions",
+                "data": { "actions": [
+                    { "action": "c2pa.created" },
+                    { "action": "c2pa.edited", "when": "2024-01-07T12:00:00Z" }
+                ] }
+            }],
+            "ingredients": [{ "title": "parent.jpg" }]
+        }"#;
+
+        let mut ids = DeterministicIds::new("seed", "Dog.jpg");
+        let applied: JsonValue =
+            serde_json::from_str(&apply_to_manifest_json(manifest, &mut ids).unwrap()).unwrap();
+
+        assert!(applied["label"].as_str().unwrap().starts_with("urn:c2pa:"));
+        assert_eq!(applied["instance_id"], "xmp:iid:fixed");
+        assert!(applied["ingredients"][0]["instance_id"]
+            .as_str()
+            .unwrap()
+            .starts_with("xmp:iid:"));
+
+        let actions = &applied["assertions"][0]["data"]["actions"];
+        assert_eq!(actions[0]["when"], DETERMINISTIC_WHEN);
+        assert_eq!(actions[1]["when"], "2024-01-07T12:00:00Z");
+    }
+}
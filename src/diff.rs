@@ -0,0 +1,550 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Comparison of two manifest stores at the assertion, ingredient and validation level.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::normalize::normalize_manifest_json;
+
+/// Top-level keys holding validation results, in either output format
+const VALIDATION_KEYS: &[&str] = &[
+    "validation_state",
+    "validation_status",
+    "validation_results",
+    "extras:validation_status",
+];
+
+/// Category of a difference between two manifest stores
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffSection {
+    Manifest,
+    Assertion,
+    Ingredient,
+    Validation,
+}
+
+/// A single difference between the left and right manifest store
+#[derive(Debug, Clone, Serialize)]
+pub struct Difference {
+    pub section: DiffSection,
+    /// Where the difference is, e.g. "manifest[0] assertion c2pa.actions.v2"
+    pub location: String,
+    /// JSON pointer below the location
+    pub path: String,
+    pub left: Option<JsonValue>,
+    pub right: Option<JsonValue>,
+}
+
+/// Load a manifest store as JSON from an extracted JSON file or a signed asset
+pub fn load_manifest_store(path: &Path, use_jpt_format: bool) -> Result<JsonValue> {
+    if !path.exists() {
+        anyhow::bail!("Input file does not exist: {:?}", path);
+    }
+
+    let is_json = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+    let manifest_json = if is_json {
+        fs::read_to_string(path).context(format!("Failed to read JSON file: {:?}", path))?
+    } else {
+        crate::read_manifest_store(path, use_jpt_format)?.json
+    };
+
+    serde_json::from_str(&manifest_json).context(format!("Failed to parse JSON from {:?}", path))
+}
+
+/// Compare two manifest stores after normalizing volatile fields
+pub fn diff_manifest_stores(left: &JsonValue, right: &JsonValue) -> Vec<Difference> {
+    let left = normalize_manifest_json(left);
+    let right = normalize_manifest_json(right);
+    let mut differences = Vec::new();
+
+    let left_manifests = manifests_in_order(&left);
+    let right_manifests = manifests_in_order(&right);
+
+    for index in 0..left_manifests.len().max(right_manifests.len()) {
+        let location = format!("manifest[{}]", index);
+        match (left_manifests.get(index), right_manifests.get(index)) {
+            (Some(l), Some(r)) => diff_manifest(&location, l, r, &mut differences),
+            (l, r) => differences.push(Difference {
+                section: DiffSection::Manifest,
+                location,
+                path: String::new(),
+                left: l.map(|m| (*m).clone()),
+                right: r.map(|m| (*m).clone()),
+            }),
+        }
+    }
+
+    let left_status = validation_entries(&left);
+    let right_status = validation_entries(&right);
+    for entry in left_status.difference(&right_status) {
+        differences.push(Difference {
+            section: DiffSection::Validation,
+            location: "validation".to_string(),
+            path: String::new(),
+            left: Some(JsonValue::String(entry.clone())),
+            right: None,
+        });
+    }
+    for entry in right_status.difference(&left_status) {
+        differences.push(Difference {
+            section: DiffSection::Validation,
+            location: "validation".to_string(),
+            path: String::new(),
+            left: None,
+            right: Some(JsonValue::String(entry.clone())),
+        });
+    }
+
+    differences
+}
+
+/// Return the manifests of a store, active manifest first
+///
+/// The standard format stores manifests in an object keyed by label, the JPEG Trust
+/// format uses an array.
+fn manifests_in_order(store: &JsonValue) -> Vec<&JsonValue> {
+    match store.get("manifests") {
+        Some(JsonValue::Array(manifests)) => manifests.iter().collect(),
+        Some(JsonValue::Object(manifests)) => {
+            let active = store.get("active_manifest").and_then(|v| v.as_str());
+            let mut ordered: Vec<&JsonValue> = active
+                .and_then(|label| manifests.get(label))
+                .into_iter()
+                .collect();
+            ordered.extend(
+                manifests
+                    .iter()
+                    .filter(|(label, _)| Some(label.as_str()) != active)
+                    .map(|(_, manifest)| manifest),
+            );
+            ordered
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn diff_manifest(
+    location: &str,
+    left: &JsonValue,
+    right: &JsonValue,
+    differences: &mut Vec<Difference>,
+) {
+    // Assertions, keyed by label
+    let left_assertions = assertions_by_label(left);
+    let right_assertions = assertions_by_label(right);
+    let labels: BTreeSet<&String> = left_assertions
+        .keys()
+        .chain(right_assertions.keys())
+        .collect();
+    for label in labels {
+        diff_values(
+            DiffSection::Assertion,
+            &format!("{} assertion {}", location, label),
+            "",
+            left_assertions.get(label).copied(),
+            right_assertions.get(label).copied(),
+            differences,
+        );
+    }
+
+    // Ingredients, paired by position
+    let empty = Vec::new();
+    let left_ingredients = ingredients(left).unwrap_or(&empty);
+    let right_ingredients = ingredients(right).unwrap_or(&empty);
+    for index in 0..left_ingredients.len().max(right_ingredients.len()) {
+        let left_ingredient = left_ingredients.get(index);
+        let right_ingredient = right_ingredients.get(index);
+        let title = left_ingredient
+            .or(right_ingredient)
+            .and_then(|i| i.get("title"))
+            .and_then(|t| t.as_str())
+            .unwrap_or("untitled");
+        diff_values(
+            DiffSection::Ingredient,
+            &format!("{} ingredient[{}] ({})", location, index, title),
+            "",
+            left_ingredient,
+            right_ingredient,
+            differences,
+        );
+    }
+
+    // Everything else at the manifest level (claim generator, title, signature info, ...)
+    let left_rest = without_keys(left, &["assertions", "ingredients"]);
+    let right_rest = without_keys(right, &["assertions", "ingredients"]);
+    diff_values(
+        DiffSection::Manifest,
+        location,
+        "",
+        Some(&left_rest),
+        Some(&right_rest),
+        differences,
+    );
+}
+
+/// Collect assertions keyed by label from either the array or object representation
+fn assertions_by_label(manifest: &JsonValue) -> BTreeMap<String, &JsonValue> {
+    let mut assertions = BTreeMap::new();
+    match manifest.get("assertions") {
+        Some(JsonValue::Array(items)) => {
+            for item in items {
+                let label = item
+                    .get("label")
+                    .and_then(|l| l.as_str())
+                    .unwrap_or("unlabeled");
+                // Repeated labels get an index suffix, as c2pa does for instances
+                let mut key = label.to_string();
+                let mut instance = 1;
+                while assertions.contains_key(&key) {
+                    instance += 1;
+                    key = format!("{}__{}", label, instance);
+                }
+                assertions.insert(key, item.get("data").unwrap_or(item));
+            }
+        }
+        Some(JsonValue::Object(items)) => {
+            for (label, data) in items {
+                assertions.insert(label.clone(), data);
+            }
+        }
+        _ => {}
+    }
+    assertions
+}
+
+fn ingredients(manifest: &JsonValue) -> Option<&Vec<JsonValue>> {
+    manifest.get("ingredients").and_then(|v| v.as_array())
+}
+
+fn without_keys(value: &JsonValue, keys: &[&str]) -> JsonValue {
+    match value.as_object() {
+        Some(map) => JsonValue::Object(
+            map.iter()
+                .filter(|(k, _)| !keys.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect(),
+        ),
+        None => value.clone(),
+    }
+}
+
+/// Collect validation status codes as "category: code (url)" strings
+fn validation_entries(store: &JsonValue) -> BTreeSet<String> {
+    let mut entries = BTreeSet::new();
+    for key in VALIDATION_KEYS {
+        if let Some(value) = store.get(*key) {
+            collect_status_codes(value, key, &mut entries);
+        }
+    }
+    entries
+}
+
+fn collect_status_codes(value: &JsonValue, category: &str, entries: &mut BTreeSet<String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(code) = map.get("code").and_then(|c| c.as_str()) {
+                let url = map.get("url").and_then(|u| u.as_str()).unwrap_or_default();
+                entries.insert(format!("{}: {} ({})", category, code, url));
+                return;
+            }
+            for (key, child) in map {
+                // Keep the success/informational/failure grouping in the category
+                let category = match key.as_str() {
+                    "success" | "informational" | "failure" => key.as_str(),
+                    _ => category,
+                };
+                collect_status_codes(child, category, entries);
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_status_codes(item, category, entries);
+            }
+        }
+        JsonValue::Null => {}
+        scalar => {
+            entries.insert(format!("{}: {}", category, scalar));
+        }
+    }
+}
+
+/// Recursively compare two JSON values, recording leaf-level differences
+fn diff_values(
+    section: DiffSection,
+    location: &str,
+    path: &str,
+    left: Option<&JsonValue>,
+    right: Option<&JsonValue>,
+    differences: &mut Vec<Difference>,
+) {
+    match (left, right) {
+        (Some(JsonValue::Object(l)), Some(JsonValue::Object(r))) => {
+            let keys: BTreeSet<&String> = l.keys().chain(r.keys()).collect();
+            for key in keys {
+                diff_values(
+                    section,
+                    location,
+                    &format!("{}/{}", path, key),
+                    l.get(key),
+                    r.get(key),
+                    differences,
+                );
+            }
+        }
+        (Some(JsonValue::Array(l)), Some(JsonValue::Array(r))) => {
+            for index in 0..l.len().max(r.len()) {
+                diff_values(
+                    section,
+                    location,
+                    &format!("{}/{}", path, index),
+                    l.get(index),
+                    r.get(index),
+                    differences,
+                );
+            }
+        }
+        (l, r) if l != r => differences.push(Difference {
+            section,
+            location: location.to_string(),
+            path: path.to_string(),
+            left: l.cloned(),
+            right: r.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+fn compact(value: &JsonValue) -> String {
+    const MAX_LEN: usize = 120;
+    let s = value.to_string();
+    if s.len() > MAX_LEN {
+        let end = (0..=MAX_LEN)
+            .rev()
+            .find(|i| s.is_char_boundary(*i))
+            .unwrap_or(0);
+        format!("{}...", &s[..end])
+    } else {
+        s
+    }
+}
+
+/// Print differences grouped by location, one line per changed value
+pub fn print_differences(differences: &[Difference]) {
+    let mut current_location = None;
+    for difference in differences {
+        if current_location != Some(&difference.location) {
+            println!("\n[{}]", difference.location);
+            current_location = Some(&difference.location);
+        }
+        let path = if difference.path.is_empty() {
+            "/"
+        } else {
+            &difference.path
+        };
+        match (&difference.left, &difference.right) {
+            (Some(l), Some(r)) => println!("  ~ {}: {} -> {}", path, compact(l), compact(r)),
+            (Some(l), None) => println!("  - {}: {}", path, compact(l)),
+            (None, Some(r)) => println!("  + {}: {}", path, compact(r)),
+            (None, None) => {}
+        }
+    }
+}
+
+/// Compare the manifests of two signed assets or extracted JSON files
+///
+/// Prints the differences grouped by location and optionally writes them as a JSON
+/// report. Returns an error if the manifest stores differ.
+pub fn diff_manifests(
+    left_path: &Path,
+    right_path: &Path,
+    use_jpt_format: bool,
+    report_path: Option<&Path>,
+) -> Result<()> {
+    println!("=== Comparing C2PA manifests ===");
+    println!("  Left:  {:?}", left_path);
+    println!("  Right: {:?}", right_path);
+    if use_jpt_format {
+        println!("  Format: JPEG Trust");
+    }
+
+    let left = load_manifest_store(left_path, use_jpt_format)?;
+    let right = load_manifest_store(right_path, use_jpt_format)?;
+
+    let differences = diff_manifest_stores(&left, &right);
+    print_differences(&differences);
+
+    let count = |section| differences.iter().filter(|d| d.section == section).count();
+
+    println!("\n=== Diff Summary ===");
+    println!("  Manifest differences: {}", count(DiffSection::Manifest));
+    println!("  Assertion differences: {}", count(DiffSection::Assertion));
+    println!(
+        "  Ingredient differences: {}",
+        count(DiffSection::Ingredient)
+    );
+    println!(
+        "  Validation differences: {}",
+        count(DiffSection::Validation)
+    );
+
+    if let Some(report_path) = report_path {
+        if let Some(parent) = report_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create output directory")?;
+        }
+        let report = json!({
+            "left": left_path,
+            "right": right_path,
+            "differences": differences,
+        });
+        fs::write(
+            report_path,
+            serde_json::to_string_pretty(&report).context("Failed to format JSON")?,
+        )
+        .context("Failed to write diff report")?;
+        println!("  Report file: {:?}", report_path);
+    }
+
+    if !differences.is_empty() {
+        anyhow::bail!(
+            "Manifests differ: {} difference(s) found",
+            differences.len()
+        );
+    }
+
+    println!("\n✓ Manifests are equivalent");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(action: &str, label: &str, code: &str) -> JsonValue {
+        json!({
+            "active_manifest": label,
+            "manifests": {
+                label: {
+                    "title": "Dog.jpg",
+                    "instance_id": format!("xmp:iid:{}", &label[9..]),
+                    "assertions": [
+                        { "label": "c2pa.actions.v2", "data": { "actions": [{ "action": action }] } }
+                    ],
+                    "ingredients": [{ "title": "parent.jpg", "relationship": "parentOf" }],
+                    "signature_info": { "alg": "Ed25519", "time": "2025-01-01T00:00:00Z" }
+                }
+            },
+            "validation_status": [{ "code": code, "url": "self#jumbf=c2pa" }]
+        })
+    }
+
+    #[test]
+    fn test_identical_stores_have_no_differences() {
+        let left = store(
+            "c2pa.created",
+            "urn:c2pa:11111111-1111-4111-8111-111111111111",
+            "claimSignature.validated",
+        );
+        let right = store(
+            "c2pa.created",
+            "urn:c2pa:22222222-2222-4222-8222-222222222222",
+            "claimSignature.validated",
+        );
+
+        assert!(diff_manifest_stores(&left, &right).is_empty());
+    }
+
+    #[test]
+    fn test_assertion_difference_is_reported() {
+        let left = store(
+            "c2pa.created",
+            "urn:c2pa:11111111-1111-4111-8111-111111111111",
+            "claimSignature.validated",
+        );
+        let right = store(
+            "c2pa.edited",
+            "urn:c2pa:22222222-2222-4222-8222-222222222222",
+            "claimSignature.validated",
+        );
+
+        let differences = diff_manifest_stores(&left, &right);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].section, DiffSection::Assertion);
+        assert_eq!(
+            differences[0].location,
+            "manifest[0] assertion c2pa.actions.v2"
+        );
+        assert_eq!(differences[0].path, "/actions/0/action");
+    }
+
+    #[test]
+    fn test_validation_difference_is_reported() {
+        let left = store(
+            "c2pa.created",
+            "urn:c2pa:11111111-1111-4111-8111-111111111111",
+            "claimSignature.validated",
+        );
+        let right = store(
+            "c2pa.created",
+            "urn:c2pa:22222222-2222-4222-8222-222222222222",
+            "signingCredential.untrusted",
+        );
+
+        let differences = diff_manifest_stores(&left, &right);
+        assert_eq!(differences.len(), 2);
+        assert!(differences
+            .iter()
+            .all(|d| d.section == DiffSection::Validation));
+    }
+
+    #[test]
+    fn test_ingredient_difference_is_reported() {
+        let left = store(
+            "c2pa.created",
+            "urn:c2pa:11111111-1111-4111-8111-111111111111",
+            "claimSignature.validated",
+        );
+        let mut right = left.clone();
+        right["manifests"]["urn:c2pa:11111111-1111-4111-8111-111111111111"]["ingredients"][0]
+            ["relationship"] = json!("componentOf");
+
+        let differences = diff_manifest_stores(&left, &right);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].section, DiffSection::Ingredient);
+        assert_eq!(
+            differences[0].location,
+            "manifest[0] ingredient[0] (parent.jpg)"
+        );
+    }
+
+    #[test]
+    fn test_jpt_assertion_objects_are_compared() {
+        let left =
+            json!({ "manifests": [{ "assertions": { "c2pa.actions": { "actions": [] } } }] });
+        let right = json!({ "manifests": [{ "assertions": {} }] });
+
+        let differences = diff_manifest_stores(&left, &right);
+        assert_eq!(differences.len(), 1);
+        assert!(differences[0].right.is_none());
+    }
+}
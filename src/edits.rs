@@ -0,0 +1,672 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Real image edits (`edits` in the manifest JSON).
+//!
+//! The edits are applied to the input pixels with the image crate before signing, and a
+//! matching `c2pa.actions.v2` entry is generated for each one, so the manifest describes
+//! what actually happened to the asset. The unedited input becomes the `parentOf`
+//! ingredient, referenced by a generated `c2pa.opened` action, and images composited
+//! onto it with `place` become `componentOf` ingredients of their `c2pa.placed` actions.
+
+use anyhow::{Context, Result};
+use c2pa::{Ingredient, Relationship};
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, ImageFormat};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+/// Manifest JSON section listing the edits to apply
+const EDITS_FIELD: &str = "edits";
+
+/// Instance ID of the generated parent ingredient, referenced by `c2pa.opened`
+const PARENT_INGREDIENT_ID: &str = "edits-parent";
+
+/// Assertion the generated actions are added to
+const ACTIONS_V2_LABEL: &str = "c2pa.actions.v2";
+
+/// Rectangle in pixels of the image the edit is applied to
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Rect {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Rect {
+    /// Check that the rectangle is non-empty and inside the image
+    fn check(&self, img: &DynamicImage) -> Result<()> {
+        let inside = u64::from(self.x) + u64::from(self.width) <= u64::from(img.width())
+            && u64::from(self.y) + u64::from(self.height) <= u64::from(img.height());
+        if self.width == 0 || self.height == 0 || !inside {
+            anyhow::bail!(
+                "Region {}x{} at ({}, {}) is empty or outside the {}x{} image",
+                self.width,
+                self.height,
+                self.x,
+                self.y,
+                img.width(),
+                img.height()
+            );
+        }
+        Ok(())
+    }
+
+    /// Actions v2 `changes` entry for the rectangle (a pixel spatial region)
+    fn change(&self, description: &str) -> JsonValue {
+        json!({
+            "region": [{
+                "type": "spatial",
+                "shape": {
+                    "type": "rectangle",
+                    "unit": "pixel",
+                    "origin": { "x": self.x, "y": self.y },
+                    "width": self.width,
+                    "height": self.height,
+                },
+            }],
+            "description": description,
+        })
+    }
+}
+
+/// One entry of the manifest's `edits` section
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum Edit {
+    Crop {
+        region: Rect,
+    },
+    /// A missing dimension keeps the aspect ratio
+    Resize {
+        width: Option<u32>,
+        height: Option<u32>,
+    },
+    /// Clockwise rotation by 90, 180 or 270 degrees
+    Rotate {
+        degrees: u32,
+    },
+    /// Gaussian blur of the whole image or a region
+    Blur {
+        sigma: f32,
+        region: Option<Rect>,
+    },
+    ColorAdjust {
+        #[serde(default)]
+        brightness: i32,
+        #[serde(default)]
+        contrast: f32,
+        /// Hue rotation in degrees
+        #[serde(default)]
+        hue: i32,
+        region: Option<Rect>,
+    },
+    /// Fill a region with black
+    RedactRegion {
+        region: Rect,
+    },
+    /// Composite an image file onto the asset with its top-left corner at (x, y)
+    Place {
+        file_path: PathBuf,
+        x: u32,
+        y: u32,
+        #[serde(default = "default_scale")]
+        scale: f32,
+        /// Ingredient title (defaults to the file name)
+        title: Option<String>,
+        /// Ingredient instance ID (defaults to component-N)
+        label: Option<String>,
+    },
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// State shared by the edits of one asset
+struct EditContext<'a> {
+    /// Directory that relative `place` file paths are resolved against
+    base_dir: &'a Path,
+    generate_thumbnails: bool,
+    /// componentOf ingredients of the placed images
+    components: Vec<Ingredient>,
+}
+
+/// Apply `op` to the whole image, or only inside `region`
+fn apply_in_region(
+    img: DynamicImage,
+    region: Option<&Rect>,
+    op: impl Fn(&DynamicImage) -> DynamicImage,
+) -> Result<DynamicImage> {
+    let Some(rect) = region else {
+        return Ok(op(&img));
+    };
+    rect.check(&img)?;
+    let patch = op(&img.crop_imm(rect.x, rect.y, rect.width, rect.height));
+    let mut img = img;
+    imageops::replace(&mut img, &patch, i64::from(rect.x), i64::from(rect.y));
+    Ok(img)
+}
+
+impl Edit {
+    /// Apply the edit; returns the edited image and its actions v2 entry (without
+    /// `when` and `softwareAgent`)
+    fn apply(
+        &self,
+        img: DynamicImage,
+        context: &mut EditContext,
+    ) -> Result<(DynamicImage, JsonValue)> {
+        let (width, height) = (img.width(), img.height());
+        Ok(match self {
+            Edit::Crop { region } => {
+                region.check(&img)?;
+                let cropped = img.crop_imm(region.x, region.y, region.width, region.height);
+                let description = format!(
+                    "Cropped from {}x{} to {}x{}",
+                    width, height, region.width, region.height
+                );
+                let action = json!({
+                    "action": "c2pa.cropped",
+                    "description": description,
+                    "changes": [region.change("Area kept from the original")],
+                });
+                (cropped, action)
+            }
+            Edit::Resize {
+                width: new_width,
+                height: new_height,
+            } => {
+                let scaled = |size: u32, num: u32, den: u32| {
+                    ((u64::from(size) * u64::from(num) + u64::from(den) / 2) / u64::from(den))
+                        .max(1) as u32
+                };
+                let (new_width, new_height) = match (new_width, new_height) {
+                    (Some(w), Some(h)) => (*w, *h),
+                    (Some(w), None) => (*w, scaled(height, *w, width)),
+                    (None, Some(h)) => (scaled(width, *h, height), *h),
+                    (None, None) => anyhow::bail!("resize needs a width or a height"),
+                };
+                if new_width == 0 || new_height == 0 {
+                    anyhow::bail!("resize dimensions must be positive");
+                }
+                let resized = img.resize_exact(new_width, new_height, FilterType::Lanczos3);
+                let description = format!(
+                    "Resized from {}x{} to {}x{}",
+                    width, height, new_width, new_height
+                );
+                let action = json!({ "action": "c2pa.resized", "description": description });
+                (resized, action)
+            }
+            Edit::Rotate { degrees } => {
+                let rotated = match degrees {
+                    90 => img.rotate90(),
+                    180 => img.rotate180(),
+                    270 => img.rotate270(),
+                    other => anyhow::bail!("rotate supports 90, 180 or 270 degrees, not {}", other),
+                };
+                let action = json!({
+                    "action": "c2pa.orientation",
+                    "description": format!("Rotated {} degrees clockwise", degrees),
+                });
+                (rotated, action)
+            }
+            Edit::Blur { sigma, region } => {
+                if !sigma.is_finite() || *sigma <= 0.0 {
+                    anyhow::bail!("blur sigma must be positive");
+                }
+                let blurred = apply_in_region(img, region.as_ref(), |patch| patch.blur(*sigma))?;
+                let description = format!("Gaussian blur (sigma {})", sigma);
+                let mut action = json!({ "action": "c2pa.filtered", "description": description });
+                if let Some(region) = region {
+                    action["changes"] = json!([region.change("Blurred area")]);
+                }
+                (blurred, action)
+            }
+            Edit::ColorAdjust {
+                brightness,
+                contrast,
+                hue,
+                region,
+            } => {
+                if *brightness == 0 && *contrast == 0.0 && *hue == 0 {
+                    anyhow::bail!("color_adjust needs a brightness, contrast or hue change");
+                }
+                let adjusted = apply_in_region(img, region.as_ref(), |patch| {
+                    patch
+                        .brighten(*brightness)
+                        .adjust_contrast(*contrast)
+                        .huerotate(*hue)
+                })?;
+                let description = format!(
+                    "Brightness {:+}, contrast {:+}, hue {:+} degrees",
+                    brightness, contrast, hue
+                );
+                let mut action =
+                    json!({ "action": "c2pa.color_adjustments", "description": description });
+                if let Some(region) = region {
+                    action["changes"] = json!([region.change("Adjusted area")]);
+                }
+                (adjusted, action)
+            }
+            Edit::RedactRegion { region } => {
+                // c2pa.redacted is reserved for redacted assertions (it requires
+                // parameters.redacted), so removed pixels are recorded as c2pa.deleted
+                let redacted = apply_in_region(img, Some(region), |patch| {
+                    DynamicImage::new_rgb8(patch.width(), patch.height())
+                })?;
+                let action = json!({
+                    "action": "c2pa.deleted",
+                    "description": "Region redacted (filled with black)",
+                    "changes": [region.change("Redacted area")],
+                });
+                (redacted, action)
+            }
+            Edit::Place {
+                file_path,
+                x,
+                y,
+                scale,
+                title,
+                label,
+            } => place(img, context, file_path, (*x, *y), *scale, title, label)?,
+        })
+    }
+}
+
+/// Composite an image file onto `img` and add it as a componentOf ingredient
+fn place(
+    mut img: DynamicImage,
+    context: &mut EditContext,
+    file_path: &Path,
+    (x, y): (u32, u32),
+    scale: f32,
+    title: &Option<String>,
+    label: &Option<String>,
+) -> Result<(DynamicImage, JsonValue)> {
+    if !scale.is_finite() || scale <= 0.0 {
+        anyhow::bail!("place scale must be positive");
+    }
+    let path = if file_path.is_absolute() {
+        file_path.to_path_buf()
+    } else {
+        context.base_dir.join(file_path)
+    };
+    let component =
+        image::open(&path).context(format!("Failed to decode component image: {:?}", path))?;
+
+    let scaled = |size: u32| ((size as f32 * scale).round() as u32).max(1);
+    let (width, height) = (scaled(component.width()), scaled(component.height()));
+    let region = Rect {
+        x,
+        y,
+        width,
+        height,
+    };
+    region
+        .check(&img)
+        .context(format!("{:?} does not fit", path))?;
+    let component = if (width, height) == (component.width(), component.height()) {
+        component
+    } else {
+        component.resize_exact(width, height, FilterType::Lanczos3)
+    };
+    imageops::overlay(&mut img, &component, i64::from(x), i64::from(y));
+
+    let file_name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("component");
+    let label = label
+        .clone()
+        .unwrap_or_else(|| format!("component-{}", context.components.len() + 1));
+    let mut ingredient = crate::load_ingredient_from_file(&path, context.generate_thumbnails)?;
+    ingredient.set_title(title.as_deref().unwrap_or(file_name));
+    ingredient.set_relationship(Relationship::ComponentOf);
+    ingredient.set_instance_id(label.as_str());
+    context.components.push(ingredient);
+
+    let description = format!(
+        "Placed {} at ({}, {}), {}x{} ({}% scale)",
+        file_name,
+        x,
+        y,
+        width,
+        height,
+        (scale * 100.0).round()
+    );
+    let action = json!({
+        "action": "c2pa.placed",
+        "description": description,
+        "parameters": { "ingredientIds": [label] },
+        "changes": [region.change("Placed component")],
+    });
+    Ok((img, action))
+}
+
+/// Whether the manifest has a non-empty `edits` section
+pub fn has_edits(manifest_json: &str) -> Result<bool> {
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    Ok(manifest
+        .get(EDITS_FIELD)
+        .and_then(|edits| edits.as_array())
+        .is_some_and(|edits| !edits.is_empty()))
+}
+
+/// Whether the manifest already declares a parentOf ingredient
+pub fn has_parent_ingredient(manifest: &JsonValue) -> bool {
+    ["ingredients", "ingredients_from_files"]
+        .iter()
+        .filter_map(|field| manifest.get(field).and_then(|v| v.as_array()))
+        .flatten()
+        .any(|ingredient| {
+            ingredient
+                .get("relationship")
+                .and_then(|v| v.as_str())
+                .is_some_and(|rel| rel.eq_ignore_ascii_case("parentOf"))
+        })
+}
+
+/// Put the generated actions at the start of the manifest's actions v2 assertion
+fn insert_actions(manifest: &mut JsonValue, generated: Vec<JsonValue>) -> Result<()> {
+    let assertions = manifest
+        .as_object_mut()
+        .context("Manifest JSON must be an object")?
+        .entry("assertions")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("Manifest assertions must be an array")?;
+
+    let label = |assertion: &JsonValue| {
+        assertion
+            .get("label")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    if assertions
+        .iter()
+        .any(|a| label(a).as_deref() == Some("c2pa.actions"))
+    {
+        anyhow::bail!(
+            "Edits generate {} actions; move the c2pa.actions assertion to {}",
+            ACTIONS_V2_LABEL,
+            ACTIONS_V2_LABEL
+        );
+    }
+
+    let Some(existing) = assertions
+        .iter_mut()
+        .find(|a| label(a).as_deref() == Some(ACTIONS_V2_LABEL))
+    else {
+        assertions.push(json!({
+            "label": ACTIONS_V2_LABEL,
+            "data": { "actions": generated },
+            "created": true,
+        }));
+        return Ok(());
+    };
+
+    let actions = existing
+        .pointer_mut("/data/actions")
+        .and_then(|v| v.as_array_mut())
+        .context("c2pa.actions.v2 assertion has no actions array")?;
+    if let Some(first) = actions
+        .first()
+        .and_then(|a| a.get("action"))
+        .and_then(|v| v.as_str())
+    {
+        if first == "c2pa.created" || first == "c2pa.opened" {
+            anyhow::bail!(
+                "Edits generate the c2pa.opened action; remove the {} action from the manifest",
+                first
+            );
+        }
+    }
+    actions.splice(0..0, generated);
+    Ok(())
+}
+
+/// Asset and manifest after applying the manifest's edits
+pub struct EditedAsset {
+    /// Edited asset, encoded in the input format
+    pub data: Vec<u8>,
+    /// Manifest JSON with the generated actions
+    pub manifest_json: String,
+    /// The unedited input as the parentOf ingredient, followed by the componentOf
+    /// ingredients of placed images
+    pub ingredients: Vec<Ingredient>,
+}
+
+/// Apply the `edits` section of the manifest to an image; None if there are no edits
+/// `when` is the timestamp of the generated actions (left unset when None); relative
+/// `place` file paths are resolved against `base_dir`
+pub fn apply_edits(
+    manifest_json: &str,
+    format: &str,
+    data: &[u8],
+    title: &str,
+    base_dir: &Path,
+    generate_thumbnails: bool,
+    when: Option<&str>,
+) -> Result<Option<EditedAsset>> {
+    let mut manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let edits: Vec<Edit> = match manifest.get(EDITS_FIELD) {
+        Some(section) => serde_json::from_value(section.clone())
+            .context(format!("Invalid {} section", EDITS_FIELD))?,
+        None => return Ok(None),
+    };
+    if edits.is_empty() {
+        return Ok(None);
+    }
+    if has_parent_ingredient(&manifest) {
+        anyhow::bail!(
+            "Edits add the original as the parentOf ingredient; \
+             remove the manifest's parentOf ingredient"
+        );
+    }
+
+    let image_format = crate::image_format(format).context(format!(
+        "Edits are only supported for JPEG, PNG, GIF, BMP, TIFF and WebP images, not {}",
+        format
+    ))?;
+    let mut img = image::load_from_memory_with_format(data, image_format)
+        .context("Failed to decode image for editing")?;
+
+    let mut actions = vec![json!({
+        "action": "c2pa.opened",
+        "parameters": { "ingredientIds": [PARENT_INGREDIENT_ID] },
+    })];
+    let mut context = EditContext {
+        base_dir,
+        generate_thumbnails,
+        components: Vec::new(),
+    };
+    for (index, edit) in edits.iter().enumerate() {
+        let (edited, action) = edit
+            .apply(img, &mut context)
+            .context(format!("Edit {} failed", index + 1))?;
+        img = edited;
+        actions.push(action);
+    }
+    for action in &mut actions {
+        action["softwareAgent"] = json!({
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+        });
+        if let Some(when) = when {
+            action["when"] = when.into();
+        }
+    }
+    status!("  Applied {} edit(s) to the asset pixels", edits.len());
+    insert_actions(&mut manifest, actions)?;
+
+    // JPEG has no alpha channel
+    if image_format == ImageFormat::Jpeg {
+        img = DynamicImage::ImageRgb8(img.to_rgb8());
+    }
+    let mut edited = Cursor::new(Vec::new());
+    img.write_to(&mut edited, image_format)
+        .context("Failed to encode edited image")?;
+
+    let mut parent =
+        crate::load_ingredient_from_stream(format, &mut Cursor::new(data), generate_thumbnails)
+            .context("Failed to create parent ingredient from the unedited asset")?;
+    parent.set_title(title);
+    parent.set_relationship(Relationship::ParentOf);
+    parent.set_instance_id(PARENT_INGREDIENT_ID);
+
+    let mut ingredients = vec![parent];
+    ingredients.append(&mut context.components);
+
+    Ok(Some(EditedAsset {
+        data: edited.into_inner(),
+        manifest_json: serde_json::to_string(&manifest)?,
+        ingredients,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edits(json: JsonValue) -> Vec<Edit> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn context(base_dir: &Path) -> EditContext<'_> {
+        EditContext {
+            base_dir,
+            generate_thumbnails: false,
+            components: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_edits_change_pixels_and_describe_regions() {
+        let mut img = DynamicImage::new_rgb8(200, 100);
+        let mut context = context(Path::new("."));
+        let mut actions = Vec::new();
+        for edit in edits(json!([
+            { "type": "crop", "region": { "x": 20, "y": 10, "width": 160, "height": 80 } },
+            { "type": "resize", "width": 80 },
+            { "type": "rotate", "degrees": 90 },
+            { "type": "redact_region", "region": { "x": 0, "y": 0, "width": 10, "height": 10 } },
+        ])) {
+            let (edited, action) = edit.apply(img, &mut context).unwrap();
+            img = edited;
+            actions.push(action);
+        }
+
+        assert_eq!((img.width(), img.height()), (40, 80));
+        assert_eq!(actions[0]["action"], "c2pa.cropped");
+        let shape = &actions[0]["changes"][0]["region"][0]["shape"];
+        assert_eq!(shape["origin"]["x"], 20);
+        assert_eq!(shape["width"], 160);
+        assert_eq!(actions[1]["action"], "c2pa.resized");
+        assert_eq!(actions[1]["description"], "Resized from 160x80 to 80x40");
+        assert_eq!(actions[2]["action"], "c2pa.orientation");
+        assert_eq!(actions[3]["action"], "c2pa.deleted");
+    }
+
+    #[test]
+    fn test_invalid_edits_are_rejected() {
+        let img = || DynamicImage::new_rgb8(100, 100);
+        for edit in edits(json!([
+            { "type": "crop", "region": { "x": 50, "y": 50, "width": 60, "height": 10 } },
+            { "type": "rotate", "degrees": 45 },
+            { "type": "blur", "sigma": 0.0 },
+            { "type": "color_adjust" },
+        ])) {
+            assert!(
+                edit.apply(img(), &mut context(Path::new("."))).is_err(),
+                "{:?} should fail",
+                edit
+            );
+        }
+        // JSON has no NaN, but the sigma is still checked before it reaches the blur
+        let nan = Edit::Blur {
+            sigma: f32::NAN,
+            region: None,
+        };
+        assert!(nan.apply(img(), &mut context(Path::new("."))).is_err());
+        assert!(serde_json::from_value::<Vec<Edit>>(json!([{ "type": "sharpen" }])).is_err());
+    }
+
+    #[test]
+    fn test_place_component() {
+        let testfiles = Path::new(env!("CARGO_MANIFEST_DIR")).join("testfiles");
+        let mut context = context(&testfiles);
+        let [place] = edits(json!([
+            { "type": "place", "file_path": "Dog.png", "x": 40, "y": 30, "scale": 0.25 }
+        ]))
+        .try_into()
+        .unwrap();
+
+        // Dog.png is 1024x683
+        let (img, action) = place
+            .apply(DynamicImage::new_rgb8(400, 300), &mut context)
+            .unwrap();
+        assert_eq!((img.width(), img.height()), (400, 300));
+        assert_eq!(action["action"], "c2pa.placed");
+        assert_eq!(action["parameters"]["ingredientIds"][0], "component-1");
+        let shape = &action["changes"][0]["region"][0]["shape"];
+        assert_eq!(
+            (&shape["origin"]["x"], &shape["origin"]["y"]),
+            (&json!(40), &json!(30))
+        );
+        assert_eq!(
+            (&shape["width"], &shape["height"]),
+            (&json!(256), &json!(171))
+        );
+        assert_eq!(context.components.len(), 1);
+
+        // The scaled component must fit inside the base image
+        let [too_big] = edits(json!([{ "type": "place", "file_path": "Dog.png", "x": 0, "y": 0 }]))
+            .try_into()
+            .unwrap();
+        assert!(too_big
+            .apply(DynamicImage::new_rgb8(400, 300), &mut context)
+            .is_err());
+    }
+
+    #[test]
+    fn test_insert_actions() {
+        let opened = json!({ "action": "c2pa.opened" });
+
+        let mut manifest = json!({ "title": "No actions" });
+        insert_actions(&mut manifest, vec![opened.clone()]).unwrap();
+        assert_eq!(manifest["assertions"][0]["label"], ACTIONS_V2_LABEL);
+
+        let mut manifest = json!({ "assertions": [{
+            "label": ACTIONS_V2_LABEL,
+            "data": { "actions": [{ "action": "c2pa.published" }] },
+        }] });
+        insert_actions(&mut manifest, vec![opened.clone()]).unwrap();
+        let actions = &manifest["assertions"][0]["data"]["actions"];
+        assert_eq!(actions[0]["action"], "c2pa.opened");
+        assert_eq!(actions[1]["action"], "c2pa.published");
+
+        let mut manifest = json!({ "assertions": [{ "label": "c2pa.actions", "data": {} }] });
+        assert!(insert_actions(&mut manifest, vec![opened.clone()]).is_err());
+
+        let mut manifest = json!({ "assertions": [{
+            "label": ACTIONS_V2_LABEL,
+            "data": { "actions": [{ "action": "c2pa.created" }] },
+        }] });
+        assert!(insert_actions(&mut manifest, vec![opened]).is_err());
+    }
+}
@@ -0,0 +1,239 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Manifest stores already present in input assets (`--existing`).
+//!
+//! Signing an asset that already has a manifest store replaces that store, so the previous
+//! provenance is lost unless the asset is also an ingredient. The policy makes this choice
+//! explicit: strip the store first, keep the asset as the parentOf ingredient opened by the
+//! new manifest, replace the store as the SDK does, or refuse to sign.
+
+use anyhow::{Context, Result};
+use c2pa::{Ingredient, Reader, Relationship};
+use serde_json::{json, Value as JsonValue};
+use std::io::{Cursor, Read, Seek};
+use std::path::Path;
+
+use crate::binding::{container_boxes, C2PA_BOX};
+
+/// Instance ID of the existing asset's ingredient, referenced by the c2pa.opened action
+const PARENT_INGREDIENT_ID: &str = "existing-parent";
+
+/// What happens to a manifest store already in the input asset
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExistingPolicy {
+    /// Remove the manifest store before signing (JPEG and PNG)
+    Strip,
+    /// Add the signed input as the parentOf ingredient with a c2pa.opened action
+    Parent,
+    /// Let the new manifest store replace the existing one
+    Replace,
+    /// Refuse to sign assets that already have a manifest store
+    Error,
+}
+
+/// Whether a read found a manifest store; stores that fail to validate or parse count
+fn store_found<T>(result: c2pa::Result<T>) -> bool {
+    !matches!(
+        result,
+        Err(c2pa::Error::JumbfNotFound) | Err(c2pa::Error::UnsupportedType)
+    )
+}
+
+/// Whether the asset in `stream` has a C2PA manifest store
+pub fn has_manifest(format: &str, stream: impl Read + Seek + Send) -> bool {
+    store_found(Reader::from_stream(format, stream))
+}
+
+/// Whether the asset file has a C2PA manifest store
+pub fn has_manifest_file(path: &Path) -> bool {
+    store_found(Reader::from_file(path))
+}
+
+/// Report what happens to an existing manifest store, or fail for the `error` policy
+pub fn report(policy: ExistingPolicy) -> Result<()> {
+    match policy {
+        ExistingPolicy::Strip => status!("  Existing manifest store: stripped before signing"),
+        ExistingPolicy::Parent => {
+            status!("  Existing manifest store: kept in the parentOf ingredient")
+        }
+        ExistingPolicy::Replace => {
+            status!("  ⚠ Existing manifest store: replaced, its provenance is not kept")
+        }
+        ExistingPolicy::Error => anyhow::bail!(
+            "The input already has a C2PA manifest store; \
+             choose what happens to it with --existing=strip|parent|replace"
+        ),
+    }
+    Ok(())
+}
+
+/// The asset without its manifest store. Only JPEG and PNG stores can be removed.
+pub fn strip_manifest(format: &str, data: &[u8]) -> Result<Vec<u8>> {
+    let boxes = container_boxes(format, data)?.context(format!(
+        "--existing=strip supports JPEG and PNG assets, not {}; use --existing=replace",
+        format
+    ))?;
+    let mut stripped = Vec::with_capacity(data.len());
+    let mut pos = 0;
+    for (_, start, end) in boxes.iter().filter(|(name, _, _)| name == C2PA_BOX) {
+        stripped.extend_from_slice(&data[pos..*start]);
+        pos = *end;
+    }
+    stripped.extend_from_slice(&data[pos..]);
+    Ok(stripped)
+}
+
+/// Put a c2pa.opened action for the existing asset first in the manifest's actions
+fn insert_opened_action(manifest: &mut JsonValue) -> Result<()> {
+    let opened = json!({
+        "action": "c2pa.opened",
+        "parameters": { "ingredientIds": [PARENT_INGREDIENT_ID] },
+    });
+    let assertions = manifest
+        .as_object_mut()
+        .context("Manifest JSON must be an object")?
+        .entry("assertions")
+        .or_insert_with(|| json!([]))
+        .as_array_mut()
+        .context("Manifest assertions must be an array")?;
+
+    let Some(existing) = assertions.iter_mut().find(|assertion| {
+        matches!(
+            assertion.get("label").and_then(|v| v.as_str()),
+            Some("c2pa.actions" | "c2pa.actions.v2")
+        )
+    }) else {
+        assertions.push(json!({
+            "label": "c2pa.actions.v2",
+            "data": { "actions": [opened] },
+            "created": true,
+        }));
+        return Ok(());
+    };
+
+    let actions = existing
+        .pointer_mut("/data/actions")
+        .and_then(|v| v.as_array_mut())
+        .context("Actions assertion has no actions array")?;
+    if let Some(first) = actions
+        .first()
+        .and_then(|a| a.get("action"))
+        .and_then(|v| v.as_str())
+    {
+        if first == "c2pa.created" || first == "c2pa.opened" {
+            anyhow::bail!(
+                "--existing=parent adds the c2pa.opened action; \
+                 remove the {} action from the manifest",
+                first
+            );
+        }
+    }
+    actions.insert(0, opened);
+    Ok(())
+}
+
+/// Add the signed input as the parentOf ingredient: returns the manifest JSON with its
+/// c2pa.opened action and the ingredient, which carries the existing manifest store
+pub fn add_existing_parent(
+    manifest_json: &str,
+    format: &str,
+    data: &[u8],
+    title: &str,
+    generate_thumbnails: bool,
+) -> Result<(String, Ingredient)> {
+    let mut manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    if crate::edits::has_parent_ingredient(&manifest) {
+        anyhow::bail!(
+            "--existing=parent adds the input as the parentOf ingredient; \
+             remove the manifest's parentOf ingredient"
+        );
+    }
+    insert_opened_action(&mut manifest)?;
+
+    let mut parent =
+        crate::load_ingredient_from_stream(format, &mut Cursor::new(data), generate_thumbnails)
+            .context("Failed to create parent ingredient from the signed input")?;
+    parent.set_title(title);
+    parent.set_relationship(Relationship::ParentOf);
+    parent.set_instance_id(PARENT_INGREDIENT_ID);
+
+    Ok((serde_json::to_string(&manifest)?, parent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dog.jpg with a fake manifest store split over two APP11 JUMBF segments after SOI
+    fn jpeg_with_store() -> (Vec<u8>, Vec<u8>) {
+        let original = std::fs::read("testfiles/Dog.jpg").unwrap();
+        let segment = |payload: &[u8]| {
+            let mut segment = vec![0xFF, 0xEB];
+            segment.extend_from_slice(&((payload.len() + 18) as u16).to_be_bytes());
+            segment.extend_from_slice(b"JP\x02\x11\x00\x00\x00\x01");
+            segment.extend_from_slice(&((payload.len() + 8) as u32).to_be_bytes());
+            segment.extend_from_slice(b"jumb");
+            segment.extend_from_slice(payload);
+            segment
+        };
+        let mut signed = original[..2].to_vec();
+        signed.extend(segment(b"c2pa first part"));
+        signed.extend(segment(b"second part"));
+        signed.extend_from_slice(&original[2..]);
+        (original, signed)
+    }
+
+    #[test]
+    fn test_strip_manifest() -> Result<()> {
+        let (original, signed) = jpeg_with_store();
+        assert_eq!(strip_manifest("image/jpeg", &signed)?, original);
+        assert_eq!(strip_manifest("image/jpeg", &original)?, original);
+
+        let png = std::fs::read("testfiles/Dog.png")?;
+        let mut chunk = 4u32.to_be_bytes().to_vec();
+        chunk.extend_from_slice(b"caBXjumb\0\0\0\0");
+        let mut signed = png[..33].to_vec();
+        signed.extend(chunk);
+        signed.extend_from_slice(&png[33..]);
+        assert_eq!(strip_manifest("image/png", &signed)?, png);
+
+        assert!(strip_manifest("image/webp", &png).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_opened_action() {
+        let mut manifest = json!({
+            "assertions": [{
+                "label": "c2pa.actions",
+                "data": { "actions": [{ "action": "c2pa.edited" }] }
+            }]
+        });
+        insert_opened_action(&mut manifest).unwrap();
+        let actions = &manifest["assertions"][0]["data"]["actions"];
+        assert_eq!(actions[0]["action"], "c2pa.opened");
+        assert_eq!(
+            actions[0]["parameters"]["ingredientIds"][0],
+            PARENT_INGREDIENT_ID
+        );
+        assert_eq!(actions[1]["action"], "c2pa.edited");
+
+        // A second opened action would conflict
+        assert!(insert_opened_action(&mut manifest).is_err());
+
+        let mut manifest = json!({ "title": "no actions" });
+        insert_opened_action(&mut manifest).unwrap();
+        assert_eq!(manifest["assertions"][0]["label"], "c2pa.actions.v2");
+    }
+}
@@ -0,0 +1,553 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Seeded fuzz corpus generation (`--fuzz`).
+//!
+//! Every manifest is random but well formed: an inception action followed by edits, a few
+//! optional assertions, ingredients (inline, or earlier corpus files so provenance chains
+//! form) and metadata values that include non-ASCII text. Random values come from SHA-256
+//! of the seed rather than a library generator, so a seed gives the same corpus on every
+//! platform and release. Signing is deterministic with the same seed.
+
+use anyhow::{Context, Result};
+use image::{DynamicImage, ImageFormat, RgbImage};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::{KeySource, ProcessingConfig};
+
+/// Report file written to the output directory
+const REPORT_FILE: &str = "fuzz_report.json";
+
+const DIGITAL_SOURCE_TYPES: &[&str] = &[
+    "digitalCapture",
+    "trainedAlgorithmicMedia",
+    "compositeSynthetic",
+    "algorithmicMedia",
+    "minorHumanEdits",
+    "digitalArt",
+];
+
+/// Actions that follow the inception action
+const EDIT_ACTIONS: &[&str] = &[
+    "c2pa.edited",
+    "c2pa.cropped",
+    "c2pa.resized",
+    "c2pa.color_adjustments",
+    "c2pa.filtered",
+    "c2pa.drawing",
+    "c2pa.orientation",
+    "c2pa.transcoded",
+    "c2pa.published",
+    "c2pa.watermarked",
+];
+
+const AGENTS: &[(&str, &str)] = &[
+    ("Photo Editor", "2.0"),
+    ("GIMP", "2.10.34"),
+    ("Custom Image Tool", "1.5.2"),
+    ("Générateur d'images", "0.9"),
+];
+
+/// Words for titles and metadata values, some outside ASCII
+const WORDS: &[&str] = &[
+    "dog", "harbor", "sunset", "portrait", "café", "東京", "naïve", "Zürich", "🐕", "test",
+];
+
+/// Reproducible random values derived from a seed and a corpus index
+struct FuzzRng {
+    seed: String,
+    index: usize,
+    counter: u64,
+}
+
+impl FuzzRng {
+    fn new(seed: &str, index: usize) -> Self {
+        Self {
+            seed: seed.to_string(),
+            index,
+            counter: 0,
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.counter += 1;
+        let mut hasher = Sha256::new();
+        hasher.update((self.seed.len() as u64).to_be_bytes());
+        hasher.update(self.seed.as_bytes());
+        hasher.update((self.index as u64).to_be_bytes());
+        hasher.update(self.counter.to_be_bytes());
+        let digest = hasher.finalize();
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&digest[..8]);
+        u64::from_be_bytes(bytes)
+    }
+
+    /// A value in `0..n`
+    fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// True with the given percent chance
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    fn words(&mut self, count: usize) -> String {
+        let words: Vec<&str> = (0..count).map(|_| *self.pick(WORDS)).collect();
+        words.join(" ")
+    }
+
+    fn hex(&mut self, bytes: usize) -> String {
+        (0..bytes)
+            .map(|_| format!("{:02x}", self.below(256)))
+            .collect()
+    }
+
+    /// A timestamp between 2020 and 2026
+    fn when(&mut self) -> String {
+        const START: i64 = 1_577_836_800; // 2020-01-01T00:00:00Z
+        crate::time::rfc3339(START + self.below(6 * 365 * 86_400) as i64)
+    }
+}
+
+/// An ingredient of a generated manifest
+struct FuzzIngredient {
+    label: String,
+    relationship: &'static str,
+}
+
+fn ingredients(rng: &mut FuzzRng, earlier: &[String]) -> (Vec<FuzzIngredient>, JsonValue) {
+    let mut ingredients = Vec::new();
+    let (mut inline, mut from_files) = (Vec::new(), Vec::new());
+    for k in 0..rng.below(4) {
+        let relationship = match k {
+            0 if rng.chance(50) => "parentOf",
+            _ if rng.chance(80) => "componentOf",
+            _ => "inputTo",
+        };
+        let label = format!("ingredient-{}", k);
+        // Files loaded from disk can only be parentOf or componentOf
+        if relationship != "inputTo" && !earlier.is_empty() && rng.chance(50) {
+            from_files.push(json!({
+                "file_path": rng.pick(earlier),
+                "label": label,
+                "relationship": relationship,
+            }));
+        } else {
+            inline.push(json!({
+                "title": format!("{}.jpg", rng.words(1)),
+                "format": "image/jpeg",
+                "relationship": relationship,
+                "label": label,
+            }));
+        }
+        ingredients.push(FuzzIngredient {
+            label,
+            relationship,
+        });
+    }
+    (
+        ingredients,
+        json!({ "ingredients": inline, "ingredients_from_files": from_files }),
+    )
+}
+
+fn actions_assertion(rng: &mut FuzzRng, ingredients: &[FuzzIngredient]) -> JsonValue {
+    let v2 = rng.chance(50);
+    let agent = |rng: &mut FuzzRng| {
+        let (name, version) = *rng.pick(AGENTS);
+        if v2 {
+            json!({ "name": name, "version": version })
+        } else {
+            json!(format!("{} {}", name, version))
+        }
+    };
+
+    let mut actions = Vec::new();
+    let ids = |relationship: &str| -> Vec<&str> {
+        ingredients
+            .iter()
+            .filter(|i| i.relationship == relationship)
+            .map(|i| i.label.as_str())
+            .collect()
+    };
+    let parents = ids("parentOf");
+    let mut first = if parents.is_empty() {
+        json!({
+            "action": "c2pa.created",
+            "digitalSourceType": format!(
+                "http://cv.iptc.org/newscodes/digitalsourcetype/{}",
+                rng.pick(DIGITAL_SOURCE_TYPES)
+            ),
+        })
+    } else {
+        json!({ "action": "c2pa.opened", "parameters": { "ingredientIds": parents } })
+    };
+    first["when"] = json!(rng.when());
+    actions.push(first);
+
+    for _ in 0..rng.below(5) {
+        let mut action = json!({ "action": rng.pick(EDIT_ACTIONS), "when": rng.when() });
+        if rng.chance(50) {
+            action["softwareAgent"] = agent(rng);
+        }
+        if rng.chance(30) {
+            action["description"] = json!(rng.words(3));
+        }
+        actions.push(action);
+    }
+    // Each component is placed somewhere after the inception action
+    for label in ids("componentOf") {
+        let position = 1 + rng.below(actions.len());
+        actions.insert(
+            position,
+            json!({
+                "action": "c2pa.placed",
+                "when": rng.when(),
+                "parameters": { "ingredientIds": [label] },
+            }),
+        );
+    }
+
+    json!({
+        "label": if v2 { "c2pa.actions.v2" } else { "c2pa.actions" },
+        "data": { "actions": actions },
+        "created": true,
+    })
+}
+
+/// Optional assertions, each present about half the time
+fn other_assertions(rng: &mut FuzzRng) -> Vec<JsonValue> {
+    let mut assertions = Vec::new();
+    if rng.chance(40) {
+        let types = [
+            "c2pa.types.generator",
+            "c2pa.types.model",
+            "com.example.fuzz",
+        ];
+        assertions.push(json!({
+            "label": "c2pa.asset-type",
+            "data": { "types": [{
+                "type": rng.pick(&types),
+                "version": format!("{}.{}", rng.below(5), rng.below(10)),
+            }]},
+        }));
+    }
+    if rng.chance(40) {
+        assertions.push(json!({
+            "label": "c2pa.soft-binding",
+            "data": {
+                "alg": "com.example.fuzz-watermark",
+                "blocks": [{ "scope": {}, "value": format!("0x{}", rng.hex(8)) }],
+            },
+        }));
+    }
+    if rng.chance(40) {
+        let references: Vec<JsonValue> = (0..1 + rng.below(3))
+            .map(|_| {
+                json!({
+                    "reference": { "uri": format!("https://example.com/{}.jpg", rng.hex(4)) },
+                    "description": rng.words(2),
+                })
+            })
+            .collect();
+        assertions.push(json!({
+            "label": "c2pa.asset-ref",
+            "data": { "references": references },
+        }));
+    }
+    if rng.chance(60) {
+        let word_count = 1 + rng.below(4);
+        let list: Vec<JsonValue> = (0..rng.below(4)).map(|_| json!(rng.below(1000))).collect();
+        assertions.push(json!({
+            "label": "com.example.fuzz.metadata",
+            "data": {
+                "text": rng.words(word_count),
+                "number": rng.below(1 << 20) as f64 / 16.0,
+                "flag": rng.chance(50),
+                "list": list,
+                "nested": { "depth": 1, "value": { "text": rng.words(2) } },
+            },
+        }));
+    }
+    assertions
+}
+
+/// A random manifest; `earlier` are corpus files it may use as ingredients
+fn fuzz_manifest(rng: &mut FuzzRng, name: &str, earlier: &[String]) -> JsonValue {
+    let (ingredients, mut manifest) = ingredients(rng, earlier);
+    let mut assertions = vec![actions_assertion(rng, &ingredients)];
+    assertions.extend(other_assertions(rng));
+
+    manifest["claim_generator_info"] = json!([{
+        "name": "c2pa-testfile-maker",
+        "version": env!("CARGO_PKG_VERSION"),
+    }]);
+    manifest["title"] = json!(format!("{} {}", name, rng.words(2)));
+    manifest["assertions"] = json!(assertions);
+    manifest
+}
+
+/// A generated gradient image; returns its MIME type, extension and bytes
+fn fuzz_image(rng: &mut FuzzRng) -> Result<(&'static str, &'static str, Vec<u8>)> {
+    let (width, height) = (32 + rng.below(225) as u32, 32 + rng.below(225) as u32);
+    let base = [rng.below(256), rng.below(256), rng.below(256)];
+    let img = RgbImage::from_fn(width, height, |x, y| {
+        let shade = (x * 255 / width + y * 255 / height) as usize;
+        image::Rgb(base.map(|c| ((c + shade) % 256) as u8))
+    });
+    let (mime, ext, format) = if rng.chance(50) {
+        ("image/jpeg", "jpg", ImageFormat::Jpeg)
+    } else {
+        ("image/png", "png", ImageFormat::Png)
+    };
+    let mut data = Cursor::new(Vec::new());
+    DynamicImage::ImageRgb8(img)
+        .write_to(&mut data, format)
+        .context("Failed to encode generated image")?;
+    Ok((mime, ext, data.into_inner()))
+}
+
+/// Options for a fuzz run
+pub struct FuzzOptions<'a> {
+    pub output_dir: &'a Path,
+    /// Assets to sign onto; images are generated when empty
+    pub assets: &'a [PathBuf],
+    pub count: usize,
+    pub seed: &'a str,
+    pub cert: &'a Path,
+    pub key: KeySource<'a>,
+    pub signing_alg: c2pa::SigningAlg,
+    pub allow_self_signed: bool,
+}
+
+/// Outcome of one corpus entry in the report
+#[derive(Serialize)]
+struct FuzzResult {
+    name: String,
+    /// Source asset, or "generated"
+    asset: String,
+    manifest: String,
+    output: String,
+    signed: bool,
+    /// Whether c2pa-rs reads the output back without validation failures
+    accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_state: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Sign a manifest onto an asset and read it back; returns the validation state and codes
+fn sign_entry(
+    options: &FuzzOptions,
+    manifest_json: &str,
+    mime: &str,
+    output: &Path,
+    data: Vec<u8>,
+) -> Result<(String, Vec<String>)> {
+    let config = ProcessingConfig {
+        allow_self_signed: options.allow_self_signed,
+        deterministic_seed: Some(options.seed),
+        ..ProcessingConfig::new(
+            manifest_json,
+            options.output_dir,
+            options.cert,
+            options.key,
+            options.signing_alg,
+        )
+    };
+    let name = output
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let signed = crate::sign_asset_bytes(&config, mime, name, data)?;
+    fs::write(output, signed).context(format!("Failed to write {:?}", output))?;
+
+    let store = crate::read_manifest_store(output, false)?;
+    let store: JsonValue =
+        serde_json::from_str(&store.json).context("Failed to parse manifest JSON")?;
+    let state = store
+        .get("validation_state")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+    let codes = crate::summary::active_status_codes(&store, "failure")
+        .iter()
+        .filter_map(|status| status.get("code").and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .collect();
+    Ok((state, codes))
+}
+
+/// Generate, sign and read back `count` random manifests
+pub fn run_fuzz(options: &FuzzOptions) -> Result<()> {
+    fs::create_dir_all(options.output_dir).context("Failed to create output directory")?;
+    println!("=== Fuzz corpus ===");
+    println!("  Seed: {}", options.seed);
+    println!("  Count: {}", options.count);
+    if options.assets.is_empty() {
+        println!("  Assets: generated");
+    } else {
+        println!("  Assets: {}", options.assets.len());
+    }
+
+    let mut results = Vec::new();
+    // Signed outputs, by file name, that later manifests may use as ingredients
+    let mut earlier = Vec::new();
+    for index in 0..options.count {
+        let mut rng = FuzzRng::new(options.seed, index);
+        let name = format!("fuzz-{:04}", index);
+
+        let (asset, mime, ext, data) = if options.assets.is_empty() {
+            let (mime, ext, data) = fuzz_image(&mut rng)?;
+            ("generated".to_string(), mime, ext, data)
+        } else {
+            let asset = rng.pick(options.assets);
+            let ext = asset
+                .extension()
+                .and_then(|s| s.to_str())
+                .context(format!("Asset has no extension: {:?}", asset))?;
+            let mime = crate::extension_to_mime(ext)
+                .context(format!("Unsupported asset format: {:?}", asset))?;
+            let data = fs::read(asset).context(format!("Failed to read asset: {:?}", asset))?;
+            (asset.display().to_string(), mime, ext, data)
+        };
+
+        let manifest = fuzz_manifest(&mut rng, &name, &earlier);
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+        let manifest_file = format!("{}.json", name);
+        fs::write(options.output_dir.join(&manifest_file), &manifest_json)
+            .context(format!("Failed to write {}", manifest_file))?;
+
+        let output_file = format!("{}.{}", name, ext);
+        let output = options.output_dir.join(&output_file);
+        let mut result = FuzzResult {
+            name: name.clone(),
+            asset,
+            manifest: manifest_file,
+            output: output_file.clone(),
+            signed: false,
+            accepted: false,
+            validation_state: None,
+            failures: Vec::new(),
+            error: None,
+        };
+        match sign_entry(options, &manifest_json, mime, &output, data) {
+            Ok((state, failures)) => {
+                result.signed = true;
+                result.accepted = state != "Invalid" && failures.is_empty();
+                println!(
+                    "  {} {} ({})",
+                    if result.accepted { "✓" } else { "✗" },
+                    name,
+                    state
+                );
+                result.validation_state = Some(state);
+                result.failures = failures;
+                earlier.push(output_file);
+            }
+            Err(e) => {
+                println!("  ⚠ {}: not signed: {:#}", name, e);
+                result.error = Some(format!("{:#}", e));
+            }
+        }
+        results.push(result);
+    }
+
+    let signed = results.iter().filter(|r| r.signed).count();
+    let accepted = results.iter().filter(|r| r.accepted).count();
+    println!("\n=== Fuzz Summary ===");
+    println!("  Accepted: {}", accepted);
+    println!("  Rejected: {}", signed - accepted);
+    println!("  Not signed: {}", results.len() - signed);
+
+    let report_path = options.output_dir.join(REPORT_FILE);
+    let report = serde_json::to_string_pretty(&results).context("Failed to format report")?;
+    fs::write(&report_path, report + "\n").context("Failed to write fuzz report")?;
+    println!("  Report: {:?}", report_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_reproducible() {
+        let values = |seed, index| {
+            let mut rng = FuzzRng::new(seed, index);
+            (0..8).map(|_| rng.next_u64()).collect::<Vec<_>>()
+        };
+        assert_eq!(values("seed", 0), values("seed", 0));
+        assert_ne!(values("seed", 0), values("seed", 1));
+        assert_ne!(values("seed", 0), values("other", 0));
+    }
+
+    #[test]
+    fn test_same_seed_same_manifest() {
+        let earlier = vec!["fuzz-0000.jpg".to_string()];
+        for index in 0..20 {
+            let first = fuzz_manifest(&mut FuzzRng::new("seed", index), "m", &earlier);
+            let second = fuzz_manifest(&mut FuzzRng::new("seed", index), "m", &earlier);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn test_manifests_are_well_formed() {
+        let earlier = vec!["fuzz-0000.jpg".to_string(), "fuzz-0001.png".to_string()];
+        for index in 0..200 {
+            let manifest = fuzz_manifest(&mut FuzzRng::new("structure", index), "m", &earlier);
+            let actions = manifest["assertions"][0]["data"]["actions"]
+                .as_array()
+                .unwrap();
+            let first = actions[0]["action"].as_str().unwrap();
+            assert!(first == "c2pa.created" || first == "c2pa.opened");
+
+            // Every ingredient ID an action references is the label of an ingredient
+            let labels: Vec<&JsonValue> = ["ingredients", "ingredients_from_files"]
+                .iter()
+                .flat_map(|key| manifest[*key].as_array().unwrap())
+                .map(|ingredient| &ingredient["label"])
+                .collect();
+            for action in actions {
+                if let Some(ids) = action.pointer("/parameters/ingredientIds") {
+                    for id in ids.as_array().unwrap() {
+                        assert!(labels.contains(&id), "{} in {:#}", id, manifest);
+                    }
+                }
+            }
+            let parents = labels.len()
+                - manifest["ingredients"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .chain(manifest["ingredients_from_files"].as_array().unwrap())
+                    .filter(|i| i["relationship"] != "parentOf")
+                    .count();
+            assert!(parents <= 1);
+            assert_eq!(first == "c2pa.opened", parents == 1);
+        }
+    }
+}
@@ -0,0 +1,472 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! CAWG identity assertions (`cawg.identity`).
+//!
+//! Identity assertions are listed in the manifest JSON under `identity_assertions` and
+//! signed with a separate identity certificate and key, not the claim signer's. Each one
+//! binds its credential to the hashes of the referenced assertions (the hard binding is
+//! always included):
+//!
+//! - `x509`: the identity key signs the signer payload directly (`cawg.x509.cose`)
+//! - `identity_claims_aggregation`: the verified identities and the signer payload are
+//!   wrapped in a W3C verifiable credential issued by the identity key and signed as an
+//!   untagged COSE_Sign1 (`cawg.identity_claims_aggregation`)
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use c2pa::dynamic_assertion::DynamicAssertion;
+use c2pa::identity::builder::{CredentialHolder, IdentityAssertionBuilder, IdentityBuilderError};
+use c2pa::identity::x509::X509CredentialHolder;
+use c2pa::identity::SignerPayload;
+use c2pa::{Signer, SigningAlg};
+use ciborium::Value as CborValue;
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::deterministic::DETERMINISTIC_WHEN;
+
+/// Manifest JSON section listing the identity assertions to add
+const IDENTITY_ASSERTIONS_FIELD: &str = "identity_assertions";
+
+/// JSON-LD contexts of an identity claims aggregation credential
+const ICA_CONTEXTS: &[&str] = &[
+    "https://www.w3.org/ns/credentials/v2",
+    "https://cawg.io/identity/1.1/ica/context/",
+];
+
+/// Certificate and private key used to sign identity assertions
+#[derive(Clone, Copy, Debug)]
+pub struct IdentityKey<'a> {
+    pub cert: &'a Path,
+    pub key: &'a Path,
+}
+
+/// Kind of credential an identity assertion carries
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum CredentialType {
+    X509,
+    IdentityClaimsAggregation,
+}
+
+/// One entry of the manifest's `identity_assertions` section
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct IdentityAssertionDef {
+    #[serde(rename = "type")]
+    credential_type: CredentialType,
+    /// Labels of the assertions the identity is bound to, e.g. "c2pa.actions"
+    #[serde(default)]
+    referenced_assertions: Vec<String>,
+    /// Roles of the named actor, e.g. "cawg.creator"
+    #[serde(default)]
+    roles: Vec<String>,
+    /// Verified identities of an identity claims aggregation credential
+    #[serde(default)]
+    verified_identities: Vec<JsonValue>,
+    /// Credential issuer DID (defaults to a did:jwk of the identity key)
+    issuer: Option<String>,
+}
+
+/// Parse the identity assertion definitions of a manifest
+fn identity_definitions(manifest_json: &str) -> Result<Vec<IdentityAssertionDef>> {
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    match manifest.get(IDENTITY_ASSERTIONS_FIELD) {
+        Some(section) => serde_json::from_value(section.clone())
+            .context(format!("Invalid {} section", IDENTITY_ASSERTIONS_FIELD)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// `did:jwk` identifier for the public key of a PEM certificate (Ed25519 or P-256)
+fn did_jwk(cert_data: &[u8]) -> Result<String> {
+    use x509_parser::prelude::*;
+
+    let pem = ::pem::parse(cert_data)
+        .map_err(|e| anyhow::anyhow!("Failed to parse identity certificate PEM: {}", e))?;
+    let (_, cert) = X509Certificate::from_der(pem.contents())
+        .map_err(|e| anyhow::anyhow!("Failed to parse identity certificate: {}", e))?;
+    let public_key = cert.public_key();
+    let key_bytes = public_key.subject_public_key.data.as_ref();
+
+    let jwk = match public_key.algorithm.algorithm.to_id_string().as_str() {
+        "1.3.101.112" => json!({
+            "kty": "OKP",
+            "crv": "Ed25519",
+            "x": URL_SAFE_NO_PAD.encode(key_bytes),
+        }),
+        // Uncompressed P-256 point: 0x04 || x || y
+        "1.2.840.10045.2.1" if key_bytes.len() == 65 && key_bytes[0] == 0x04 => json!({
+            "kty": "EC",
+            "crv": "P-256",
+            "x": URL_SAFE_NO_PAD.encode(&key_bytes[1..33]),
+            "y": URL_SAFE_NO_PAD.encode(&key_bytes[33..]),
+        }),
+        _ => anyhow::bail!(
+            "Identity claims aggregation needs an Ed25519 or P-256 identity key \
+             (or an explicit issuer)"
+        ),
+    };
+
+    Ok(format!(
+        "did:jwk:{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&jwk)?)
+    ))
+}
+
+/// Encode a CBOR value
+fn cbor_bytes(value: &CborValue) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    ciborium::ser::into_writer(value, &mut out).context("Failed to encode CBOR")?;
+    Ok(out)
+}
+
+/// Sign a verifiable credential as an untagged COSE_Sign1 with content type application/vc
+fn cose_sign1(payload: &[u8], signing_alg: SigningAlg, key_data: &[u8]) -> Result<Vec<u8>> {
+    let cose_alg = match signing_alg {
+        SigningAlg::Ed25519 => -8,
+        SigningAlg::Es256 => -7,
+        other => anyhow::bail!(
+            "{:?} identity keys are not supported for identity claims aggregation \
+             (use Ed25519 or ES256)",
+            other
+        ),
+    };
+
+    let protected = cbor_bytes(&CborValue::Map(vec![
+        (
+            CborValue::Integer(1.into()),
+            CborValue::Integer(cose_alg.into()),
+        ),
+        (
+            CborValue::Integer(3.into()),
+            CborValue::Text("application/vc".to_string()),
+        ),
+    ]))?;
+
+    // Sig_structure for COSE_Sign1 with no external AAD
+    let to_be_signed = cbor_bytes(&CborValue::Array(vec![
+        CborValue::Text("Signature1".to_string()),
+        CborValue::Bytes(protected.clone()),
+        CborValue::Bytes(Vec::new()),
+        CborValue::Bytes(payload.to_vec()),
+    ]))?;
+    let signature = match signing_alg {
+        SigningAlg::Ed25519 => crate::ed25519_sign(&to_be_signed, key_data),
+        _ => crate::ecdsa_sign(&to_be_signed, key_data),
+    }
+    .context("Failed to sign identity credential")?;
+
+    cbor_bytes(&CborValue::Array(vec![
+        CborValue::Bytes(protected),
+        CborValue::Map(Vec::new()),
+        CborValue::Bytes(payload.to_vec()),
+        CborValue::Bytes(signature),
+    ]))
+}
+
+/// Credential holder for identity claims aggregation: the identity key acts as the
+/// aggregator that issues a credential over the verified identities
+struct IcaCredentialHolder {
+    issuer: String,
+    verified_identities: Vec<JsonValue>,
+    valid_from: String,
+    key_data: Vec<u8>,
+    signing_alg: SigningAlg,
+}
+
+impl IcaCredentialHolder {
+    /// The verifiable credential for a signer payload (given as JSON)
+    fn credential(&self, c2pa_asset: JsonValue) -> JsonValue {
+        // Identities without a verification time were verified when the credential was issued
+        let verified_identities: Vec<JsonValue> = self
+            .verified_identities
+            .iter()
+            .cloned()
+            .map(|mut identity| {
+                if let Some(fields) = identity.as_object_mut() {
+                    fields
+                        .entry("verifiedAt")
+                        .or_insert_with(|| self.valid_from.clone().into());
+                }
+                identity
+            })
+            .collect();
+
+        json!({
+            "@context": ICA_CONTEXTS,
+            "type": ["VerifiableCredential", "IdentityClaimsAggregationCredential"],
+            "issuer": self.issuer,
+            "validFrom": self.valid_from,
+            "credentialSubject": {
+                "verifiedIdentities": verified_identities,
+                "c2paAsset": c2pa_asset,
+            },
+        })
+    }
+}
+
+impl CredentialHolder for IcaCredentialHolder {
+    fn sig_type(&self) -> &'static str {
+        "cawg.identity_claims_aggregation"
+    }
+
+    fn reserve_size(&self) -> usize {
+        // Credential boilerplate, signer payload hashes and the COSE envelope
+        let identities = serde_json::to_string(&self.verified_identities).map_or(0, |s| s.len());
+        identities + self.issuer.len() + 8192
+    }
+
+    fn sign(&self, signer_payload: &SignerPayload) -> Result<Vec<u8>, IdentityBuilderError> {
+        let c2pa_asset = serde_json::to_value(signer_payload)
+            .map_err(|e| IdentityBuilderError::InternalError(e.to_string()))?;
+        let payload = serde_json::to_vec(&self.credential(c2pa_asset))
+            .map_err(|e| IdentityBuilderError::InternalError(e.to_string()))?;
+        cose_sign1(&payload, self.signing_alg, &self.key_data)
+            .map_err(|e| IdentityBuilderError::SignerError(format!("{:#}", e)))
+    }
+}
+
+/// Create the identity assertion builder for one definition
+fn identity_builder(
+    def: IdentityAssertionDef,
+    cert_data: &[u8],
+    key_data: &[u8],
+    signing_alg: SigningAlg,
+    valid_from: &str,
+) -> Result<IdentityAssertionBuilder> {
+    let mut builder = match def.credential_type {
+        CredentialType::X509 => {
+            if !def.verified_identities.is_empty() || def.issuer.is_some() {
+                anyhow::bail!(
+                    "verified_identities and issuer only apply to identity_claims_aggregation"
+                );
+            }
+            let raw_signer = c2pa::crypto::raw_signature::signer_from_cert_chain_and_private_key(
+                cert_data,
+                key_data,
+                signing_alg,
+                None,
+            )
+            .context("Failed to create X.509 identity signer")?;
+            IdentityAssertionBuilder::for_credential_holder(X509CredentialHolder::from_raw_signer(
+                raw_signer,
+            ))
+        }
+        CredentialType::IdentityClaimsAggregation => {
+            if def.verified_identities.is_empty() {
+                anyhow::bail!("identity_claims_aggregation needs at least one verified identity");
+            }
+            let issuer = match def.issuer {
+                Some(issuer) => issuer,
+                None => did_jwk(cert_data)?,
+            };
+            IdentityAssertionBuilder::for_credential_holder(IcaCredentialHolder {
+                issuer,
+                verified_identities: def.verified_identities,
+                valid_from: valid_from.to_string(),
+                key_data: key_data.to_vec(),
+                signing_alg,
+            })
+        }
+    };
+
+    let referenced: Vec<&str> = def
+        .referenced_assertions
+        .iter()
+        .map(String::as_str)
+        .collect();
+    builder.add_referenced_assertions(&referenced);
+    let roles: Vec<&str> = def.roles.iter().map(String::as_str).collect();
+    builder.add_roles(&roles);
+    Ok(builder)
+}
+
+/// Wrap a signer so it adds the identity assertions defined in the manifest JSON.
+/// The signer is returned unchanged when the manifest defines none.
+pub fn with_identity_assertions(
+    signer: Box<dyn Signer>,
+    manifest_json: &str,
+    identity: Option<IdentityKey>,
+    deterministic: bool,
+) -> Result<Box<dyn Signer>> {
+    let definitions = identity_definitions(manifest_json)?;
+    if definitions.is_empty() {
+        return Ok(signer);
+    }
+    let identity = identity.context(format!(
+        "{} in the manifest require --identity-cert and --identity-key",
+        IDENTITY_ASSERTIONS_FIELD
+    ))?;
+
+    let cert_data = fs::read(identity.cert).context("Failed to read identity certificate")?;
+    let key_data = fs::read(identity.key).context("Failed to read identity private key")?;
+    let signing_alg = crate::detect_signing_algorithm(identity.cert)?;
+    let valid_from = if deterministic {
+        DETERMINISTIC_WHEN.to_string()
+    } else {
+        crate::time::rfc3339(crate::time::unix_time_now()?)
+    };
+
+    let count = definitions.len();
+    let builders = definitions
+        .into_iter()
+        .enumerate()
+        .map(|(index, def)| {
+            identity_builder(def, &cert_data, &key_data, signing_alg, &valid_from)
+                .context(format!("Identity assertion {}", index + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    status!("  Adding {} identity assertion(s)", count);
+
+    Ok(Box::new(IdentitySigner {
+        inner: signer,
+        builders: Mutex::new(builders),
+    }))
+}
+
+/// Signer that adds identity assertions to the claim it signs
+struct IdentitySigner {
+    inner: Box<dyn Signer>,
+    builders: Mutex<Vec<IdentityAssertionBuilder>>,
+}
+
+impl Signer for IdentitySigner {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.inner.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.inner.alg()
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        self.inner.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        self.inner.reserve_size()
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.inner.time_authority_url()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        self.inner.ocsp_val()
+    }
+
+    fn dynamic_assertions(&self) -> Vec<Box<dyn DynamicAssertion>> {
+        // A signer is created for each asset, so the builders are handed over once
+        let builders = match self.builders.lock() {
+            Ok(mut builders) => std::mem::take(&mut *builders),
+            Err(_) => Vec::new(),
+        };
+        builders
+            .into_iter()
+            .map(|builder| Box::new(builder) as Box<dyn DynamicAssertion>)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_definitions() {
+        let manifest = r#"{
+            "title": "Identity",
+            "identity_assertions": [
+                { "type": "x509", "referenced_assertions": ["c2pa.actions"], "roles": ["cawg.creator"] },
+                { "type": "identity_claims_aggregation",
+                  "verified_identities": [{ "type": "cawg.social_media", "username": "jdoe" }] }
+            ]
+        }"#;
+        let defs = identity_definitions(manifest).unwrap();
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs[0].credential_type, CredentialType::X509);
+        assert_eq!(defs[0].roles, ["cawg.creator"]);
+        assert_eq!(
+            defs[1].credential_type,
+            CredentialType::IdentityClaimsAggregation
+        );
+
+        assert!(identity_definitions(r#"{"title": "None"}"#)
+            .unwrap()
+            .is_empty());
+        assert!(identity_definitions(r#"{"identity_assertions": [{"type": "oauth"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_did_jwk_for_ed25519_cert() {
+        let cert = fs::read(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/certs/ed25519.pub"),
+        )
+        .unwrap();
+        let did = did_jwk(&cert).unwrap();
+        let jwk: JsonValue = serde_json::from_slice(
+            &URL_SAFE_NO_PAD
+                .decode(did.strip_prefix("did:jwk:").unwrap())
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(jwk["kty"], "OKP");
+        assert_eq!(jwk["crv"], "Ed25519");
+        assert_eq!(
+            URL_SAFE_NO_PAD
+                .decode(jwk["x"].as_str().unwrap())
+                .unwrap()
+                .len(),
+            32
+        );
+    }
+
+    #[test]
+    fn test_ica_credential() {
+        let holder = IcaCredentialHolder {
+            issuer: "did:example:aggregator".to_string(),
+            verified_identities: vec![
+                json!({ "type": "cawg.social_media", "username": "jdoe" }),
+                json!({ "type": "cawg.affiliation", "verifiedAt": "2024-06-01T00:00:00Z" }),
+            ],
+            valid_from: crate::time::rfc3339(1_709_210_096),
+            key_data: Vec::new(),
+            signing_alg: SigningAlg::Ed25519,
+        };
+        let credential =
+            holder.credential(json!({ "sig_type": "cawg.identity_claims_aggregation" }));
+
+        assert_eq!(credential["@context"][1], ICA_CONTEXTS[1]);
+        assert_eq!(credential["issuer"], "did:example:aggregator");
+        assert_eq!(credential["validFrom"], "2024-02-29T12:34:56Z");
+        let subject = &credential["credentialSubject"];
+        assert_eq!(
+            subject["verifiedIdentities"][0]["verifiedAt"],
+            "2024-02-29T12:34:56Z"
+        );
+        assert_eq!(
+            subject["verifiedIdentities"][1]["verifiedAt"],
+            "2024-06-01T00:00:00Z"
+        );
+        assert_eq!(
+            subject["c2paAsset"]["sig_type"],
+            "cawg.identity_claims_aggregation"
+        );
+    }
+}
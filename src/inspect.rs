@@ -0,0 +1,1025 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Low-level dump of an embedded manifest store.
+//!
+//! Locates the JUMBF data in the container (JPEG APP11 segments, PNG `caBX` chunk,
+//! WebP `C2PA` chunk), then prints the JUMBF box tree with offsets and lengths, every
+//! CBOR box in diagnostic notation and the COSE_Sign1 structure of each claim signature.
+//! Nothing here goes through c2pa-rs, so the output shows what is actually in the file.
+
+use anyhow::{Context, Result};
+use ciborium::Value as CborValue;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Byte strings longer than this are abbreviated in diagnostic notation
+const MAX_BYTES_SHOWN: usize = 32;
+
+/// Single-line diagnostic notation is used for arrays and maps up to this length
+const MAX_INLINE_DIAG: usize = 80;
+
+/// JPEG APP11 marker
+const JPEG_APP11: u8 = 0xEB;
+
+/// Where a piece of the manifest store sits in the container
+struct Segment {
+    description: String,
+    offset: usize,
+    length: usize,
+}
+
+/// Manifest store located in a container
+struct LocatedStore {
+    container: &'static str,
+    segments: Vec<Segment>,
+    /// The JUMBF manifest store, reassembled if it spans several segments
+    store: Vec<u8>,
+    /// File offset of the store when it is stored contiguously
+    file_offset: Option<usize>,
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos + 4)
+        .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Option<u64> {
+    data.get(pos..pos + 8).map(|b| {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(b);
+        u64::from_be_bytes(bytes)
+    })
+}
+
+/// Find the manifest store in the JPEG APP11 segments
+///
+/// Each segment holds `CI ("JP") | En | Z | box data`. Segments after the first repeat
+/// the 8-byte LBox/TBox header of the superbox, which is dropped when reassembling.
+fn locate_in_jpeg(data: &[u8]) -> Option<LocatedStore> {
+    let mut segments = Vec::new();
+    let mut store = Vec::new();
+    let mut pos = 2;
+
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            break;
+        }
+        let marker = data[pos + 1];
+        // Fill bytes and markers without a length field
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+        if marker == 0x01 || (0xD0..=0xD8).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+        // Entropy-coded data follows the start of scan
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+
+        let length = read_u16(data, pos + 2)? as usize;
+        let payload_start = pos + 4;
+        let payload_end = (pos + 2 + length).min(data.len());
+
+        if marker == JPEG_APP11 && data.get(payload_start..payload_start + 2) == Some(b"JP") {
+            let instance = read_u16(data, payload_start + 2)?;
+            let sequence = read_u32(data, payload_start + 4)?;
+            let box_data = payload_start + 8;
+            let skip = if sequence > 1 { 8 } else { 0 };
+
+            if box_data + skip <= payload_end {
+                store.extend_from_slice(&data[box_data + skip..payload_end]);
+            }
+            segments.push(Segment {
+                description: format!("APP11 segment En={} Z={}", instance, sequence),
+                offset: pos,
+                length: length + 2,
+            });
+        }
+
+        pos += 2 + length;
+    }
+
+    if segments.is_empty() {
+        return None;
+    }
+    let file_offset = (segments.len() == 1).then(|| segments[0].offset + 12);
+    Some(LocatedStore {
+        container: "JPEG",
+        segments,
+        store,
+        file_offset,
+    })
+}
+
+/// Find the manifest store in the PNG `caBX` chunk
+fn locate_in_png(data: &[u8]) -> Option<LocatedStore> {
+    let mut pos = 8;
+    while pos + 12 <= data.len() {
+        let length = read_u32(data, pos)? as usize;
+        let chunk_type = &data[pos + 4..pos + 8];
+        if chunk_type == b"caBX" {
+            let start = pos + 8;
+            let end = (start + length).min(data.len());
+            return Some(LocatedStore {
+                container: "PNG",
+                segments: vec![Segment {
+                    description: "caBX chunk".to_string(),
+                    offset: pos,
+                    length: length + 12,
+                }],
+                store: data[start..end].to_vec(),
+                file_offset: Some(start),
+            });
+        }
+        if chunk_type == b"IEND" {
+            break;
+        }
+        pos += length + 12;
+    }
+    None
+}
+
+/// Find the manifest store in the WebP `C2PA` chunk
+fn locate_in_webp(data: &[u8]) -> Option<LocatedStore> {
+    let mut pos = 12;
+    while pos + 8 <= data.len() {
+        let chunk_type = &data[pos..pos + 4];
+        let length = u32::from_le_bytes(data[pos + 4..pos + 8].try_into().ok()?) as usize;
+        if chunk_type == b"C2PA" {
+            let start = pos + 8;
+            let end = (start + length).min(data.len());
+            return Some(LocatedStore {
+                container: "WebP",
+                segments: vec![Segment {
+                    description: "C2PA chunk".to_string(),
+                    offset: pos,
+                    length: length + 8,
+                }],
+                store: data[start..end].to_vec(),
+                file_offset: Some(start),
+            });
+        }
+        // Chunks are padded to an even size
+        pos += 8 + length + (length & 1);
+    }
+    None
+}
+
+/// Fall back to scanning for a `jumb` superbox whose first child is a `jumd` box
+fn locate_by_scan(data: &[u8]) -> Option<LocatedStore> {
+    let pos = data
+        .windows(12)
+        .position(|w| &w[0..4] == b"jumb" && &w[8..12] == b"jumd")?;
+    let start = pos.checked_sub(4)?;
+    let length = (read_u32(data, start)? as usize).min(data.len() - start);
+    Some(LocatedStore {
+        container: "unknown (found by scanning for a JUMBF superbox)",
+        segments: vec![Segment {
+            description: "JUMBF superbox".to_string(),
+            offset: start,
+            length,
+        }],
+        store: data[start..start + length].to_vec(),
+        file_offset: Some(start),
+    })
+}
+
+fn locate_store(data: &[u8]) -> Option<LocatedStore> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        if let Some(found) = locate_in_jpeg(data) {
+            return Some(found);
+        }
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        if let Some(found) = locate_in_png(data) {
+            return Some(found);
+        }
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        if let Some(found) = locate_in_webp(data) {
+            return Some(found);
+        }
+    }
+    locate_by_scan(data)
+}
+
+/// Header of a JUMBF (ISO BMFF style) box
+struct BoxHeader {
+    box_type: [u8; 4],
+    header_len: usize,
+    length: usize,
+}
+
+fn parse_box_header(data: &[u8], pos: usize, end: usize) -> std::result::Result<BoxHeader, String> {
+    let lbox = read_u32(data, pos).ok_or("truncated box header")? as usize;
+    let box_type: [u8; 4] = data
+        .get(pos + 4..pos + 8)
+        .ok_or("truncated box header")?
+        .try_into()
+        .map_err(|_| "truncated box header")?;
+
+    let available = end.saturating_sub(pos);
+    let (header_len, length) = match lbox {
+        0 => (8, available),
+        1 => {
+            let xlbox = read_u64(data, pos + 8).ok_or("truncated XLBox")?;
+            (16, usize::try_from(xlbox).unwrap_or(usize::MAX))
+        }
+        n if n < 8 => return Err(format!("invalid box length {}", n)),
+        n => (8, n),
+    };
+    // Compared against what is left rather than `pos + length`, which a huge XLBox overflows
+    if length < header_len || length > available {
+        return Err(format!(
+            "box length {} exceeds the {} byte(s) left in its parent",
+            length, available
+        ));
+    }
+
+    Ok(BoxHeader {
+        box_type,
+        header_len,
+        length,
+    })
+}
+
+/// JUMBF description box (`jumd`) contents
+struct Description {
+    box_len: usize,
+    type_uuid: [u8; 16],
+    toggles: u8,
+    label: Option<String>,
+    id: Option<u32>,
+}
+
+fn parse_description(data: &[u8], pos: usize, end: usize) -> Option<Description> {
+    let header = parse_box_header(data, pos, end).ok()?;
+    if &header.box_type != b"jumd" {
+        return None;
+    }
+    let content_end = pos + header.length;
+    let mut cursor = pos + header.header_len;
+
+    let type_uuid: [u8; 16] = data.get(cursor..cursor + 16)?.try_into().ok()?;
+    cursor += 16;
+    let toggles = *data.get(cursor)?;
+    cursor += 1;
+
+    let mut label = None;
+    if toggles & 0x02 != 0 {
+        let rest = data.get(cursor..content_end)?;
+        let nul = rest.iter().position(|&b| b == 0).unwrap_or(rest.len());
+        label = Some(String::from_utf8_lossy(&rest[..nul]).into_owned());
+        cursor += nul + 1;
+    }
+    let id = if toggles & 0x04 != 0 {
+        read_u32(data, cursor)
+    } else {
+        None
+    };
+
+    Some(Description {
+        box_len: header.length,
+        type_uuid,
+        toggles,
+        label,
+        id,
+    })
+}
+
+/// Short name of a JUMBF content type: the 4-character code C2PA types start with
+fn type_name(uuid: &[u8; 16]) -> String {
+    if uuid[..4].iter().all(|b| b.is_ascii_alphanumeric()) {
+        String::from_utf8_lossy(&uuid[..4]).into_owned()
+    } else {
+        format_uuid(uuid)
+    }
+}
+
+fn format_uuid(uuid: &[u8]) -> String {
+    let hex = hex_string(uuid);
+    if hex.len() != 32 {
+        return hex;
+    }
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Format a box position, with the file offset when the store is contiguous in the file
+fn location(pos: usize, length: usize, file_offset: Option<usize>) -> String {
+    match file_offset {
+        Some(base) => format!("@{} (file @{}) len {}", pos, base + pos, length),
+        None => format!("@{} len {}", pos, length),
+    }
+}
+
+/// Append a multi-line block with every line indented
+fn write_block(out: &mut String, indent: &str, block: &str) {
+    for line in block.lines() {
+        let _ = writeln!(out, "{}{}", indent, line);
+    }
+}
+
+/// CBOR byte string in diagnostic notation, abbreviated when long
+fn bytes_diag(bytes: &[u8]) -> String {
+    if bytes.len() <= MAX_BYTES_SHOWN {
+        format!("h'{}'", hex_string(bytes))
+    } else {
+        format!(
+            "h'{}...' / {} bytes /",
+            hex_string(&bytes[..MAX_BYTES_SHOWN]),
+            bytes.len()
+        )
+    }
+}
+
+fn compound_diag(open: char, close: char, parts: Vec<String>, depth: usize) -> String {
+    if parts.is_empty() {
+        return format!("{}{}", open, close);
+    }
+    let inline = format!("{}{}{}", open, parts.join(", "), close);
+    if inline.len() <= MAX_INLINE_DIAG && !inline.contains('\n') {
+        return inline;
+    }
+
+    let indent = "  ".repeat(depth + 1);
+    let body: Vec<String> = parts
+        .iter()
+        .map(|part| format!("{}{}", indent, part))
+        .collect();
+    format!(
+        "{}\n{}\n{}{}",
+        open,
+        body.join(",\n"),
+        "  ".repeat(depth),
+        close
+    )
+}
+
+/// CBOR diagnostic notation (RFC 8949 section 8) of a decoded value
+fn cbor_diag(value: &CborValue, depth: usize) -> String {
+    match value {
+        CborValue::Integer(i) => i128::from(*i).to_string(),
+        CborValue::Bytes(bytes) => bytes_diag(bytes),
+        CborValue::Float(f) => format!("{:?}", f),
+        CborValue::Text(text) => serde_json::to_string(text).unwrap_or_default(),
+        CborValue::Bool(b) => b.to_string(),
+        CborValue::Null => "null".to_string(),
+        CborValue::Tag(tag, inner) => format!("{}({})", tag, cbor_diag(inner, depth)),
+        CborValue::Array(items) => compound_diag(
+            '[',
+            ']',
+            items
+                .iter()
+                .map(|item| cbor_diag(item, depth + 1))
+                .collect(),
+            depth,
+        ),
+        CborValue::Map(entries) => compound_diag(
+            '{',
+            '}',
+            entries
+                .iter()
+                .map(|(k, v)| format!("{}: {}", cbor_diag(k, depth + 1), cbor_diag(v, depth + 1)))
+                .collect(),
+            depth,
+        ),
+        _ => "undefined".to_string(),
+    }
+}
+
+fn decode_cbor(bytes: &[u8]) -> std::result::Result<CborValue, String> {
+    ciborium::de::from_reader(bytes).map_err(|e| e.to_string())
+}
+
+/// Name of a COSE algorithm identifier
+fn cose_alg_name(alg: i128) -> &'static str {
+    match alg {
+        -7 => "ES256",
+        -35 => "ES384",
+        -36 => "ES512",
+        -37 => "PS256",
+        -38 => "PS384",
+        -39 => "PS512",
+        -8 => "EdDSA",
+        _ => "unknown",
+    }
+}
+
+/// Name of a COSE header label
+fn header_name(label: &CborValue) -> String {
+    match label {
+        CborValue::Integer(i) => match i128::from(*i) {
+            1 => "alg (1)".to_string(),
+            3 => "content type (3)".to_string(),
+            4 => "kid (4)".to_string(),
+            33 => "x5chain (33)".to_string(),
+            n => n.to_string(),
+        },
+        CborValue::Text(text) => text.clone(),
+        other => cbor_diag(other, 0),
+    }
+}
+
+/// Certificates in an x5chain header (a single bstr or an array of them)
+fn x5chain_certs(value: &CborValue) -> Vec<&[u8]> {
+    match value {
+        CborValue::Bytes(der) => vec![der.as_slice()],
+        CborValue::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                CborValue::Bytes(der) => Some(der.as_slice()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn describe_certificate(der: &[u8]) -> String {
+    match x509_parser::parse_x509_certificate(der) {
+        Ok((_, cert)) => format!(
+            "subject: {}\nissuer: {}\nserial: {}\nvalidity: {} .. {}\n({} bytes)",
+            cert.subject(),
+            cert.issuer(),
+            cert.raw_serial_as_string(),
+            cert.validity().not_before,
+            cert.validity().not_after,
+            der.len()
+        ),
+        Err(e) => format!("unparseable certificate ({} bytes): {}", der.len(), e),
+    }
+}
+
+/// First GeneralizedTime in a DER structure; for an RFC 3161 token this is the genTime
+fn find_generalized_time(der: &[u8]) -> Option<String> {
+    der.windows(2).enumerate().find_map(|(i, w)| {
+        let len = w[1] as usize;
+        if w[0] != 0x18 || !(15..=23).contains(&len) {
+            return None;
+        }
+        let text = der.get(i + 2..i + 2 + len)?;
+        let valid = text.ends_with(b"Z")
+            && text[..14].iter().all(|b| b.is_ascii_digit())
+            && text.iter().all(|b| b.is_ascii_graphic());
+        valid.then(|| String::from_utf8_lossy(text).into_owned())
+    })
+}
+
+/// Describe the `sigTst`/`sigTst2` timestamp header: `{ "tstTokens": [{ "val": bstr }] }`
+fn describe_timestamps(value: &CborValue) -> String {
+    let tokens: Vec<&[u8]> = match value {
+        CborValue::Map(entries) => entries
+            .iter()
+            .filter(|(k, _)| matches!(k, CborValue::Text(t) if t == "tstTokens"))
+            .filter_map(|(_, v)| match v {
+                CborValue::Array(items) => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|token| match token {
+                CborValue::Map(fields) => fields.iter().find_map(|(k, v)| match (k, v) {
+                    (CborValue::Text(key), CborValue::Bytes(val)) if key == "val" => {
+                        Some(val.as_slice())
+                    }
+                    _ => None,
+                }),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut out = format!("{} token(s)", tokens.len());
+    for (i, token) in tokens.iter().enumerate() {
+        let _ = write!(out, "\n[{}] {} bytes", i, token.len());
+        if let Some(time) = find_generalized_time(token) {
+            let _ = write!(out, ", genTime {}", time);
+        }
+    }
+    out
+}
+
+fn describe_header(label: &CborValue, value: &CborValue) -> String {
+    let name = header_name(label);
+    let details = match (&name[..], value) {
+        ("alg (1)", CborValue::Integer(i)) => {
+            let alg = i128::from(*i);
+            format!("{} ({})", alg, cose_alg_name(alg))
+        }
+        ("x5chain (33)", _) | ("x5chain", _) => {
+            let certs = x5chain_certs(value);
+            let mut text = format!("{} certificate(s)", certs.len());
+            for (i, der) in certs.iter().enumerate() {
+                let _ = write!(text, "\n[{}]", i);
+                for line in describe_certificate(der).lines() {
+                    let _ = write!(text, "\n    {}", line);
+                }
+            }
+            text
+        }
+        ("sigTst", _) | ("sigTst2", _) => describe_timestamps(value),
+        ("pad", CborValue::Bytes(bytes)) => format!("{} byte(s) of padding", bytes.len()),
+        _ => cbor_diag(value, 0),
+    };
+    format!("{}: {}", name, details)
+}
+
+fn write_headers(out: &mut String, indent: &str, headers: &CborValue) {
+    match headers {
+        CborValue::Map(entries) if !entries.is_empty() => {
+            for (label, value) in entries {
+                write_block(out, indent, &describe_header(label, value));
+            }
+        }
+        CborValue::Map(_) => {
+            let _ = writeln!(out, "{}(empty)", indent);
+        }
+        other => write_block(out, indent, &cbor_diag(other, 0)),
+    }
+}
+
+/// Print the COSE_Sign1 structure: [protected bstr, unprotected map, payload, signature]
+fn write_cose_sign1(out: &mut String, indent: &str, value: &CborValue) {
+    let (tagged, inner) = match value {
+        CborValue::Tag(18, inner) => (true, inner.as_ref()),
+        other => (false, other),
+    };
+    let items = match inner {
+        CborValue::Array(items) if items.len() == 4 => items,
+        other => {
+            let _ = writeln!(out, "{}✗ Not a COSE_Sign1 structure:", indent);
+            write_block(out, indent, &cbor_diag(other, 0));
+            return;
+        }
+    };
+
+    let _ = writeln!(
+        out,
+        "{}COSE_Sign1{}",
+        indent,
+        if tagged { " (tag 18)" } else { " (untagged)" }
+    );
+    let nested = format!("{}  ", indent);
+    let headers_indent = format!("{}    ", indent);
+
+    match &items[0] {
+        CborValue::Bytes(protected) => {
+            let _ = writeln!(
+                out,
+                "{}Protected headers ({} bytes):",
+                nested,
+                protected.len()
+            );
+            match decode_cbor(protected) {
+                Ok(headers) => write_headers(out, &headers_indent, &headers),
+                Err(e) => {
+                    let _ = writeln!(out, "{}✗ Failed to decode: {}", headers_indent, e);
+                }
+            }
+        }
+        other => {
+            let _ = writeln!(
+                out,
+                "{}✗ Protected headers are not a byte string: {}",
+                nested,
+                cbor_diag(other, 0)
+            );
+        }
+    }
+
+    let _ = writeln!(out, "{}Unprotected headers:", nested);
+    write_headers(out, &headers_indent, &items[1]);
+
+    let payload = match &items[2] {
+        CborValue::Null => "detached (null)".to_string(),
+        other => cbor_diag(other, 0),
+    };
+    let _ = writeln!(out, "{}Payload: {}", nested, payload);
+
+    match &items[3] {
+        CborValue::Bytes(signature) => {
+            let _ = writeln!(
+                out,
+                "{}Signature: {} bytes {}",
+                nested,
+                signature.len(),
+                bytes_diag(signature)
+            );
+        }
+        other => {
+            let _ = writeln!(out, "{}Signature: {}", nested, cbor_diag(other, 0));
+        }
+    }
+}
+
+/// Recursively print the JUMBF boxes between `start` and `end`
+fn write_boxes(
+    out: &mut String,
+    data: &[u8],
+    start: usize,
+    end: usize,
+    depth: usize,
+    file_offset: Option<usize>,
+    in_signature: bool,
+) {
+    let indent = "  ".repeat(depth + 1);
+    let mut pos = start;
+
+    while pos < end {
+        let header = match parse_box_header(data, pos, end) {
+            Ok(header) => header,
+            Err(e) => {
+                let _ = writeln!(out, "{}✗ Malformed box at @{}: {}", indent, pos, e);
+                return;
+            }
+        };
+        let content_start = pos + header.header_len;
+        let content_end = pos + header.length;
+        let box_type = String::from_utf8_lossy(&header.box_type).into_owned();
+        let loc = location(pos, header.length, file_offset);
+
+        match &header.box_type {
+            b"jumb" => {
+                let description = parse_description(data, content_start, content_end);
+                let label = description
+                    .as_ref()
+                    .and_then(|d| d.label.clone())
+                    .unwrap_or_default();
+                let kind = description
+                    .as_ref()
+                    .map(|d| type_name(&d.type_uuid))
+                    .unwrap_or_else(|| "?".to_string());
+                let _ = writeln!(out, "{}jumb {} [{}] type {}", indent, loc, label, kind);
+
+                let children_start = match &description {
+                    Some(d) => {
+                        let mut details = format!(
+                            "{}  jumd {} uuid {} toggles 0x{:02x}",
+                            indent,
+                            location(content_start, d.box_len, file_offset),
+                            format_uuid(&d.type_uuid),
+                            d.toggles
+                        );
+                        if let Some(id) = d.id {
+                            let _ = write!(details, " id {}", id);
+                        }
+                        let _ = writeln!(out, "{}", details);
+                        content_start + d.box_len
+                    }
+                    None => {
+                        let _ = writeln!(out, "{}  ✗ Missing description box", indent);
+                        content_start
+                    }
+                };
+
+                let in_signature = in_signature || label == "c2pa.signature";
+                write_boxes(
+                    out,
+                    data,
+                    children_start,
+                    content_end,
+                    depth + 1,
+                    file_offset,
+                    in_signature,
+                );
+            }
+            b"cbor" => {
+                let _ = writeln!(out, "{}cbor {}", indent, loc);
+                let block_indent = format!("{}  ", indent);
+                match decode_cbor(&data[content_start..content_end]) {
+                    Ok(value) if in_signature => write_cose_sign1(out, &block_indent, &value),
+                    Ok(value) => write_block(out, &block_indent, &cbor_diag(&value, 0)),
+                    Err(e) => {
+                        let _ = writeln!(out, "{}✗ Invalid CBOR: {}", block_indent, e);
+                    }
+                }
+            }
+            b"json" => {
+                let _ = writeln!(out, "{}json {}", indent, loc);
+                let text = String::from_utf8_lossy(&data[content_start..content_end]);
+                let pretty = serde_json::from_str::<serde_json::Value>(&text)
+                    .ok()
+                    .and_then(|v| serde_json::to_string_pretty(&v).ok())
+                    .unwrap_or_else(|| text.into_owned());
+                write_block(out, &format!("{}  ", indent), &pretty);
+            }
+            b"bfdb" => {
+                // Toggles, then the NUL-terminated media type and optional file name
+                let content = &data[content_start..content_end];
+                let fields: Vec<String> = content
+                    .get(1..)
+                    .unwrap_or_default()
+                    .split(|&b| b == 0)
+                    .filter(|s| !s.is_empty())
+                    .map(|s| String::from_utf8_lossy(s).into_owned())
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "{}bfdb {} embedded file: {}",
+                    indent,
+                    loc,
+                    fields.join(", ")
+                );
+            }
+            b"uuid" => {
+                let uuid = data
+                    .get(content_start..content_start + 16)
+                    .map(format_uuid)
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "{}uuid {} {} ({} data bytes)",
+                    indent,
+                    loc,
+                    uuid,
+                    (content_end - content_start).saturating_sub(16)
+                );
+            }
+            _ => {
+                let _ = writeln!(
+                    out,
+                    "{}{} {} ({} content bytes)",
+                    indent,
+                    box_type,
+                    loc,
+                    content_end - content_start
+                );
+            }
+        }
+
+        pos = content_end;
+    }
+}
+
+/// Build the low-level dump of the manifest store embedded in `data`
+fn build_dump(data: &[u8]) -> Result<String> {
+    let located = locate_store(data).context("No JUMBF manifest store found in the file")?;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "Container: {}", located.container);
+    for segment in &located.segments {
+        let _ = writeln!(
+            out,
+            "  {} @{} len {}",
+            segment.description, segment.offset, segment.length
+        );
+    }
+    let _ = writeln!(out, "Manifest store: {} bytes", located.store.len());
+    if located.file_offset.is_none() {
+        let _ = writeln!(
+            out,
+            "  Offsets are relative to the reassembled manifest store"
+        );
+    }
+
+    let _ = writeln!(out, "JUMBF boxes:");
+    write_boxes(
+        &mut out,
+        &located.store,
+        0,
+        located.store.len(),
+        0,
+        located.file_offset,
+        false,
+    );
+    Ok(out)
+}
+
+/// Dump the manifest store of a file; written to `output_path` if given, else printed
+pub fn inspect_file(input_path: &Path, output_path: Option<&Path>) -> Result<()> {
+    println!("=== Inspecting {:?} ===", input_path);
+    let data = fs::read(input_path).context(format!("Failed to read file: {:?}", input_path))?;
+    let dump = build_dump(&data)?;
+
+    match output_path {
+        Some(path) => {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).context("Failed to create output directory")?;
+            }
+            fs::write(path, &dump).context(format!("Failed to write dump: {:?}", path))?;
+            println!("✓ Dump written to {:?}", path);
+        }
+        None => print!("{}", dump),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a box from its type and content
+    fn make_box(box_type: &[u8; 4], content: &[u8]) -> Vec<u8> {
+        let mut out = ((content.len() + 8) as u32).to_be_bytes().to_vec();
+        out.extend_from_slice(box_type);
+        out.extend_from_slice(content);
+        out
+    }
+
+    /// Build a labeled JUMBF superbox with the given type code and children
+    fn make_superbox(type_code: &[u8; 4], label: &str, children: &[Vec<u8>]) -> Vec<u8> {
+        let mut description = type_code.to_vec();
+        description.extend_from_slice(&[0x00, 0x11, 0x00, 0x10, 0x80, 0x00]);
+        description.extend_from_slice(&[0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71]);
+        description.push(0x03);
+        description.extend_from_slice(label.as_bytes());
+        description.push(0);
+
+        let mut content = make_box(b"jumd", &description);
+        for child in children {
+            content.extend_from_slice(child);
+        }
+        make_box(b"jumb", &content)
+    }
+
+    fn cbor_bytes(value: &CborValue) -> Vec<u8> {
+        let mut out = Vec::new();
+        ciborium::ser::into_writer(value, &mut out).unwrap();
+        out
+    }
+
+    fn sample_store() -> Vec<u8> {
+        let assertion = CborValue::Map(vec![(
+            CborValue::Text("actions".into()),
+            CborValue::Array(vec![CborValue::Map(vec![(
+                CborValue::Text("action".into()),
+                CborValue::Text("c2pa.created".into()),
+            )])]),
+        )]);
+        let protected = cbor_bytes(&CborValue::Map(vec![(
+            CborValue::Integer(1.into()),
+            CborValue::Integer((-8).into()),
+        )]));
+        let signature = CborValue::Tag(
+            18,
+            Box::new(CborValue::Array(vec![
+                CborValue::Bytes(protected),
+                CborValue::Map(vec![]),
+                CborValue::Null,
+                CborValue::Bytes(vec![0xAB; 64]),
+            ])),
+        );
+
+        let assertions = make_superbox(
+            b"c2as",
+            "c2pa.assertions",
+            &[make_superbox(
+                b"cbor",
+                "c2pa.actions.v2",
+                &[make_box(b"cbor", &cbor_bytes(&assertion))],
+            )],
+        );
+        let signature = make_superbox(
+            b"c2cs",
+            "c2pa.signature",
+            &[make_box(b"cbor", &cbor_bytes(&signature))],
+        );
+        let manifest = make_superbox(b"c2ma", "urn:c2pa:test", &[assertions, signature]);
+        make_superbox(b"c2pa", "c2pa", &[manifest])
+    }
+
+    /// Split a store over JPEG APP11 segments the way c2pa-rs does
+    fn make_jpeg(store: &[u8], max_segment: usize) -> Vec<u8> {
+        let mut jpeg = vec![0xFF, 0xD8];
+        let header = &store[..8];
+        for (i, chunk) in store.chunks(max_segment).enumerate() {
+            let mut payload = b"JP".to_vec();
+            payload.extend_from_slice(&1u16.to_be_bytes());
+            payload.extend_from_slice(&((i + 1) as u32).to_be_bytes());
+            if i > 0 {
+                payload.extend_from_slice(header);
+            }
+            payload.extend_from_slice(chunk);
+
+            jpeg.extend_from_slice(&[0xFF, JPEG_APP11]);
+            jpeg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+            jpeg.extend_from_slice(&payload);
+        }
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x02, 0xFF, 0xD9]);
+        jpeg
+    }
+
+    #[test]
+    fn test_jpeg_segments_are_reassembled() {
+        let store = sample_store();
+        let jpeg = make_jpeg(&store, 100);
+
+        let located = locate_store(&jpeg).expect("store should be found");
+        assert_eq!(located.container, "JPEG");
+        assert!(located.segments.len() > 1);
+        assert!(located.file_offset.is_none());
+        assert_eq!(located.store, store);
+    }
+
+    #[test]
+    fn test_dump_shows_tree_cbor_and_cose() {
+        let dump = build_dump(&make_jpeg(&sample_store(), 4096)).unwrap();
+
+        assert!(dump.contains("Container: JPEG"));
+        assert!(dump.contains("APP11 segment En=1 Z=1 @2"));
+        assert!(dump.contains("jumb @0 (file @14) len"));
+        assert!(dump.contains("[urn:c2pa:test] type c2ma"));
+        assert!(dump.contains("[c2pa.actions.v2] type cbor"));
+        assert!(dump.contains(r#"{"actions": [{"action": "c2pa.created"}]}"#));
+        assert!(dump.contains("COSE_Sign1 (tag 18)"));
+        assert!(dump.contains("alg (1): -8 (EdDSA)"));
+        assert!(dump.contains("Payload: detached (null)"));
+        assert!(dump.contains("Signature: 64 bytes"));
+    }
+
+    #[test]
+    fn test_png_cabx_chunk() {
+        let store = sample_store();
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&(store.len() as u32).to_be_bytes());
+        png.extend_from_slice(b"caBX");
+        png.extend_from_slice(&store);
+        png.extend_from_slice(&[0; 4]);
+
+        let located = locate_store(&png).expect("store should be found");
+        assert_eq!(located.container, "PNG");
+        assert_eq!(located.file_offset, Some(16));
+        assert_eq!(located.store, store);
+    }
+
+    #[test]
+    fn test_malformed_box_is_reported() {
+        let mut store = sample_store();
+        // Claim a length larger than the data
+        store[3] = store[3].wrapping_add(50);
+        let mut out = String::new();
+        write_boxes(&mut out, &store, 0, store.len(), 0, None, false);
+        assert!(out.contains("✗ Malformed box at @0"));
+    }
+
+    #[test]
+    fn test_huge_xlbox_is_reported() {
+        // LBox 1 means a 64-bit XLBox follows; one near u64::MAX must not overflow
+        let mut data = 1u32.to_be_bytes().to_vec();
+        data.extend_from_slice(b"jumb");
+        data.extend_from_slice(&(u64::MAX - 4).to_be_bytes());
+        data.extend_from_slice(&[0; 16]);
+        for pos in [0, 4] {
+            let mut shifted = vec![0; pos];
+            shifted.extend_from_slice(&data);
+            let error = parse_box_header(&shifted, pos, shifted.len())
+                .err()
+                .expect("huge XLBox should be rejected");
+            assert!(error.contains("exceeds"), "{}", error);
+        }
+
+        let mut out = String::new();
+        write_boxes(&mut out, &data, 0, data.len(), 0, None, false);
+        assert!(out.contains("✗ Malformed box at @0"));
+    }
+
+    #[test]
+    fn test_cbor_diag_notation() {
+        let value = CborValue::Map(vec![
+            (
+                CborValue::Text("alg".into()),
+                CborValue::Text("sha256".into()),
+            ),
+            (
+                CborValue::Text("hash".into()),
+                CborValue::Bytes(vec![0x01, 0x02]),
+            ),
+            (CborValue::Text("pad".into()), CborValue::Bytes(vec![0; 40])),
+            (CborValue::Integer((-1).into()), CborValue::Bool(true)),
+        ]);
+        let diag = cbor_diag(&value, 0);
+
+        assert!(diag.contains(r#""alg": "sha256""#));
+        assert!(diag.contains("\"hash\": h'0102'"));
+        assert!(diag.contains("/ 40 bytes /"));
+        assert!(diag.contains("-1: true"));
+    }
+}
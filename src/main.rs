@@ -19,8 +19,53 @@ use clap::Parser;
 use glob::glob;
 use serde_json::Value as JsonValue;
 use std::fs;
-use std::io::{BufReader, Cursor};
+use std::io::{BufReader, Cursor, Read, Seek, Write};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Path that stands for stdin (as input) or stdout (as output)
+const STDIO_PATH: &str = "-";
+
+/// Set while stdout carries asset or manifest data, so progress messages go to stderr
+static STATUS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Print a progress message to stdout, or to stderr while stdout carries data
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::STATUS_TO_STDERR.load(::std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+mod assertion_files;
+mod binding;
+mod deterministic;
+mod diff;
+mod edits;
+mod existing;
+mod fuzz;
+mod identity;
+mod inspect;
+mod matrix;
+mod mutate;
+mod normalize;
+mod ocsp;
+mod pkcs11;
+mod references;
+mod regions;
+mod regress;
+mod resources;
+mod serve;
+mod stress;
+mod summary;
+mod testplan;
+mod time;
+mod trust;
+
+use deterministic::DeterministicIds;
 
 /// C2PA Testfile Maker - Create and embed C2PA manifests into media assets
 #[derive(Parser, Debug)]
@@ -30,22 +75,77 @@ struct Cli {
     #[arg(short, long, value_name = "FILE")]
     manifest: Option<PathBuf>,
 
-    /// Path(s) to input media asset(s) (JPEG, PNG, etc.). Supports glob patterns (e.g., "*.jpg", "images/*.png")
-    #[arg(value_name = "INPUT_FILE", required = true, num_args = 1..)]
+    /// Path(s) to input media asset(s) (JPEG, PNG, etc.). Supports glob patterns (e.g., "*.jpg", "images/*.png").
+    /// Use "-" to read a single asset from stdin (requires --format)
+    #[arg(value_name = "INPUT_FILE", required_unless_present_any = ["serve", "matrix", "plan", "fuzz", "stress"], num_args = 1..)]
     input: Vec<String>,
 
-    /// Path to the output file or directory (not required in validate mode)
+    /// Path to the output file or directory (not required in validate mode).
+    /// Use "-" to write the signed asset or extracted JSON to stdout
     #[arg(short, long, value_name = "PATH")]
     output: Option<PathBuf>,
 
+    /// Asset format (extension such as "jpg" or a MIME type) when reading from stdin or
+    /// writing to stdout, where there is no file extension to detect it from
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<String>,
+
     /// Path to the certificate file (PEM format, not required in extract mode)
     #[arg(short, long, value_name = "FILE")]
     cert: Option<PathBuf>,
 
     /// Path to the private key file (PEM format, not required in extract mode)
-    #[arg(short, long, value_name = "FILE")]
+    #[arg(short, long, value_name = "FILE", conflicts_with = "signer_cmd")]
     key: Option<PathBuf>,
 
+    /// External signer used instead of --key: the program (with any arguments, separated
+    /// by whitespace) receives the to-be-signed bytes on stdin and writes the raw signature
+    /// to stdout. --cert still provides the certificate chain
+    #[arg(long, value_name = "PROGRAM")]
+    signer_cmd: Option<String>,
+
+    /// PKCS#11 module (e.g. libsofthsm2.so) holding the signing key, used instead of --key.
+    /// The token PIN is read from the C2PA_PKCS11_PIN environment variable
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["key", "signer_cmd"])]
+    pkcs11_module: Option<PathBuf>,
+
+    /// PKCS#11 slot ID (defaults to the first slot with a token)
+    #[arg(long, value_name = "ID", requires = "pkcs11_module")]
+    pkcs11_slot: Option<u64>,
+
+    /// Label (CKA_LABEL) of the private key in the PKCS#11 token
+    #[arg(long, value_name = "LABEL", requires = "pkcs11_module")]
+    key_label: Option<String>,
+
+    /// Staple an OCSP response for the signer certificate fetched from this responder URL
+    /// (the --cert chain must include the issuer certificate)
+    #[arg(long, value_name = "URL", conflicts_with = "ocsp_status")]
+    ocsp_url: Option<String>,
+
+    /// Staple an OCSP response generated by the local responder stand-in, reporting the
+    /// signer certificate as good, revoked or unknown (requires --ocsp-responder-cert and
+    /// --ocsp-responder-key)
+    #[arg(long, value_name = "STATUS", value_enum, requires_all = ["ocsp_responder_cert", "ocsp_responder_key"])]
+    ocsp_status: Option<ocsp::CertStatus>,
+
+    /// Certificate (PEM, optionally followed by its chain) of the CA or delegated OCSP
+    /// responder that signs locally generated OCSP responses
+    #[arg(long, value_name = "FILE", requires = "ocsp_status")]
+    ocsp_responder_cert: Option<PathBuf>,
+
+    /// Private key (PEM) of the local OCSP responder
+    #[arg(long, value_name = "FILE", requires = "ocsp_status")]
+    ocsp_responder_key: Option<PathBuf>,
+
+    /// Certificate (PEM) of the identity that signs the CAWG identity assertions listed under
+    /// `identity_assertions` in the manifest JSON (requires --identity-key)
+    #[arg(long, value_name = "FILE", requires = "identity_key")]
+    identity_cert: Option<PathBuf>,
+
+    /// Private key (PEM) for --identity-cert
+    #[arg(long, value_name = "FILE", requires = "identity_cert")]
+    identity_key: Option<PathBuf>,
+
     /// Signing algorithm (es256, es384, es512, ps256, ps384, ps512, ed25519)
     /// If not specified, will be auto-detected from the certificate
     #[arg(short, long)]
@@ -59,10 +159,20 @@ struct Cli {
     #[arg(short, long, default_value = "false")]
     extract: bool,
 
-    /// Use JPEG Trust format for extraction (only valid with --extract)
+    /// Use JPEG Trust format for extraction (only valid with --extract or --diff)
     #[arg(long, default_value = "false")]
     jpt: bool,
 
+    /// Also write the human-readable validation summary next to each extracted JSON file
+    /// as <name>_summary.txt (only valid with --extract)
+    #[arg(long, default_value = "false")]
+    summary: bool,
+
+    /// Write the thumbnails, icons and data boxes referenced by each extracted manifest
+    /// store into this directory and point the JSON at them (only valid with --extract)
+    #[arg(long, value_name = "DIR")]
+    resources: Option<PathBuf>,
+
     /// Base directory for resolving relative ingredient file paths (defaults to manifest directory)
     #[arg(long, value_name = "DIR")]
     ingredients_dir: Option<PathBuf>,
@@ -75,9 +185,169 @@ struct Cli {
     #[arg(long, default_value = "false")]
     thumbnail_ingredients: bool,
 
+    /// Fail instead of warning when a spatial region in the manifest is malformed or lies
+    /// outside the asset's pixel dimensions
+    #[arg(long, default_value = "false")]
+    strict_regions: bool,
+
+    /// What happens to a C2PA manifest store already in an input asset: strip it before
+    /// signing, keep the input as the parentOf ingredient, replace it, or fail
+    #[arg(long, value_name = "POLICY", value_enum, default_value = "replace")]
+    existing: existing::ExistingPolicy,
+
+    /// Serve files referenced by c2pa.cloud-data and c2pa.external-reference assertions
+    /// (via file_path) from a local HTTP stand-in at this address after signing, pointing
+    /// their location URLs at it (use port 0 for any free port)
+    #[arg(long, value_name = "ADDR")]
+    serve_references: Option<String>,
+
     /// Validate JSON files against the indicators schema
     #[arg(short = 'v', long, default_value = "false")]
     validate: bool,
+
+    /// Dump the raw manifest store: container location, JUMBF box tree with offsets, CBOR
+    /// assertions and claim in diagnostic notation, and the COSE_Sign1 signature structure
+    #[arg(long, default_value = "false")]
+    inspect: bool,
+
+    /// Compare the manifests of two signed assets or extracted JSON files (volatile fields
+    /// such as instance IDs, signatures and timestamps are ignored)
+    #[arg(long, default_value = "false")]
+    diff: bool,
+
+    /// Reproducible signing: derive the manifest label, instance IDs and default action
+    /// timestamps from this seed so repeated runs produce byte-identical files
+    #[arg(long, value_name = "SEED")]
+    deterministic: Option<String>,
+
+    /// Golden-file regression: sign each input manifest onto a reference asset and compare
+    /// the normalized standard and JPEG Trust extractions against stored golden JSON files
+    #[arg(long, default_value = "false")]
+    regress: bool,
+
+    /// Directory holding the golden JSON files for --regress (defaults to tests/goldens)
+    #[arg(long, value_name = "DIR")]
+    goldens: Option<PathBuf>,
+
+    /// Overwrite the golden files with the current output (only valid with --regress)
+    #[arg(long, default_value = "false")]
+    update_goldens: bool,
+
+    /// Asset that --regress signs each manifest onto (defaults to testfiles/Dog.jpg)
+    #[arg(long, value_name = "FILE")]
+    regress_asset: Option<PathBuf>,
+
+    /// Test matrix: sign every combination of the assets, manifests, signing algorithms and
+    /// output formats listed in this JSON config into the --output directory
+    #[arg(long, value_name = "FILE")]
+    matrix: Option<PathBuf>,
+
+    /// Test plan (CSV or XLSX) whose rows name a test ID, asset, manifest and expected
+    /// result; signs every row into the --output directory and writes a results table
+    #[arg(long, value_name = "FILE")]
+    plan: Option<PathBuf>,
+
+    /// Negative variant mode: input files are p-* manifests; writes their n-* variants and a
+    /// variants.csv test plan describing them to the --output directory
+    #[arg(long, default_value = "false")]
+    mutate: bool,
+
+    /// Fuzz corpus: sign this many random, well-formed manifests into the --output directory,
+    /// onto the input files or generated images. The --deterministic seed picks everything
+    #[arg(long, value_name = "COUNT")]
+    fuzz: Option<usize>,
+
+    /// Stress corpus: sign many-ingredient, large-assertion, long-history and deep-chain
+    /// assets into the --output directory. Limits are optional key=value pairs, e.g.
+    /// "ingredients=300,actions=1000,depth=50,assertion-kib=1024"
+    #[arg(long, value_name = "LIMITS", num_args = 0..=1, default_missing_value = "")]
+    stress: Option<String>,
+
+    /// c2pa settings file (TOML) applied before reading or signing
+    #[arg(long, value_name = "FILE")]
+    settings: Option<PathBuf>,
+
+    /// PEM bundle of trust anchor certificates used to validate signers when reading
+    #[arg(long, value_name = "FILE")]
+    trust_anchors: Option<PathBuf>,
+
+    /// PEM bundle of end-entity signing certificates that are trusted directly
+    #[arg(long, value_name = "FILE")]
+    allowed_list: Option<PathBuf>,
+
+    /// Allowed extended key usage OIDs for signing certificates, one per line
+    #[arg(long, value_name = "FILE")]
+    trust_config: Option<PathBuf>,
+
+    /// Run a local HTTP service exposing POST /sign, /extract and /validate instead of
+    /// processing input files (/sign requires --cert and a signing key)
+    #[arg(long, default_value = "false")]
+    serve: bool,
+
+    /// Address the --serve HTTP service listens on (use port 0 for any free port)
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+    bind: String,
+}
+
+/// Where the private key signing operation happens
+#[derive(Clone, Copy, Debug)]
+enum KeySource<'a> {
+    /// PEM private key file read by the tool
+    File(&'a Path),
+    /// External program given by --signer-cmd
+    Command(&'a str),
+    /// Key held in a PKCS#11 token
+    Pkcs11(pkcs11::Pkcs11Key<'a>),
+}
+
+/// The OCSP response to staple for the signer certificate, fetched or generated as the
+/// command line asks
+fn cli_ocsp_response(cli: &Cli, cert: &Path) -> Result<Option<Vec<u8>>> {
+    let source = match (&cli.ocsp_url, cli.ocsp_status) {
+        (Some(url), _) => ocsp::OcspSource::Responder(url),
+        (None, Some(status)) => ocsp::OcspSource::Local {
+            status,
+            responder_cert: cli
+                .ocsp_responder_cert
+                .as_deref()
+                .context("--ocsp-responder-cert is required with --ocsp-status")?,
+            responder_key: cli
+                .ocsp_responder_key
+                .as_deref()
+                .context("--ocsp-responder-key is required with --ocsp-status")?,
+        },
+        (None, None) => return Ok(None),
+    };
+    let response = ocsp::ocsp_response(cert, &source).context("Failed to get OCSP response")?;
+    Ok(Some(response))
+}
+
+/// The identity assertion signing key selected on the command line, if any
+fn cli_identity(cli: &Cli) -> Option<identity::IdentityKey<'_>> {
+    cli.identity_cert
+        .as_deref()
+        .zip(cli.identity_key.as_deref())
+        .map(|(cert, key)| identity::IdentityKey { cert, key })
+}
+
+/// The key source selected on the command line, if any
+fn cli_key_source(cli: &Cli) -> Result<Option<KeySource<'_>>> {
+    if let Some(module) = &cli.pkcs11_module {
+        let label = cli
+            .key_label
+            .as_deref()
+            .context("--key-label is required with --pkcs11-module")?;
+        return Ok(Some(KeySource::Pkcs11(pkcs11::Pkcs11Key {
+            module,
+            slot: cli.pkcs11_slot,
+            label,
+        })));
+    }
+    Ok(match (&cli.key, &cli.signer_cmd) {
+        (Some(key), _) => Some(KeySource::File(key)),
+        (None, Some(command)) => Some(KeySource::Command(command)),
+        (None, None) => None,
+    })
 }
 
 /// Configuration for processing files with C2PA manifests
@@ -85,11 +355,51 @@ struct ProcessingConfig<'a> {
     manifest_json: &'a str,
     ingredients_base_dir: &'a Path,
     cert: &'a Path,
-    key: &'a Path,
+    key: KeySource<'a>,
     signing_alg: SigningAlg,
     allow_self_signed: bool,
     thumbnail_asset: bool,
     thumbnail_ingredients: bool,
+    deterministic_seed: Option<&'a str>,
+    /// DER OCSP response for the signer certificate, stapled to each signature
+    ocsp_response: Option<&'a [u8]>,
+    /// Key and certificate that sign the manifest's CAWG identity assertions
+    identity: Option<identity::IdentityKey<'a>>,
+    /// Fail on invalid regions instead of warning
+    strict_regions: bool,
+    /// What happens to a manifest store already in the input
+    existing: existing::ExistingPolicy,
+    /// Base URL of the --serve-references stand-in that referenced files are fetched from
+    reference_base_url: Option<&'a str>,
+}
+
+impl<'a> ProcessingConfig<'a> {
+    /// Configuration with the required inputs and every option off, for modes that only
+    /// set a few options with struct update syntax
+    fn new(
+        manifest_json: &'a str,
+        ingredients_base_dir: &'a Path,
+        cert: &'a Path,
+        key: KeySource<'a>,
+        signing_alg: SigningAlg,
+    ) -> Self {
+        ProcessingConfig {
+            manifest_json,
+            ingredients_base_dir,
+            cert,
+            key,
+            signing_alg,
+            allow_self_signed: false,
+            thumbnail_asset: false,
+            thumbnail_ingredients: false,
+            deterministic_seed: None,
+            ocsp_response: None,
+            identity: None,
+            strict_regions: false,
+            existing: existing::ExistingPolicy::Replace,
+            reference_base_url: None,
+        }
+    }
 }
 
 /// Expand glob patterns and collect matching file paths
@@ -133,6 +443,63 @@ fn determine_output_path(input: &Path, output: &Path) -> Result<PathBuf> {
     }
 }
 
+/// Whether a path is "-" (stdin or stdout)
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+/// Resolve the MIME type of an asset from --format, or from the file extension
+fn resolve_format(format: Option<&str>, path: &Path) -> Result<String> {
+    if let Some(format) = format {
+        if format.contains('/') {
+            return Ok(format.to_string());
+        }
+        return extension_to_mime(format)
+            .map(str::to_string)
+            .context(format!("Unsupported --format: {}", format));
+    }
+
+    if is_stdio(path) {
+        anyhow::bail!("--format is required when reading from stdin or writing to stdout");
+    }
+    let extension = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .context(format!("File has no extension: {:?}", path))?;
+    extension_to_mime(extension)
+        .map(str::to_string)
+        .context(format!("Unsupported file format: {}", extension))
+}
+
+/// Read a whole asset from a file, or from stdin for "-"
+fn read_asset(path: &Path) -> Result<Vec<u8>> {
+    if is_stdio(path) {
+        let mut data = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut data)
+            .context("Failed to read asset from stdin")?;
+        Ok(data)
+    } else {
+        fs::read(path).context(format!("Failed to read input file: {:?}", path))
+    }
+}
+
+/// Write a whole asset to a file, or to stdout for "-"
+fn write_asset(path: &Path, data: &[u8]) -> Result<()> {
+    if is_stdio(path) {
+        let mut stdout = std::io::stdout().lock();
+        stdout
+            .write_all(data)
+            .and_then(|_| stdout.flush())
+            .context("Failed to write to stdout")
+    } else {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create output directory")?;
+        }
+        fs::write(path, data).context(format!("Failed to write output file: {:?}", path))
+    }
+}
+
 /// Converts a file extension to a MIME type
 fn extension_to_mime(extension: &str) -> Option<&'static str> {
     Some(match extension.to_lowercase().as_str() {
@@ -164,21 +531,31 @@ fn extension_to_mime(extension: &str) -> Option<&'static str> {
     })
 }
 
-/// Generate a thumbnail from an image stream
-/// Returns (format, thumbnail_bytes)
-fn make_thumbnail_from_stream(format: &str, stream: &mut fs::File) -> Result<(String, Vec<u8>)> {
+/// Image format the image crate can decode and encode for a MIME type
+fn image_format(format: &str) -> Option<image::ImageFormat> {
     use image::ImageFormat;
 
-    // Determine image format from MIME type
-    let img_format = match format {
+    Some(match format {
         "image/jpeg" => ImageFormat::Jpeg,
         "image/png" => ImageFormat::Png,
         "image/gif" => ImageFormat::Gif,
         "image/bmp" => ImageFormat::Bmp,
         "image/tiff" => ImageFormat::Tiff,
         "image/webp" => ImageFormat::WebP,
-        _ => ImageFormat::Jpeg, // Default to JPEG for unknown formats
-    };
+        _ => return None,
+    })
+}
+
+/// Generate a thumbnail from an image stream
+/// Returns (format, thumbnail_bytes)
+fn make_thumbnail_from_stream<R: Read + Seek>(
+    format: &str,
+    stream: &mut R,
+) -> Result<(String, Vec<u8>)> {
+    use image::ImageFormat;
+
+    // Determine image format from MIME type (default to JPEG for unknown formats)
+    let img_format = image_format(format).unwrap_or(ImageFormat::Jpeg);
 
     // Wrap in BufReader for image loading
     let reader = BufReader::new(stream);
@@ -206,7 +583,7 @@ fn load_ingredient_from_file(file_path: &Path, generate_thumbnail: bool) -> Resu
         anyhow::bail!("Ingredient file not found: {:?}", file_path);
     }
 
-    println!("  Loading ingredient: {:?}", file_path);
+    status!("  Loading ingredient: {:?}", file_path);
 
     // Load the ingredient file
     let mut source = fs::File::open(file_path)
@@ -221,17 +598,24 @@ fn load_ingredient_from_file(file_path: &Path, generate_thumbnail: bool) -> Resu
     let format = extension_to_mime(extension)
         .context(format!("Unsupported ingredient file format: {}", extension))?;
 
-    // Create an Ingredient from the file
-    let mut ingredient = Ingredient::from_stream(format, &mut source).context(format!(
+    load_ingredient_from_stream(format, &mut source, generate_thumbnail).context(format!(
         "Failed to create ingredient from file: {:?}",
         file_path
-    ))?;
+    ))
+}
+
+/// Create an ingredient from an asset stream, optionally generating its thumbnail
+fn load_ingredient_from_stream<R: Read + Seek + Send>(
+    format: &str,
+    source: &mut R,
+    generate_thumbnail: bool,
+) -> Result<Ingredient> {
+    let mut ingredient = Ingredient::from_stream(format, source)?;
 
     // Generate thumbnail if requested and not already present
     if generate_thumbnail && ingredient.thumbnail_ref().is_none() {
-        use std::io::Seek;
         source.rewind()?;
-        let (thumb_format, thumbnail) = make_thumbnail_from_stream(format, &mut source)
+        let (thumb_format, thumbnail) = make_thumbnail_from_stream(format, source)
             .context("Failed to generate thumbnail for ingredient")?;
         ingredient
             .set_thumbnail(&thumb_format, thumbnail)
@@ -247,6 +631,7 @@ fn process_ingredients(
     manifest_json: &str,
     ingredients_base_dir: &Path,
     generate_thumbnails: bool,
+    mut deterministic_ids: Option<&mut DeterministicIds>,
 ) -> Result<usize> {
     // Parse the manifest JSON to check for ingredients with file paths
     let manifest: JsonValue =
@@ -307,6 +692,9 @@ fn process_ingredients(
             // This allows the ingredient to be referenced in actions by this label
             if let Some(label) = ingredient_def.get("label").and_then(|v| v.as_str()) {
                 ingredient.set_instance_id(label);
+            } else if let Some(ids) = deterministic_ids.as_deref_mut() {
+                // Replace the randomly generated instance ID in deterministic mode
+                ingredient.set_instance_id(ids.instance_id());
             }
 
             // Set metadata if provided
@@ -324,7 +712,7 @@ fn process_ingredients(
                     }
 
                     ingredient.set_metadata(assertion_metadata);
-                    println!(
+                    status!(
                         "  Set {} metadata field(s) on ingredient",
                         metadata_map.len()
                     );
@@ -352,6 +740,18 @@ fn parse_signing_algorithm(alg: &str) -> Result<SigningAlg> {
     }
 }
 
+/// Use the explicitly requested signing algorithm, or detect it from the certificate
+fn resolve_signing_algorithm(algorithm: Option<&str>, cert_path: &Path) -> Result<SigningAlg> {
+    if let Some(alg_str) = algorithm {
+        parse_signing_algorithm(alg_str)
+    } else {
+        status!("Auto-detecting signing algorithm from certificate...");
+        let detected = detect_signing_algorithm(cert_path)?;
+        status!("  Detected: {:?}", detected);
+        Ok(detected)
+    }
+}
+
 /// Detect the signing algorithm from a certificate file
 /// This examines the public key type and parameters to determine the appropriate algorithm
 fn detect_signing_algorithm(cert_path: &Path) -> Result<SigningAlg> {
@@ -433,6 +833,137 @@ fn create_callback_signer(
     Ok(signer)
 }
 
+/// Create a callback signer that delegates signing to an external program
+///
+/// The program is started once per signature with the to-be-signed bytes on stdin and
+/// must write the raw signature to stdout. `C2PA_SIGNING_ALG` is set to the algorithm
+/// name so one helper can serve several key types.
+fn create_command_signer(
+    command: &str,
+    cert_path: &Path,
+    signing_alg: SigningAlg,
+) -> Result<CallbackSigner> {
+    let cert_data = fs::read(cert_path).context("Failed to read certificate file")?;
+    let args: Vec<String> = command.split_whitespace().map(str::to_string).collect();
+    if args.is_empty() {
+        anyhow::bail!("--signer-cmd is empty");
+    }
+
+    let cmd_signer = move |_context: *const (), data: &[u8]| command_sign(data, &args, signing_alg);
+    Ok(CallbackSigner::new(cmd_signer, signing_alg, cert_data))
+}
+
+/// Sign data by running an external signer program
+fn command_sign(data: &[u8], args: &[String], signing_alg: SigningAlg) -> c2pa::Result<Vec<u8>> {
+    use c2pa::crypto::raw_signature::RawSignerError;
+    use std::process::{Command, Stdio};
+
+    let mut child = Command::new(&args[0])
+        .args(&args[1..])
+        .env("C2PA_SIGNING_ALG", signing_alg.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            RawSignerError::InternalError(format!("Failed to start signer {}: {}", args[0], e))
+        })?;
+
+    // Write from a separate thread so a signer that streams its output cannot deadlock
+    let mut stdin = child.stdin.take().expect("stdin should be piped");
+    let to_sign = data.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&to_sign));
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| RawSignerError::InternalError(format!("Signer failed: {}", e)))?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        return Err(RawSignerError::InternalError(format!(
+            "Signer {} exited with {}: {}",
+            args[0],
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+        .into());
+    }
+    if output.stdout.is_empty() {
+        return Err(RawSignerError::InternalError(format!(
+            "Signer {} returned no signature",
+            args[0]
+        ))
+        .into());
+    }
+
+    Ok(match ecdsa_field_size(signing_alg) {
+        Some(field_size) => ecdsa_der_to_raw(&output.stdout, field_size).unwrap_or(output.stdout),
+        None => output.stdout,
+    })
+}
+
+/// Size in bytes of r and s in a raw (r || s) ECDSA signature
+fn ecdsa_field_size(signing_alg: SigningAlg) -> Option<usize> {
+    match signing_alg {
+        SigningAlg::Es256 => Some(32),
+        SigningAlg::Es384 => Some(48),
+        SigningAlg::Es512 => Some(66),
+        _ => None,
+    }
+}
+
+/// Convert a DER-encoded ECDSA signature (as returned by most KMS APIs and OpenSSL) to
+/// the raw r || s form COSE expects. Returns None if `sig` is not DER.
+fn ecdsa_der_to_raw(sig: &[u8], field_size: usize) -> Option<Vec<u8>> {
+    fn read_len(data: &[u8], pos: &mut usize) -> Option<usize> {
+        let first = *data.get(*pos)?;
+        *pos += 1;
+        if first < 0x80 {
+            return Some(first as usize);
+        }
+        let count = (first & 0x7F) as usize;
+        let mut len = 0usize;
+        for _ in 0..count {
+            len = (len << 8) | *data.get(*pos)? as usize;
+            *pos += 1;
+        }
+        Some(len)
+    }
+
+    fn read_integer<'d>(data: &'d [u8], pos: &mut usize) -> Option<&'d [u8]> {
+        if *data.get(*pos)? != 0x02 {
+            return None;
+        }
+        *pos += 1;
+        let len = read_len(data, pos)?;
+        let value = data.get(*pos..*pos + len)?;
+        *pos += len;
+        Some(value)
+    }
+
+    let mut pos = 0;
+    if *sig.first()? != 0x30 {
+        return None;
+    }
+    pos += 1;
+    if read_len(sig, &mut pos)? != sig.len() - pos {
+        return None;
+    }
+
+    let mut raw = Vec::with_capacity(field_size * 2);
+    for _ in 0..2 {
+        let value = read_integer(sig, &mut pos)?;
+        let start = value.iter().position(|&b| b != 0).unwrap_or(value.len());
+        let value = &value[start..];
+        if value.len() > field_size {
+            return None;
+        }
+        raw.extend(std::iter::repeat_n(0u8, field_size - value.len()));
+        raw.extend_from_slice(value);
+    }
+    (pos == sig.len()).then_some(raw)
+}
+
 /// Sign data using Ed25519
 fn ed25519_sign(data: &[u8], private_key: &[u8]) -> c2pa::Result<Vec<u8>> {
     use c2pa::crypto::raw_signature::RawSignerError;
@@ -490,21 +1021,52 @@ fn rsa_sign(data: &[u8], private_key: &[u8]) -> c2pa::Result<Vec<u8>> {
     Ok(signature.to_vec())
 }
 
-/// Extract C2PA manifest from a file and save it as JSON
-fn extract_manifest(input_path: &Path, output_path: &Path, use_jpt_format: bool) -> Result<()> {
-    // Validate input file exists
-    if !input_path.exists() {
-        anyhow::bail!("Input file does not exist: {:?}", input_path);
+/// Reader used to load a manifest store
+enum StoreReader {
+    Standard(Reader),
+    JpegTrust(JpegTrustReader),
+}
+
+/// Manifest store read from a signed file
+struct ManifestStore {
+    active_label: String,
+    /// Manifest store JSON in the requested format (standard or JPEG Trust)
+    json: String,
+    reader: StoreReader,
+}
+
+impl ManifestStore {
+    /// The standard c2pa-rs reader, also available in JPEG Trust mode
+    fn reader(&self) -> &Reader {
+        match &self.reader {
+            StoreReader::Standard(reader) => reader,
+            StoreReader::JpegTrust(jpt_reader) => jpt_reader.inner(),
+        }
     }
 
-    println!("Extracting C2PA manifest...");
-    println!("  Input: {:?}", input_path);
-    if use_jpt_format {
-        println!("  Format: JPEG Trust");
+    /// Wrap a reader, looking up the active manifest and the output JSON
+    fn from_reader(reader: StoreReader) -> Result<Self> {
+        let (active_label, json) = match &reader {
+            StoreReader::Standard(reader) => (reader.active_label(), reader.json()),
+            StoreReader::JpegTrust(jpt_reader) => {
+                (jpt_reader.inner().active_label(), jpt_reader.json())
+            }
+        };
+        let active_label = active_label
+            .context("No active C2PA manifest found in the input file")?
+            .to_string();
+
+        Ok(ManifestStore {
+            active_label,
+            json,
+            reader,
+        })
     }
+}
 
-    // Get the manifest JSON string based on format
-    let manifest_json = if use_jpt_format {
+/// Read the manifest store of a signed file
+fn read_manifest_store(input_path: &Path, use_jpt_format: bool) -> Result<ManifestStore> {
+    let reader = if use_jpt_format {
         // Use JPEG Trust Reader
         let mut jpt_reader = JpegTrustReader::from_file(input_path).context(
             "Failed to read C2PA data from input file. The file may not contain a C2PA manifest.",
@@ -512,43 +1074,100 @@ fn extract_manifest(input_path: &Path, output_path: &Path, use_jpt_format: bool)
 
         // Compute asset hash to include asset_info in the output
         if let Ok(hash) = jpt_reader.compute_asset_hash_from_file(input_path) {
-            println!("  Asset hash computed: {}", hash);
+            status!("  Asset hash computed: {}", hash);
         }
 
-        // Get the active manifest
-        let active_label = jpt_reader
-            .inner()
-            .active_label()
-            .context("No active C2PA manifest found in the input file")?;
-
-        println!("  Active manifest label: {}", active_label);
-
-        jpt_reader.json()
+        StoreReader::JpegTrust(jpt_reader)
     } else {
         // Use standard Reader
-        let reader = Reader::from_file(input_path).context(
+        StoreReader::Standard(Reader::from_file(input_path).context(
             "Failed to read C2PA data from input file. The file may not contain a C2PA manifest.",
-        )?;
-
-        // Get the active manifest
-        let active_label = reader
-            .active_label()
-            .context("No active C2PA manifest found in the input file")?;
+        )?)
+    };
 
-        println!("  Active manifest label: {}", active_label);
+    ManifestStore::from_reader(reader)
+}
 
-        reader.json()
+/// Read the manifest store of an asset held in memory (e.g. read from stdin)
+fn read_manifest_store_from_bytes(
+    data: Vec<u8>,
+    format: &str,
+    use_jpt_format: bool,
+) -> Result<ManifestStore> {
+    let reader = if use_jpt_format {
+        // The asset hash in asset_info is only computed for files
+        status!("  Note: asset hash is not computed for stdin input");
+        StoreReader::JpegTrust(
+            JpegTrustReader::from_stream(format, Cursor::new(data)).context(
+                "Failed to read C2PA data from stdin. The asset may not contain a C2PA manifest.",
+            )?,
+        )
+    } else {
+        StoreReader::Standard(Reader::from_stream(format, Cursor::new(data)).context(
+            "Failed to read C2PA data from stdin. The asset may not contain a C2PA manifest.",
+        )?)
     };
 
-    // Determine the final output path
-    let final_output_path = if output_path.is_dir() {
-        // If output is a directory, create a filename based on the input
+    ManifestStore::from_reader(reader)
+}
+
+/// Options for extract mode
+struct ExtractConfig<'a> {
+    use_jpt_format: bool,
+    /// Also write the validation summary next to the JSON
+    write_summary: bool,
+    /// Export referenced resources to `{resources_dir}/{input_stem}/`
+    resources_dir: Option<&'a Path>,
+    /// Asset format for stdin input
+    format: Option<&'a str>,
+}
+
+/// Extract C2PA manifest from a file (or stdin for "-") and save it as JSON
+/// A validation summary is printed, and also written next to the JSON if requested.
+/// Writing to "-" prints the JSON to stdout.
+fn extract_manifest(input_path: &Path, output_path: &Path, config: &ExtractConfig) -> Result<()> {
+    let from_stdin = is_stdio(input_path);
+
+    // Validate input file exists
+    if !from_stdin && !input_path.exists() {
+        anyhow::bail!("Input file does not exist: {:?}", input_path);
+    }
+
+    status!("Extracting C2PA manifest...");
+    if from_stdin {
+        status!("  Input: stdin");
+    } else {
+        status!("  Input: {:?}", input_path);
+    }
+    if config.use_jpt_format {
+        status!("  Format: JPEG Trust");
+    }
+
+    let (store, input_stem) = if from_stdin {
+        let format = resolve_format(config.format, input_path)?;
+        let data = read_asset(input_path)?;
+        (
+            read_manifest_store_from_bytes(data, &format, config.use_jpt_format)?,
+            "stdin",
+        )
+    } else {
         let input_stem = input_path
             .file_stem()
             .context("Input file has no filename")?
             .to_str()
             .context("Invalid UTF-8 in filename")?;
-        let suffix = if use_jpt_format {
+        (
+            read_manifest_store(input_path, config.use_jpt_format)?,
+            input_stem,
+        )
+    };
+    status!("  Active manifest label: {}", store.active_label);
+
+    // Determine the final output path
+    let to_stdout = is_stdio(output_path);
+    let final_output_path = if output_path.is_dir() {
+        // If output is a directory, create a filename based on the input
+        let suffix = if config.use_jpt_format {
             "_manifest_jpt.json"
         } else {
             "_manifest.json"
@@ -558,25 +1177,226 @@ fn extract_manifest(input_path: &Path, output_path: &Path, use_jpt_format: bool)
         output_path.to_path_buf()
     };
 
+    if to_stdout && config.write_summary {
+        anyhow::bail!("--summary needs a file or directory output, not stdout");
+    }
+
     // Create output directory if it doesn't exist
-    if let Some(parent) = final_output_path.parent() {
-        fs::create_dir_all(parent).context("Failed to create output directory")?;
+    if !to_stdout {
+        if let Some(parent) = final_output_path.parent() {
+            fs::create_dir_all(parent).context("Failed to create output directory")?;
+        }
     }
 
     // Parse and re-serialize the JSON for pretty formatting
-    let json_value: serde_json::Value =
-        serde_json::from_str(&manifest_json).context("Failed to parse manifest JSON")?;
+    let mut json_value: serde_json::Value =
+        serde_json::from_str(&store.json).context("Failed to parse manifest JSON")?;
+    // The standard format lists every resource and feeds the validation summary
+    let standard_value: serde_json::Value =
+        serde_json::from_str(&store.reader().json()).context("Failed to parse manifest JSON")?;
+
+    if let Some(resources_dir) = config.resources_dir {
+        let asset_resources_dir = resources_dir.join(input_stem);
+        let json_dir = if to_stdout {
+            Path::new("")
+        } else {
+            final_output_path.parent().unwrap_or_else(|| Path::new(""))
+        };
+        let exported = resources::export_resources(
+            store.reader(),
+            &standard_value,
+            &mut json_value,
+            &asset_resources_dir,
+            json_dir,
+        )?;
+        status!(
+            "  Resources exported: {} to {:?}",
+            exported.len(),
+            asset_resources_dir
+        );
+        for path in &exported {
+            status!("    {:?}", path);
+        }
+    }
+
     let pretty_json = serde_json::to_string_pretty(&json_value).context("Failed to format JSON")?;
 
-    fs::write(&final_output_path, pretty_json)
-        .context("Failed to write manifest JSON to output file")?;
+    if let Some(state) = json_value.get("validation_state").and_then(|v| v.as_str()) {
+        status!("  Validation state: {}", state);
+    }
 
-    println!("✓ Successfully extracted C2PA manifest");
-    println!("  Output file: {:?}", final_output_path);
+    if to_stdout {
+        write_asset(&final_output_path, format!("{}\n", pretty_json).as_bytes())?;
+    } else {
+        fs::write(&final_output_path, pretty_json)
+            .context("Failed to write manifest JSON to output file")?;
+    }
+
+    // Human-readable validation summary, always built from the standard format
+    let summary = summary::build_summary(&standard_value);
+    status!("\n--- Validation summary ---");
+    for line in summary.lines() {
+        status!("  {}", line);
+    }
+
+    status!("✓ Successfully extracted C2PA manifest");
+    if to_stdout {
+        status!("  Output: stdout");
+    } else {
+        status!("  Output file: {:?}", final_output_path);
+    }
+
+    if config.write_summary {
+        let summary_path = summary_path_for(&final_output_path);
+        fs::write(&summary_path, &summary).context(format!(
+            "Failed to write validation summary: {:?}",
+            summary_path
+        ))?;
+        status!("  Summary file: {:?}", summary_path);
+    }
 
     Ok(())
 }
 
+/// Path of the validation summary written next to an extracted JSON file
+fn summary_path_for(json_path: &Path) -> PathBuf {
+    let stem = json_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("manifest");
+    json_path.with_file_name(format!("{}_summary.txt", stem))
+}
+
+/// Build the manifest for one asset: deterministic IDs and file-based ingredients
+/// `context_name` identifies the asset when deriving deterministic IDs
+fn prepare_builder(config: &ProcessingConfig, context_name: &str) -> Result<Builder> {
+    // In deterministic mode, derive all generated values from the seed and the input name
+    let mut deterministic_ids = config
+        .deterministic_seed
+        .map(|seed| DeterministicIds::new(seed, context_name));
+
+    let manifest_json = match deterministic_ids.as_mut() {
+        Some(ids) => deterministic::apply_to_manifest_json(config.manifest_json, ids)?,
+        None => config.manifest_json.to_string(),
+    };
+
+    // Cloud-data and external-reference assertions backed by local files are hashed here
+    let (manifest_json, references) = references::resolve_references(
+        &manifest_json,
+        config.ingredients_base_dir,
+        config.reference_base_url,
+    )
+    .context("Failed to resolve referenced files")?;
+
+    // Create a builder from the JSON manifest
+    let mut builder = Builder::from_json(&manifest_json)
+        .context("Failed to create builder from JSON manifest")?;
+    for (label, value) in &references {
+        builder
+            .add_assertion(label, value)
+            .context(format!("Failed to add assertion {}", label))?;
+    }
+    if !references.is_empty() {
+        status!("  Hashed {} referenced file(s)", references.len());
+    }
+
+    // Process any ingredients with file paths
+    let ingredient_count = process_ingredients(
+        &mut builder,
+        &manifest_json,
+        config.ingredients_base_dir,
+        config.thumbnail_ingredients,
+        deterministic_ids.as_mut(),
+    )
+    .context("Failed to process ingredients")?;
+
+    if ingredient_count > 0 {
+        status!("  Processed {} ingredient(s) from files", ingredient_count);
+        if config.thumbnail_ingredients {
+            status!("  Generated thumbnails for ingredients");
+        }
+    }
+
+    // Add any assertions whose contents come from files
+    let assertion_count = assertion_files::process_assertion_files(
+        &mut builder,
+        &manifest_json,
+        config.ingredients_base_dir,
+    )
+    .context("Failed to process assertion files")?;
+    if assertion_count > 0 {
+        status!("  Added {} assertion(s) from files", assertion_count);
+    }
+
+    Ok(builder)
+}
+
+/// Generate a thumbnail of the asset and set it as the claim thumbnail
+fn set_asset_thumbnail<R: Read + Seek>(
+    builder: &mut Builder,
+    format: &str,
+    asset: &mut R,
+) -> Result<()> {
+    status!("  Generating thumbnail for main asset...");
+    let (thumb_format, thumbnail) = make_thumbnail_from_stream(format, asset)
+        .context("Failed to generate thumbnail for main asset")?;
+
+    builder
+        .set_thumbnail(&thumb_format, &mut Cursor::new(thumbnail))
+        .context("Failed to set thumbnail for main asset")?;
+    Ok(())
+}
+
+/// Create the signer for a processing configuration
+fn create_config_signer(config: &ProcessingConfig) -> Result<Box<dyn c2pa::Signer>> {
+    let signer = create_key_signer(config)?;
+    let signer: Box<dyn c2pa::Signer> = match config.ocsp_response {
+        Some(response) => Box::new(ocsp::StapledSigner::new(signer, response.to_vec())),
+        None => signer,
+    };
+    identity::with_identity_assertions(
+        signer,
+        config.manifest_json,
+        config.identity,
+        config.deterministic_seed.is_some(),
+    )
+}
+
+/// Create the signer for the configured key source
+fn create_key_signer(config: &ProcessingConfig) -> Result<Box<dyn c2pa::Signer>> {
+    let key = match config.key {
+        KeySource::File(key) => key,
+        KeySource::Command(command) => {
+            let signer = create_command_signer(command, config.cert, config.signing_alg)
+                .context("Failed to create external command signer")?;
+            return Ok(Box::new(signer));
+        }
+        KeySource::Pkcs11(key) => {
+            let signer = pkcs11::create_pkcs11_signer(&key, config.cert, config.signing_alg)
+                .context("Failed to create PKCS#11 signer")?;
+            return Ok(Box::new(signer));
+        }
+    };
+
+    if config.allow_self_signed || config.deterministic_seed.is_some() {
+        // Use callback signer that bypasses certificate validation
+        // (deterministic mode also needs it: the local Ed25519 and RFC 6979 ECDSA
+        // implementations produce reproducible signatures and no timestamp is requested)
+        let signer = create_callback_signer(config.cert, key, config.signing_alg)
+            .context("Failed to create callback signer")?;
+        Ok(Box::new(signer))
+    } else {
+        // Use standard signer with full certificate validation
+        create_signer::from_files(
+            config.cert.to_str().context("Invalid cert path")?,
+            key.to_str().context("Invalid key path")?,
+            config.signing_alg,
+            None,
+        )
+        .context("Failed to create signer")
+    }
+}
+
 /// Process a single input file with the manifest
 fn process_single_file(
     input_path: &Path,
@@ -610,81 +1430,225 @@ fn process_single_file(
     println!("  Input: {:?}", input_path);
     println!("  Output: {:?}", final_output_path);
 
-    // Create a builder from the JSON manifest
-    let mut builder = Builder::from_json(config.manifest_json)
-        .context("Failed to create builder from JSON manifest")?;
+    let context_name = input_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .context("Input file has no filename")?;
+    let has_existing = existing::has_manifest_file(input_path);
+    let in_memory = edits::has_edits(config.manifest_json)?
+        || binding::hard_binding(config.manifest_json)?.is_some()
+        || (has_existing
+            && matches!(
+                config.existing,
+                existing::ExistingPolicy::Strip | existing::ExistingPolicy::Parent
+            ));
+    if in_memory {
+        // Edits change the pixels, custom hard bindings place the manifest themselves and
+        // existing manifest stores are stripped or read as the parent, so the asset is
+        // signed in memory
+        let extension = input_path
+            .extension()
+            .and_then(|s| s.to_str())
+            .context("Input file has no extension")?;
+        let format = extension_to_mime(extension)
+            .context(format!("Unsupported input file format: {}", extension))?;
+        let data = fs::read(input_path).context("Failed to read input file")?;
+        let signed = sign_asset_bytes(config, format, context_name, data)?;
+        fs::write(&final_output_path, signed).context("Failed to write output file")?;
+    } else {
+        if has_existing {
+            existing::report(config.existing)?;
+        }
+        regions::check_manifest_regions(
+            config.manifest_json,
+            image::image_dimensions(input_path).ok(),
+            config.strict_regions,
+        )?;
+        let mut builder = prepare_builder(config, context_name)?;
 
-    // Process any ingredients with file paths
-    let ingredient_count = process_ingredients(
-        &mut builder,
+        // Generate thumbnail for the asset if requested
+        if config.thumbnail_asset {
+            let mut input_file = fs::File::open(input_path)
+                .context("Failed to open input file for thumbnail generation")?;
+
+            // Determine format from input file extension
+            let input_extension = input_path
+                .extension()
+                .and_then(|s| s.to_str())
+                .context("Input file has no extension")?;
+
+            let input_format = extension_to_mime(input_extension)
+                .context("Unsupported input file format for thumbnail")?;
+
+            set_asset_thumbnail(&mut builder, input_format, &mut input_file)?;
+        }
+
+        // Sign and embed the manifest into the asset
+        let signer = create_config_signer(config)?;
+        builder
+            .sign_file(&*signer, input_path, &final_output_path)
+            .context("Failed to sign and embed manifest")?;
+    }
+
+    println!("✓ Successfully created and embedded C2PA manifest");
+    println!("  Output file: {:?}", final_output_path);
+
+    Ok(())
+}
+
+/// Sign an asset held in memory with `Builder::sign` on streams; returns the signed asset
+/// `context_name` identifies the asset when deriving deterministic IDs
+fn sign_asset_bytes(
+    config: &ProcessingConfig,
+    format: &str,
+    context_name: &str,
+    data: Vec<u8>,
+) -> Result<Vec<u8>> {
+    // Deal with a manifest store already in the asset before anything else reads it
+    let has_existing = existing::has_manifest(format, Cursor::new(&data));
+    if has_existing {
+        existing::report(config.existing)?;
+    }
+    let data = match config.existing {
+        existing::ExistingPolicy::Strip if has_existing => existing::strip_manifest(format, &data)?,
+        _ => data,
+    };
+    // Edits already keep the unedited input, with its manifest store, as the parent
+    let existing_parent = has_existing
+        && config.existing == existing::ExistingPolicy::Parent
+        && !edits::has_edits(config.manifest_json)?;
+
+    // Apply the manifest's edits to the pixels; the generated actions go into the manifest
+    let when = match config.deterministic_seed {
+        Some(_) => None,
+        None => Some(time::rfc3339(time::unix_time_now()?)),
+    };
+    let edited = edits::apply_edits(
         config.manifest_json,
+        format,
+        &data,
+        context_name,
         config.ingredients_base_dir,
         config.thumbnail_ingredients,
-    )
-    .context("Failed to process ingredients")?;
+        when.as_deref(),
+    )?;
+    // Regions written in the manifest describe the asset as signed (after any edits)
+    regions::check_manifest_regions(
+        config.manifest_json,
+        regions::image_dimensions(edited.as_ref().map_or(&data, |edited| &edited.data)),
+        config.strict_regions,
+    )?;
+    let (mut manifest_json, data, mut ingredients) = match edited {
+        Some(edited) => (edited.manifest_json, edited.data, edited.ingredients),
+        None => (config.manifest_json.to_string(), data, Vec::new()),
+    };
+    if existing_parent {
+        let (with_parent, parent) = existing::add_existing_parent(
+            &manifest_json,
+            format,
+            &data,
+            context_name,
+            config.thumbnail_ingredients,
+        )?;
+        manifest_json = with_parent;
+        ingredients.insert(0, parent);
+    }
+    let config = ProcessingConfig {
+        manifest_json: &manifest_json,
+        ..*config
+    };
 
-    if ingredient_count > 0 {
-        println!("  Processed {} ingredient(s) from files", ingredient_count);
-        if config.thumbnail_ingredients {
-            println!("  Generated thumbnails for ingredients");
-        }
+    let mut builder = prepare_builder(&config, context_name)?;
+    for ingredient in ingredients {
+        builder.add_ingredient(ingredient);
     }
 
-    // Generate thumbnail for the asset if requested
+    let mut source = Cursor::new(data);
     if config.thumbnail_asset {
-        println!("  Generating thumbnail for main asset...");
-        let mut input_file = fs::File::open(input_path)
-            .context("Failed to open input file for thumbnail generation")?;
+        set_asset_thumbnail(&mut builder, format, &mut source)?;
+        source.rewind().context("Failed to rewind input asset")?;
+    }
 
-        // Determine format from input file extension
-        let input_extension = input_path
-            .extension()
-            .and_then(|s| s.to_str())
-            .context("Input file has no extension")?;
+    // Sign and embed the manifest into the asset
+    let signer = create_config_signer(&config)?;
+    if let Some(binding) = binding::hard_binding(config.manifest_json)? {
+        return binding::sign_with_binding(
+            &mut builder,
+            &*signer,
+            format,
+            source.into_inner(),
+            &binding,
+        );
+    }
+    let mut dest = Cursor::new(Vec::new());
+    builder
+        .sign(&*signer, format, &mut source, &mut dest)
+        .context("Failed to sign and embed manifest")?;
 
-        let input_format = extension_to_mime(input_extension)
-            .context("Unsupported input file format for thumbnail")?;
+    Ok(dest.into_inner())
+}
 
-        let (thumb_format, thumbnail) = make_thumbnail_from_stream(input_format, &mut input_file)
-            .context("Failed to generate thumbnail for main asset")?;
+/// Sign an asset read from stdin and/or written to stdout ("-")
+/// The whole asset is buffered in memory and signed with `Builder::sign` on streams.
+fn process_stdio(
+    input_path: &Path,
+    output_path: &Path,
+    format: Option<&str>,
+    config: &ProcessingConfig,
+) -> Result<()> {
+    let from_stdin = is_stdio(input_path);
+    let describe = |path: &Path, stream: &str| {
+        if is_stdio(path) {
+            stream.to_string()
+        } else {
+            format!("{:?}", path)
+        }
+    };
+    status!("\n=== Processing: {} ===", describe(input_path, "stdin"));
 
-        builder
-            .set_thumbnail(&thumb_format, &mut Cursor::new(thumbnail))
-            .context("Failed to set thumbnail for main asset")?;
+    if from_stdin && output_path.is_dir() {
+        anyhow::bail!(
+            "Output must be a file or \"-\" when reading from stdin. Got directory: {:?}",
+            output_path
+        );
     }
+    let final_output_path = if is_stdio(output_path) {
+        output_path.to_path_buf()
+    } else {
+        determine_output_path(input_path, output_path)?
+    };
 
-    // Sign and embed the manifest into the asset
-    if config.allow_self_signed {
-        // Use callback signer that bypasses certificate validation
-        let signer = create_callback_signer(config.cert, config.key, config.signing_alg)
-            .context("Failed to create callback signer")?;
-        builder
-            .sign_file(&signer, input_path, &final_output_path)
-            .context("Failed to sign and embed manifest")?;
+    // Format comes from --format, or from the extension of whichever side is a file
+    let format_source = if from_stdin {
+        &final_output_path
     } else {
-        // Use standard signer with full certificate validation
-        let signer = create_signer::from_files(
-            config.cert.to_str().context("Invalid cert path")?,
-            config.key.to_str().context("Invalid key path")?,
-            config.signing_alg,
-            None,
-        )
-        .context("Failed to create signer")?;
-        builder
-            .sign_file(&*signer, input_path, &final_output_path)
-            .context("Failed to sign and embed manifest")?;
-    }
+        input_path
+    };
+    let asset_format = resolve_format(format, format_source)?;
 
-    println!("✓ Successfully created and embedded C2PA manifest");
-    println!("  Output file: {:?}", final_output_path);
+    status!("  Input: {}", describe(input_path, "stdin"));
+    status!("  Output: {}", describe(&final_output_path, "stdout"));
+    status!("  Format: {}", asset_format);
+
+    let context_name = if from_stdin {
+        "stdin"
+    } else {
+        input_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .context("Input file has no filename")?
+    };
+    let signed = sign_asset_bytes(config, &asset_format, context_name, read_asset(input_path)?)?;
+    write_asset(&final_output_path, &signed)?;
+
+    status!("✓ Successfully created and embedded C2PA manifest");
+    status!("  Output: {}", describe(&final_output_path, "stdout"));
 
     Ok(())
 }
 
-/// Validate JSON files against the indicators schema
-fn validate_json_files(input_paths: &[PathBuf]) -> Result<()> {
-    println!("=== Validating JSON files against indicators schema ===\n");
-
+/// Load and compile the indicators JSON schema
+fn load_indicators_schema() -> Result<jsonschema::Validator> {
     // Load the schema from the embedded file
     let schema_path = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("INTERNAL")
@@ -703,8 +1667,32 @@ fn validate_json_files(input_paths: &[PathBuf]) -> Result<()> {
         serde_json::from_str(&schema_content).context("Failed to parse indicators schema JSON")?;
 
     // Compile the schema
-    let compiled_schema = jsonschema::validator_for(&schema_json)
-        .map_err(|e| anyhow::anyhow!("Failed to compile JSON schema: {}", e))?;
+    jsonschema::validator_for(&schema_json)
+        .map_err(|e| anyhow::anyhow!("Failed to compile JSON schema: {}", e))
+}
+
+/// Schema validation errors of a JSON document, as "At {path}: {error}" messages
+fn schema_errors(validator: &jsonschema::Validator, json_value: &JsonValue) -> Vec<String> {
+    match validator.validate(json_value) {
+        Ok(_) => Vec::new(),
+        Err(errors) => errors
+            .map(|error| {
+                let instance_path = if error.instance_path.to_string().is_empty() {
+                    "root".to_string()
+                } else {
+                    error.instance_path.to_string()
+                };
+                format!("At {}: {}", instance_path, error)
+            })
+            .collect(),
+    }
+}
+
+/// Validate JSON files against the indicators schema
+fn validate_json_files(input_paths: &[PathBuf]) -> Result<()> {
+    println!("=== Validating JSON files against indicators schema ===\n");
+
+    let compiled_schema = load_indicators_schema()?;
 
     println!("Schema compiled successfully\n");
 
@@ -740,31 +1728,23 @@ fn validate_json_files(input_paths: &[PathBuf]) -> Result<()> {
         };
 
         // Validate against schema
-        let validation_result = compiled_schema.validate(&json_value);
-        match validation_result {
-            Ok(_) => {
-                println!("  ✓ Valid\n");
-                valid_files += 1;
-            }
-            Err(errors) => {
-                println!("  ✗ Validation failed:");
-                let mut error_messages = Vec::new();
-
-                for error in errors {
-                    let instance_path = if error.instance_path.to_string().is_empty() {
-                        "root".to_string()
-                    } else {
-                        error.instance_path.to_string()
-                    };
-                    let message = format!("    - At {}: {}", instance_path, error);
-                    println!("{}", message);
-                    error_messages.push(message);
-                }
-                println!();
+        let errors = schema_errors(&compiled_schema, &json_value);
+        if errors.is_empty() {
+            println!("  ✓ Valid\n");
+            valid_files += 1;
+        } else {
+            println!("  ✗ Validation failed:");
+            let mut error_messages = Vec::new();
 
-                invalid_files += 1;
-                error_details.push((input_path.clone(), error_messages.join("\n")));
+            for error in errors {
+                let message = format!("    - {}", error);
+                println!("{}", message);
+                error_messages.push(message);
             }
+            println!();
+
+            invalid_files += 1;
+            error_details.push((input_path.clone(), error_messages.join("\n")));
         }
     }
 
@@ -792,22 +1772,224 @@ fn validate_json_files(input_paths: &[PathBuf]) -> Result<()> {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Expand glob patterns and collect all input files
-    let input_files =
-        expand_input_patterns(&cli.input).context("Failed to expand input file patterns")?;
+    // When stdout carries the signed asset or JSON, progress messages go to stderr
+    let writes_stdout = cli.output.as_deref().is_some_and(is_stdio);
+    let reads_stdin = cli.input.iter().any(|input| input == STDIO_PATH);
+    STATUS_TO_STDERR.store(writes_stdout, Ordering::Relaxed);
+
+    // Load c2pa settings and trust configuration before any manifest is read
+    let trust_options = trust::TrustOptions {
+        settings: cli.settings.as_deref(),
+        trust_anchors: cli.trust_anchors.as_deref(),
+        allowed_list: cli.allowed_list.as_deref(),
+        trust_config: cli.trust_config.as_deref(),
+    };
+    if trust_options.is_configured() {
+        trust::apply_trust_options(&trust_options)?;
+    }
 
-    if input_files.is_empty() {
-        anyhow::bail!("No input files specified");
+    // Deterministic signing uses the local callback signer, which skips certificate checks
+    if cli.deterministic.is_some() && cli.key.is_some() && !cli.allow_self_signed {
+        status!("  Warning: --deterministic implies --allow-self-signed; the signing certificate is not validated");
     }
 
-    // Validate input files exist
-    for input_file in &input_files {
-        if !input_file.exists() {
-            anyhow::bail!("Input file does not exist: {:?}", input_file);
+    // Handle serve mode
+    if cli.serve {
+        if !cli.input.is_empty() {
+            anyhow::bail!("--serve does not take input files");
+        }
+        if cli.serve_references.is_some() {
+            anyhow::bail!("--serve-references applies to signing runs, not --serve");
+        }
+        let signing = match (cli.cert.as_deref(), cli_key_source(&cli)?) {
+            (Some(cert), Some(key)) => Some(serve::SigningSetup {
+                cert,
+                key,
+                signing_alg: resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?,
+                ocsp_response: cli_ocsp_response(&cli, cert)?,
+            }),
+            (None, None) => None,
+            _ => anyhow::bail!(
+                "--serve needs --cert and one of --key, --signer-cmd or --pkcs11-module to enable /sign"
+            ),
+        };
+        let ingredients_base_dir = cli
+            .ingredients_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        return serve::serve(&serve::ServeConfig {
+            bind: &cli.bind,
+            signing,
+            allow_self_signed: cli.allow_self_signed,
+            thumbnail_asset: cli.thumbnail_asset,
+            thumbnail_ingredients: cli.thumbnail_ingredients,
+            ingredients_base_dir: &ingredients_base_dir,
+            deterministic_seed: cli.deterministic.as_deref(),
+            identity: cli_identity(&cli),
+            strict_regions: cli.strict_regions,
+            existing: cli.existing,
+        });
+    }
+
+    // Handle matrix mode (assets and manifests come from the config)
+    if let Some(matrix_config) = &cli.matrix {
+        if !cli.input.is_empty() {
+            anyhow::bail!("--matrix takes its assets from the config, not input files");
         }
+        let output = cli
+            .output
+            .as_deref()
+            .context("--output is required when using --matrix mode")?;
+        return matrix::run_matrix(
+            matrix_config,
+            &matrix::MatrixOptions {
+                output_dir: output,
+                cert: cli.cert.as_deref(),
+                key: cli_key_source(&cli)?,
+                algorithm: cli.algorithm.as_deref(),
+                allow_self_signed: cli.allow_self_signed,
+                deterministic_seed: cli.deterministic.as_deref(),
+            },
+        );
     }
 
-    println!("Found {} input file(s) to process", input_files.len());
+    // Handle test plan mode (assets and manifests come from the plan's rows)
+    if let Some(plan) = &cli.plan {
+        if !cli.input.is_empty() {
+            anyhow::bail!("--plan takes its assets from the plan, not input files");
+        }
+        let output = cli
+            .output
+            .as_deref()
+            .context("--output is required when using --plan mode")?;
+        let cert = cli
+            .cert
+            .as_deref()
+            .context("--cert is required when using --plan mode")?;
+        let key = cli_key_source(&cli)?
+            .context("--key, --signer-cmd or --pkcs11-module is required when using --plan mode")?;
+        return testplan::run_plan(
+            plan,
+            &testplan::PlanOptions {
+                output_dir: output,
+                cert,
+                key,
+                signing_alg: resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?,
+                allow_self_signed: cli.allow_self_signed,
+                deterministic_seed: cli.deterministic.as_deref(),
+            },
+        );
+    }
+
+    // Handle fuzz mode (input files, if any, are the assets to sign onto)
+    if let Some(count) = cli.fuzz {
+        let output = cli
+            .output
+            .as_deref()
+            .context("--output is required when using --fuzz mode")?;
+        let seed = cli.deterministic.as_deref().context(
+            "--fuzz requires --deterministic <SEED>; the seed makes the corpus reproducible",
+        )?;
+        let cert = cli
+            .cert
+            .as_deref()
+            .context("--cert is required when using --fuzz mode")?;
+        let key = cli_key_source(&cli)?
+            .context("--key, --signer-cmd or --pkcs11-module is required when using --fuzz mode")?;
+        let assets =
+            expand_input_patterns(&cli.input).context("Failed to expand asset patterns")?;
+        return fuzz::run_fuzz(&fuzz::FuzzOptions {
+            output_dir: output,
+            assets: &assets,
+            count,
+            seed,
+            cert,
+            key,
+            signing_alg: resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?,
+            allow_self_signed: cli.allow_self_signed,
+        });
+    }
+
+    // Handle stress mode (the input file, if any, is the asset to sign onto)
+    if let Some(limits) = &cli.stress {
+        let output = cli
+            .output
+            .as_deref()
+            .context("--output is required when using --stress mode")?;
+        let cert = cli
+            .cert
+            .as_deref()
+            .context("--cert is required when using --stress mode")?;
+        let key = cli_key_source(&cli)?.context(
+            "--key, --signer-cmd or --pkcs11-module is required when using --stress mode",
+        )?;
+        let asset = match cli.input.as_slice() {
+            [] => Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("testfiles")
+                .join("Dog.jpg"),
+            [asset] => PathBuf::from(asset),
+            _ => anyhow::bail!(
+                "--stress signs onto a single asset, got {}",
+                cli.input.len()
+            ),
+        };
+        return stress::run_stress(&stress::StressOptions {
+            asset: &asset,
+            output_dir: output,
+            limits: stress::StressLimits::parse(limits)?,
+            cert,
+            key,
+            signing_alg: resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?,
+            allow_self_signed: cli.allow_self_signed,
+            deterministic_seed: cli.deterministic.as_deref(),
+        });
+    }
+
+    // Handle diff mode (inputs are used in the given order: left, then right)
+    if cli.diff {
+        if cli.input.len() != 2 {
+            anyhow::bail!(
+                "--diff requires exactly two inputs (signed assets or extracted JSON files), got {}",
+                cli.input.len()
+            );
+        }
+        return diff::diff_manifests(
+            Path::new(&cli.input[0]),
+            Path::new(&cli.input[1]),
+            cli.jpt,
+            cli.output.as_deref(),
+        );
+    }
+
+    // "-" (stdin/stdout) is only supported for signing and extraction
+    if (reads_stdin || writes_stdout) && (cli.validate || cli.inspect || cli.regress) {
+        anyhow::bail!("\"-\" (stdin/stdout) can only be used for signing and --extract mode");
+    }
+
+    // Expand glob patterns and collect all input files
+    let input_files = if reads_stdin {
+        if cli.input.len() > 1 {
+            anyhow::bail!("\"-\" (stdin) cannot be combined with other inputs");
+        }
+        vec![PathBuf::from(STDIO_PATH)]
+    } else {
+        let input_files =
+            expand_input_patterns(&cli.input).context("Failed to expand input file patterns")?;
+
+        if input_files.is_empty() {
+            anyhow::bail!("No input files specified");
+        }
+
+        // Validate input files exist
+        for input_file in &input_files {
+            if !input_file.exists() {
+                anyhow::bail!("Input file does not exist: {:?}", input_file);
+            }
+        }
+        input_files
+    };
+
+    status!("Found {} input file(s) to process", input_files.len());
 
     // Handle validation mode
     if cli.validate {
@@ -815,6 +1997,98 @@ fn main() -> Result<()> {
         return validate_json_files(&input_files);
     }
 
+    // Handle inspect mode
+    if cli.inspect {
+        // Output is optional; without it the dumps are printed
+        if let Some(output) = &cli.output {
+            if input_files.len() > 1 && !output.is_dir() {
+                anyhow::bail!(
+                    "Output must be a directory when inspecting multiple input files. Got: {:?}",
+                    output
+                );
+            }
+        }
+
+        let mut error_count = 0;
+        for input_file in &input_files {
+            let output_path = match &cli.output {
+                Some(output) if output.is_dir() => {
+                    let input_stem = input_file
+                        .file_stem()
+                        .and_then(|s| s.to_str())
+                        .context("Input file has no filename")?;
+                    Some(output.join(format!("{}_inspect.txt", input_stem)))
+                }
+                Some(output) => Some(output.clone()),
+                None => None,
+            };
+
+            if let Err(e) = inspect::inspect_file(input_file, output_path.as_deref()) {
+                eprintln!("Error inspecting {:?}: {}", input_file, e);
+                error_count += 1;
+            }
+        }
+
+        if error_count > 0 {
+            anyhow::bail!("{} file(s) failed to inspect", error_count);
+        }
+
+        return Ok(());
+    }
+
+    // Handle negative variant mode (input files are the manifests to mutate)
+    if cli.mutate {
+        let output = cli
+            .output
+            .context("--output is required when using --mutate mode")?;
+        return mutate::run_mutate(&input_files, &output);
+    }
+
+    // Handle regression mode
+    if cli.regress {
+        // In regression mode, input files are the manifests to sign and compare
+        let output = cli
+            .output
+            .as_deref()
+            .context("--output is required when using --regress mode")?;
+        let cert = cli
+            .cert
+            .as_deref()
+            .context("--cert is required when using --regress mode")?;
+        let key = cli_key_source(&cli)?.context(
+            "--key, --signer-cmd or --pkcs11-module is required when using --regress mode",
+        )?;
+
+        let project_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let goldens_dir = cli
+            .goldens
+            .clone()
+            .unwrap_or_else(|| project_dir.join("tests").join("goldens"));
+        let asset = cli
+            .regress_asset
+            .clone()
+            .unwrap_or_else(|| project_dir.join("testfiles").join("Dog.jpg"));
+
+        let signing_alg = resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?;
+
+        let config = regress::RegressConfig {
+            asset: &asset,
+            goldens_dir: &goldens_dir,
+            output_dir: output,
+            cert,
+            key,
+            signing_alg,
+            allow_self_signed: cli.allow_self_signed,
+            update_goldens: cli.update_goldens,
+        };
+
+        return regress::run_regression(&input_files, &config);
+    }
+
+    if cli.update_goldens {
+        anyhow::bail!("--update-goldens can only be used with --regress mode");
+    }
+
     // Handle extract mode
     if cli.extract {
         // Require output for extract mode
@@ -824,7 +2098,7 @@ fn main() -> Result<()> {
 
         // Validate --jpt can only be used with --extract
         if cli.jpt {
-            println!("Using JPEG Trust format for extraction");
+            status!("Using JPEG Trust format for extraction");
         }
 
         // Output must be a directory if processing multiple files
@@ -835,12 +2109,19 @@ fn main() -> Result<()> {
             );
         }
 
+        let extract_config = ExtractConfig {
+            use_jpt_format: cli.jpt,
+            write_summary: cli.summary,
+            resources_dir: cli.resources.as_deref(),
+            format: cli.format.as_deref(),
+        };
+
         // Process each file
         let mut success_count = 0;
         let mut error_count = 0;
 
         for input_file in &input_files {
-            match extract_manifest(input_file, &output, cli.jpt) {
+            match extract_manifest(input_file, &output, &extract_config) {
                 Ok(_) => success_count += 1,
                 Err(e) => {
                     eprintln!("Error processing {:?}: {}", input_file, e);
@@ -849,10 +2130,10 @@ fn main() -> Result<()> {
             }
         }
 
-        println!("\n=== Extraction Summary ===");
-        println!("  Successful: {}", success_count);
-        println!("  Failed: {}", error_count);
-        println!("  Total: {}", input_files.len());
+        status!("\n=== Extraction Summary ===");
+        status!("  Successful: {}", success_count);
+        status!("  Failed: {}", error_count);
+        status!("  Total: {}", input_files.len());
 
         if error_count > 0 {
             anyhow::bail!("{} file(s) failed to extract", error_count);
@@ -861,24 +2142,35 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Validate --jpt can only be used with --extract
+    if cli.summary {
+        anyhow::bail!("--summary can only be used with --extract mode");
+    }
+
+    if cli.resources.is_some() {
+        anyhow::bail!("--resources can only be used with --extract mode");
+    }
+
+    // Validate --jpt can only be used with --extract or --diff
     if cli.jpt {
-        anyhow::bail!("--jpt can only be used with --extract mode");
+        anyhow::bail!("--jpt can only be used with --extract or --diff mode");
     }
 
     // Normal signing mode - validate required arguments
     let output = cli
         .output
+        .as_deref()
         .context("--output is required when not in extract or validate mode")?;
     let manifest = cli
         .manifest
+        .as_deref()
         .context("--manifest is required when not in extract or validate mode")?;
     let cert = cli
         .cert
+        .as_deref()
         .context("--cert is required when not in extract or validate mode")?;
-    let key = cli
-        .key
-        .context("--key is required when not in extract or validate mode")?;
+    let key = cli_key_source(&cli)?.context(
+        "--key, --signer-cmd or --pkcs11-module is required when not in extract or validate mode",
+    )?;
 
     // Output must be a directory if processing multiple files
     if input_files.len() > 1 && !output.is_dir() {
@@ -890,12 +2182,12 @@ fn main() -> Result<()> {
 
     // Read and parse the JSON manifest configuration
     let manifest_json =
-        fs::read_to_string(&manifest).context("Failed to read manifest JSON file")?;
+        fs::read_to_string(manifest).context("Failed to read manifest JSON file")?;
 
     // Determine the ingredients base directory
     // Use the provided ingredients_dir, or default to the manifest's parent directory
-    let ingredients_base_dir = if let Some(ing_dir) = cli.ingredients_dir {
-        ing_dir
+    let ingredients_base_dir = if let Some(ing_dir) = &cli.ingredients_dir {
+        ing_dir.clone()
     } else {
         manifest
             .parent()
@@ -903,34 +2195,64 @@ fn main() -> Result<()> {
             .unwrap_or_else(|| PathBuf::from("."))
     };
 
-    println!("  Ingredients base directory: {:?}", ingredients_base_dir);
+    status!("  Ingredients base directory: {:?}", ingredients_base_dir);
 
     // Auto-detect or parse signing algorithm
-    let signing_alg = if let Some(alg_str) = &cli.algorithm {
-        parse_signing_algorithm(alg_str)?
-    } else {
-        println!("Auto-detecting signing algorithm from certificate...");
-        let detected = detect_signing_algorithm(&cert)?;
-        println!("  Detected: {:?}", detected);
-        detected
-    };
+    let signing_alg = resolve_signing_algorithm(cli.algorithm.as_deref(), cert)?;
 
-    println!("Creating C2PA manifest(s)...");
-    println!("  Algorithm: {:?}", signing_alg);
+    status!("Creating C2PA manifest(s)...");
+    status!("  Algorithm: {:?}", signing_alg);
     if cli.allow_self_signed {
-        println!("  Note: Allowing self-signed certificates (development mode)");
+        status!("  Note: Allowing self-signed certificates (development mode)");
+    }
+    match key {
+        KeySource::Command(command) => status!("  External signer: {}", command),
+        KeySource::Pkcs11(pkcs11_key) => status!(
+            "  PKCS#11 key: {:?} ({:?})",
+            pkcs11_key.label,
+            pkcs11_key.module
+        ),
+        KeySource::File(_) => {}
+    }
+    if let Some(seed) = &cli.deterministic {
+        status!("  Deterministic mode (seed: {})", seed);
+        // A --key file signs PS* with deterministic PKCS#1 v1.5; other signers produce real PSS
+        let external = matches!(key, KeySource::Command(_) | KeySource::Pkcs11(_));
+        if external
+            && matches!(
+                signing_alg,
+                SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512
+            )
+        {
+            status!("  Warning: RSA-PSS signatures are randomized; signature bytes will differ between runs");
+        }
     }
 
+    // Fetch or generate the OCSP response once for all files
+    let ocsp_response = cli_ocsp_response(&cli, cert)?;
+
+    // Bind the reference stand-in first so signed manifests can point at its address
+    let reference_server = match &cli.serve_references {
+        Some(addr) => Some(references::bind(addr)?),
+        None => None,
+    };
+
     // Create processing configuration
     let config = ProcessingConfig {
         manifest_json: &manifest_json,
         ingredients_base_dir: &ingredients_base_dir,
-        cert: &cert,
-        key: &key,
+        cert,
+        key,
         signing_alg,
         allow_self_signed: cli.allow_self_signed,
         thumbnail_asset: cli.thumbnail_asset,
         thumbnail_ingredients: cli.thumbnail_ingredients,
+        deterministic_seed: cli.deterministic.as_deref(),
+        ocsp_response: ocsp_response.as_deref(),
+        identity: cli_identity(&cli),
+        strict_regions: cli.strict_regions,
+        existing: cli.existing,
+        reference_base_url: reference_server.as_ref().map(|(_, url)| url.as_str()),
     };
 
     // Process each input file
@@ -938,24 +2260,34 @@ fn main() -> Result<()> {
     let mut error_count = 0;
 
     for input_file in &input_files {
-        match process_single_file(input_file, &output, &config) {
+        let result = if is_stdio(input_file) || is_stdio(output) {
+            process_stdio(input_file, output, cli.format.as_deref(), &config)
+        } else {
+            process_single_file(input_file, output, &config)
+        };
+        match result {
             Ok(_) => success_count += 1,
             Err(e) => {
-                eprintln!("Error processing {:?}: {}", input_file, e);
+                eprintln!("Error processing {:?}: {:#}", input_file, e);
                 error_count += 1;
             }
         }
     }
 
-    println!("\n=== Processing Summary ===");
-    println!("  Successful: {}", success_count);
-    println!("  Failed: {}", error_count);
-    println!("  Total: {}", input_files.len());
+    status!("\n=== Processing Summary ===");
+    status!("  Successful: {}", success_count);
+    status!("  Failed: {}", error_count);
+    status!("  Total: {}", input_files.len());
 
     if error_count > 0 {
         anyhow::bail!("{} file(s) failed to process", error_count);
     }
 
+    if let Some((server, base_url)) = reference_server {
+        let files = references::referenced_files(&manifest_json, &ingredients_base_dir)?;
+        return references::serve_files(server, &base_url, &files);
+    }
+
     Ok(())
 }
 
@@ -996,6 +2328,45 @@ mod tests {
         assert!(parse_signing_algorithm("invalid").is_err());
     }
 
+    #[test]
+    fn test_resolve_format() {
+        // --format takes precedence and accepts extensions or MIME types
+        assert_eq!(
+            resolve_format(Some("jpg"), Path::new("-")).unwrap(),
+            "image/jpeg"
+        );
+        assert_eq!(
+            resolve_format(Some("image/png"), Path::new("a.jpg")).unwrap(),
+            "image/png"
+        );
+        assert_eq!(
+            resolve_format(None, Path::new("photo.PNG")).unwrap(),
+            "image/png"
+        );
+
+        // stdin/stdout has no extension to fall back on
+        assert!(resolve_format(None, Path::new("-")).is_err());
+        assert!(resolve_format(Some("xyz"), Path::new("-")).is_err());
+    }
+
+    #[test]
+    fn test_ecdsa_der_to_raw() {
+        // SEQUENCE { INTEGER 0x00 0x80 0x01 (leading zero), INTEGER 0x05 }
+        let der = [0x30, 0x08, 0x02, 0x03, 0x00, 0x80, 0x01, 0x02, 0x01, 0x05];
+        let raw = ecdsa_der_to_raw(&der, 32).unwrap();
+        assert_eq!(raw.len(), 64);
+        assert_eq!(&raw[30..32], &[0x80, 0x01]);
+        assert_eq!(raw[63], 0x05);
+        assert!(raw[..30].iter().all(|&b| b == 0));
+
+        // Raw signatures are not DER and are left alone
+        assert!(ecdsa_der_to_raw(&[0xAB; 64], 32).is_none());
+        // Trailing bytes after the sequence are rejected
+        assert!(
+            ecdsa_der_to_raw(&[0x30, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01, 0x00], 32).is_none()
+        );
+    }
+
     #[test]
     fn test_validate_json_files_with_valid_manifest() {
         // Test with a valid example manifest
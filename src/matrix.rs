@@ -0,0 +1,525 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Test matrix expansion (`--matrix`).
+//!
+//! A JSON config lists input assets (paths or glob patterns), manifest files, signers
+//! (name, algorithm, certificate and key) and output formats. Every combination is signed into
+//! the output directory under a naming template, and the results are summarized on the
+//! console and in `matrix_report.json`. Relative paths are resolved against the directory
+//! of the config file.
+
+use anyhow::{Context, Result};
+use c2pa::{Reader, SigningAlg};
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::{KeySource, ProcessingConfig};
+
+/// Output file name used when the config has no `name`
+const DEFAULT_NAME_TEMPLATE: &str = "{stem}-{manifest}-{signer}.{ext}";
+
+/// Report written into the output directory
+const REPORT_FILE: &str = "matrix_report.json";
+
+fn default_name_template() -> String {
+    DEFAULT_NAME_TEMPLATE.to_string()
+}
+
+/// A signer entry of the config
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SignerEntry {
+    /// Name for the {signer} placeholder and the report; the algorithm name when absent
+    name: Option<String>,
+    /// Signing algorithm; detected from the certificate when absent
+    alg: Option<String>,
+    cert: PathBuf,
+    key: PathBuf,
+}
+
+/// The matrix config file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MatrixFile {
+    /// Input assets, as paths or glob patterns
+    assets: Vec<String>,
+    /// Manifest files, as paths or glob patterns
+    manifests: Vec<String>,
+    /// Signers; the command line --cert/--key/--algorithm when empty
+    #[serde(default)]
+    algorithms: Vec<SignerEntry>,
+    /// Output file extensions; each asset keeps its own format when empty
+    #[serde(default)]
+    formats: Vec<String>,
+    /// Output file name template with {stem}, {manifest}, {signer}, {alg} and {ext}
+    #[serde(default = "default_name_template")]
+    name: String,
+}
+
+/// Private key of a resolved signer
+enum MatrixKey<'a> {
+    /// Key file listed in the config
+    File(PathBuf),
+    /// The command line key source (--key, --signer-cmd or --pkcs11-module)
+    Cli(KeySource<'a>),
+}
+
+/// A resolved signer
+struct MatrixSigner<'a> {
+    name: String,
+    alg: SigningAlg,
+    cert: PathBuf,
+    key: MatrixKey<'a>,
+}
+
+impl MatrixSigner<'_> {
+    /// Algorithm name for the {alg} placeholder and the report
+    fn alg_name(&self) -> String {
+        self.alg.to_string().to_lowercase()
+    }
+}
+
+/// One asset x manifest x signer x format combination
+#[derive(Debug)]
+struct Combination {
+    asset: PathBuf,
+    manifest: PathBuf,
+    signer: usize,
+    ext: String,
+    output: PathBuf,
+}
+
+/// Outcome of one combination in the report
+#[derive(Serialize)]
+struct MatrixResult<'a> {
+    asset: &'a Path,
+    manifest: &'a Path,
+    signer: &'a str,
+    alg: String,
+    format: &'a str,
+    output: &'a Path,
+    signed: bool,
+    /// Validation state of the signed output as read back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Command line options that apply to every combination
+pub struct MatrixOptions<'a> {
+    pub output_dir: &'a Path,
+    /// Signer used when the config lists no algorithms
+    pub cert: Option<&'a Path>,
+    pub key: Option<KeySource<'a>>,
+    pub algorithm: Option<&'a str>,
+    pub allow_self_signed: bool,
+    pub deterministic_seed: Option<&'a str>,
+}
+
+/// Resolve a path from the config against the config's directory
+fn resolve(base_dir: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// Expand the config's paths and glob patterns
+fn expand(base_dir: &Path, patterns: &[String], what: &str) -> Result<Vec<PathBuf>> {
+    if patterns.is_empty() {
+        anyhow::bail!("The matrix config lists no {}", what);
+    }
+    let patterns: Vec<String> = patterns
+        .iter()
+        .map(|pattern| {
+            resolve(base_dir, Path::new(pattern))
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    crate::expand_input_patterns(&patterns).context(format!("Failed to expand matrix {}", what))
+}
+
+/// Output file name for a combination
+fn output_name(
+    template: &str,
+    stem: &str,
+    manifest: &str,
+    signer: &MatrixSigner,
+    ext: &str,
+) -> String {
+    template
+        .replace("{stem}", stem)
+        .replace("{manifest}", manifest)
+        .replace("{signer}", &signer.name)
+        .replace("{alg}", &signer.alg_name())
+        .replace("{ext}", ext)
+}
+
+fn file_stem(path: &Path) -> Result<&str> {
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .context(format!("Path has no file name: {:?}", path))
+}
+
+/// Every combination, in asset, manifest, signer, format order. Fails if two
+/// combinations would write the same output file.
+fn combinations(
+    matrix: &MatrixFile,
+    assets: &[PathBuf],
+    manifests: &[PathBuf],
+    signers: &[MatrixSigner],
+    output_dir: &Path,
+) -> Result<Vec<Combination>> {
+    let mut combinations = Vec::new();
+    let mut names = HashSet::new();
+    for asset in assets {
+        let asset_ext = asset
+            .extension()
+            .and_then(|s| s.to_str())
+            .context(format!("Asset has no extension: {:?}", asset))?;
+        let formats = if matrix.formats.is_empty() {
+            vec![asset_ext.to_string()]
+        } else {
+            matrix.formats.clone()
+        };
+        for manifest in manifests {
+            for (signer, signer_entry) in signers.iter().enumerate() {
+                for ext in &formats {
+                    let name = output_name(
+                        &matrix.name,
+                        file_stem(asset)?,
+                        file_stem(manifest)?,
+                        signer_entry,
+                        ext,
+                    );
+                    if !names.insert(name.clone()) {
+                        anyhow::bail!(
+                            "Several combinations are named {:?}; add the placeholders that \
+                             tell them apart to the name template {:?}",
+                            name,
+                            matrix.name
+                        );
+                    }
+                    combinations.push(Combination {
+                        asset: asset.clone(),
+                        manifest: manifest.clone(),
+                        signer,
+                        ext: ext.clone(),
+                        output: output_dir.join(name),
+                    });
+                }
+            }
+        }
+    }
+    Ok(combinations)
+}
+
+/// Re-encode an image in another format
+fn convert(data: &[u8], from: &str, to: &str) -> Result<Vec<u8>> {
+    let (Some(from_format), Some(to_format)) = (crate::image_format(from), crate::image_format(to))
+    else {
+        anyhow::bail!(
+            "Cannot convert {} to {}; conversions are between JPEG, PNG, GIF, BMP, TIFF and WebP",
+            from,
+            to
+        );
+    };
+    let mut img = image::load_from_memory_with_format(data, from_format)
+        .context("Failed to decode asset for conversion")?;
+    // JPEG has no alpha channel
+    if to_format == ImageFormat::Jpeg {
+        img = DynamicImage::ImageRgb8(img.to_rgb8());
+    }
+    let mut converted = Cursor::new(Vec::new());
+    img.write_to(&mut converted, to_format)
+        .context("Failed to encode converted asset")?;
+    Ok(converted.into_inner())
+}
+
+/// MIME type of a file extension
+fn mime(ext: &str) -> Result<&'static str> {
+    crate::extension_to_mime(ext).context(format!("Unsupported format: {}", ext))
+}
+
+/// Sign one combination, converting the asset first when the output format differs
+fn sign_combination(
+    combination: &Combination,
+    signer: &MatrixSigner,
+    options: &MatrixOptions,
+) -> Result<()> {
+    let manifest_json = fs::read_to_string(&combination.manifest).context(format!(
+        "Failed to read manifest: {:?}",
+        combination.manifest
+    ))?;
+    let config = ProcessingConfig {
+        allow_self_signed: options.allow_self_signed,
+        deterministic_seed: options.deterministic_seed,
+        ..ProcessingConfig::new(
+            &manifest_json,
+            combination.manifest.parent().unwrap_or(Path::new(".")),
+            &signer.cert,
+            match &signer.key {
+                MatrixKey::File(key) => KeySource::File(key),
+                MatrixKey::Cli(key) => *key,
+            },
+            signer.alg,
+        )
+    };
+
+    let asset_ext = combination
+        .asset
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let (from, to) = (mime(asset_ext)?, mime(&combination.ext)?);
+    if from == to {
+        return crate::process_single_file(&combination.asset, &combination.output, &config);
+    }
+
+    status!(
+        "\n=== Processing: {:?} as {} ===",
+        combination.asset,
+        combination.ext
+    );
+    let data = fs::read(&combination.asset)
+        .context(format!("Failed to read asset: {:?}", combination.asset))?;
+    let context_name = format!("{}.{}", file_stem(&combination.asset)?, combination.ext);
+    let signed = crate::sign_asset_bytes(&config, to, &context_name, convert(&data, from, to)?)?;
+    fs::write(&combination.output, signed)
+        .context(format!("Failed to write {:?}", combination.output))
+}
+
+/// Resolve the config's signers, or the command line signer
+fn signers<'a>(
+    matrix: &MatrixFile,
+    base_dir: &Path,
+    options: &MatrixOptions<'a>,
+) -> Result<Vec<MatrixSigner<'a>>> {
+    let entries: Vec<(Option<&str>, Option<&str>, PathBuf, MatrixKey)> =
+        if matrix.algorithms.is_empty() {
+            let (Some(cert), Some(key)) = (options.cert, options.key) else {
+                anyhow::bail!(
+                    "The matrix config lists no algorithms, so --cert and one of --key, \
+                     --signer-cmd or --pkcs11-module are required"
+                );
+            };
+            vec![(
+                None,
+                options.algorithm,
+                cert.to_path_buf(),
+                MatrixKey::Cli(key),
+            )]
+        } else {
+            matrix
+                .algorithms
+                .iter()
+                .map(|entry| {
+                    (
+                        entry.name.as_deref(),
+                        entry.alg.as_deref(),
+                        resolve(base_dir, &entry.cert),
+                        MatrixKey::File(resolve(base_dir, &entry.key)),
+                    )
+                })
+                .collect()
+        };
+
+    entries
+        .into_iter()
+        .map(|(name, alg, cert, key)| {
+            let alg = crate::resolve_signing_algorithm(alg, &cert)?;
+            Ok(MatrixSigner {
+                name: name.map_or_else(|| alg.to_string().to_lowercase(), str::to_string),
+                alg,
+                cert,
+                key,
+            })
+        })
+        .collect()
+}
+
+/// Sign every combination of the matrix config and write the report
+pub fn run_matrix(config_path: &Path, options: &MatrixOptions) -> Result<()> {
+    let matrix: MatrixFile = serde_json::from_str(
+        &fs::read_to_string(config_path)
+            .context(format!("Failed to read matrix config: {:?}", config_path))?,
+    )
+    .context(format!("Invalid matrix config: {:?}", config_path))?;
+    let base_dir = config_path.parent().unwrap_or(Path::new("."));
+
+    let assets = expand(base_dir, &matrix.assets, "assets")?;
+    let manifests = expand(base_dir, &matrix.manifests, "manifests")?;
+    let signers = signers(&matrix, base_dir, options)?;
+    fs::create_dir_all(options.output_dir).context("Failed to create output directory")?;
+    let combinations = combinations(&matrix, &assets, &manifests, &signers, options.output_dir)?;
+
+    println!("=== Test matrix ===");
+    println!("  Assets: {}", assets.len());
+    println!("  Manifests: {}", manifests.len());
+    let names: Vec<&str> = signers.iter().map(|s| s.name.as_str()).collect();
+    println!("  Signers: {}", names.join(", "));
+    if !matrix.formats.is_empty() {
+        println!("  Formats: {}", matrix.formats.join(", "));
+    }
+    println!("  Combinations: {}", combinations.len());
+
+    let mut outcomes = Vec::new();
+    for combination in &combinations {
+        let signer = &signers[combination.signer];
+        let outcome = sign_combination(combination, signer, options).and_then(|()| {
+            let reader = Reader::from_file(&combination.output)
+                .context("Failed to read back the signed output")?;
+            Ok(format!("{:?}", reader.validation_state()))
+        });
+        outcomes.push(outcome);
+    }
+
+    println!("\n=== Matrix Summary ===");
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for (combination, outcome) in combinations.iter().zip(&outcomes) {
+        let name = combination.output.file_name().unwrap_or_default();
+        let (validation_state, error) = match outcome {
+            Ok(state) => {
+                println!("  ✓ {} ({})", name.to_string_lossy(), state);
+                (Some(state.clone()), None)
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}: {:#}", name.to_string_lossy(), e);
+                (None, Some(format!("{:#}", e)))
+            }
+        };
+        results.push(MatrixResult {
+            asset: &combination.asset,
+            manifest: &combination.manifest,
+            signer: &signers[combination.signer].name,
+            alg: signers[combination.signer].alg_name(),
+            format: &combination.ext,
+            output: &combination.output,
+            signed: error.is_none(),
+            validation_state,
+            error,
+        });
+    }
+    println!("  Signed: {}", combinations.len() - failed);
+    println!("  Failed: {}", failed);
+    println!("  Total: {}", combinations.len());
+
+    let report_path = options.output_dir.join(REPORT_FILE);
+    let report = serde_json::to_string_pretty(&results).context("Failed to format report")?;
+    fs::write(&report_path, report + "\n").context("Failed to write matrix report")?;
+    println!("  Report: {:?}", report_path);
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} of {} combination(s) failed to sign",
+            failed,
+            combinations.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signer(name: &str) -> MatrixSigner<'static> {
+        MatrixSigner {
+            name: name.to_string(),
+            alg: SigningAlg::Ed25519,
+            cert: PathBuf::from("cert.pem"),
+            key: MatrixKey::File(PathBuf::from("key.pem")),
+        }
+    }
+
+    fn matrix(json: serde_json::Value) -> MatrixFile {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_combinations_and_names() -> Result<()> {
+        let config = matrix(serde_json::json!({
+            "assets": ["*.jpg"],
+            "manifests": ["*.json"],
+            "formats": ["jpg", "png"],
+        }));
+        let assets = [PathBuf::from("a/Dog.jpg"), PathBuf::from("a/Cat.jpeg")];
+        let manifests = [PathBuf::from("m/simple.json")];
+        let signers = [signer("ed25519"), signer("es256")];
+
+        let all = combinations(&config, &assets, &manifests, &signers, Path::new("out"))?;
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[0].output, Path::new("out/Dog-simple-ed25519.jpg"));
+        assert_eq!(all[1].output, Path::new("out/Dog-simple-ed25519.png"));
+        assert_eq!(all[3].output, Path::new("out/Dog-simple-es256.png"));
+        assert_eq!(all[7].output, Path::new("out/Cat-simple-es256.png"));
+
+        // Without formats each asset keeps its own extension
+        let config = matrix(serde_json::json!({ "assets": [], "manifests": [] }));
+        let all = combinations(
+            &config,
+            &assets[1..],
+            &manifests,
+            &signers,
+            Path::new("out"),
+        )?;
+        assert_eq!(all[0].output, Path::new("out/Cat-simple-ed25519.jpeg"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_name_template_must_tell_combinations_apart() {
+        let config = matrix(serde_json::json!({
+            "assets": [], "manifests": [], "name": "{stem}-{manifest}.{ext}"
+        }));
+        let assets = [PathBuf::from("Dog.jpg")];
+        let manifests = [PathBuf::from("simple.json")];
+        let signers = [signer("ed25519"), signer("es256")];
+        let error = combinations(&config, &assets, &manifests, &signers, Path::new("out"))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Dog-simple.jpg"), "{}", error);
+    }
+
+    #[test]
+    fn test_convert_between_formats() -> Result<()> {
+        let png = fs::read("testfiles/Dog.png")?;
+        let jpeg = convert(&png, "image/png", "image/jpeg")?;
+        assert_eq!(image::guess_format(&jpeg)?, ImageFormat::Jpeg);
+        assert!(convert(&png, "image/png", "video/mp4").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_signer_and_alg_placeholders() {
+        let config = matrix(serde_json::json!({
+            "assets": [], "manifests": [], "name": "{stem}-{signer}-{alg}.{ext}"
+        }));
+        let assets = [PathBuf::from("Dog.jpg")];
+        let manifests = [PathBuf::from("simple.json")];
+        // Two signers with the same algorithm are told apart by name
+        let signers = [signer("test-ca"), signer("expired")];
+        let all = combinations(&config, &assets, &manifests, &signers, Path::new("out")).unwrap();
+        assert_eq!(all[0].output, Path::new("out/Dog-test-ca-ed25519.jpg"));
+        assert_eq!(all[1].output, Path::new("out/Dog-expired-ed25519.jpg"));
+    }
+}
@@ -0,0 +1,681 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Negative variants of positive manifests (`--mutate`).
+//!
+//! Each mutation rule breaks one thing in a copy of a `p-*` manifest: it drops a required
+//! field, combines fields that exclude each other, reorders actions or points references at
+//! nothing. A rule that applies to several actions gives one variant per action. The
+//! variants are written as `n-*` manifests next to a `variants.csv` test plan that records
+//! what each one breaks, so they can be signed with `--plan`.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A rule gives the description and manifest of each variant it makes
+type Mutation = fn(&JsonValue) -> Vec<(String, JsonValue)>;
+
+/// Mutation rules by name; the name ends the variant's test ID
+const RULES: &[(&str, Mutation)] = &[
+    ("no-dst", no_digital_source_type),
+    ("agent-and-index", agent_and_index),
+    ("agent-index-out-of-range", agent_index_out_of_range),
+    ("bad-reason", bad_redaction_reason),
+    ("no-reason", no_redaction_reason),
+    ("missing-ingredient", missing_ingredient),
+    ("no-ingredient-ids", no_ingredient_ids),
+    ("no-parameters", no_parameters),
+    ("no-ingredients", no_ingredients),
+    ("reorder", reorder_actions),
+    ("duplicate-inception", duplicate_inception),
+    ("no-soft-binding", no_soft_binding),
+    ("soft-binding-no-alg", |m| soft_binding_without(m, "alg")),
+    ("soft-binding-no-blocks", |m| {
+        soft_binding_without(m, "blocks")
+    }),
+];
+
+/// Actions that start a manifest's history
+const INCEPTION_ACTIONS: &[&str] = &["c2pa.created", "c2pa.opened"];
+
+/// A redaction reason that is not in the specification's list
+const INVALID_REASON: &str = "c2pa.not-a-reason";
+
+fn is_actions(assertion: &JsonValue) -> bool {
+    matches!(
+        assertion.get("label").and_then(|v| v.as_str()),
+        Some("c2pa.actions" | "c2pa.actions.v2")
+    )
+}
+
+fn action_name(action: &JsonValue) -> &str {
+    action
+        .get("action")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unnamed")
+}
+
+fn ingredient_ids(action: &JsonValue) -> Option<&Vec<JsonValue>> {
+    action
+        .pointer("/parameters/ingredientIds")
+        .and_then(|v| v.as_array())
+        .filter(|ids| !ids.is_empty())
+}
+
+/// Apply `mutate` to a copy of the manifest for every action matching `applies`. The closure
+/// gets the actions assertion's data and the action's index, and returns the description.
+fn per_action(
+    manifest: &JsonValue,
+    applies: impl Fn(&JsonValue) -> bool,
+    mutate: impl Fn(&mut JsonValue, usize) -> String,
+) -> Vec<(String, JsonValue)> {
+    let mut variants = Vec::new();
+    let Some(assertions) = manifest.get("assertions").and_then(|v| v.as_array()) else {
+        return variants;
+    };
+    for (a, assertion) in assertions.iter().enumerate() {
+        if !is_actions(assertion) {
+            continue;
+        }
+        let Some(actions) = assertion
+            .pointer("/data/actions")
+            .and_then(|v| v.as_array())
+        else {
+            continue;
+        };
+        for (i, action) in actions.iter().enumerate() {
+            if !applies(action) {
+                continue;
+            }
+            let mut variant = manifest.clone();
+            let data = &mut variant["assertions"][a]["data"];
+            let description = mutate(data, i);
+            variants.push((description, variant));
+        }
+    }
+    variants
+}
+
+/// Apply `mutate` to a copy of the manifest for every actions assertion matching `applies`
+fn per_actions_list(
+    manifest: &JsonValue,
+    applies: impl Fn(&[JsonValue]) -> bool,
+    mutate: impl Fn(&mut Vec<JsonValue>) -> String,
+) -> Vec<(String, JsonValue)> {
+    let mut variants = Vec::new();
+    let Some(assertions) = manifest.get("assertions").and_then(|v| v.as_array()) else {
+        return variants;
+    };
+    for (a, assertion) in assertions.iter().enumerate() {
+        let Some(actions) = assertion
+            .pointer("/data/actions")
+            .and_then(|v| v.as_array())
+        else {
+            continue;
+        };
+        if !is_actions(assertion) || !applies(actions) {
+            continue;
+        }
+        let mut variant = manifest.clone();
+        if let Some(actions) = variant["assertions"][a]["data"]["actions"].as_array_mut() {
+            let description = mutate(actions);
+            variants.push((description, variant));
+        }
+    }
+    variants
+}
+
+fn no_digital_source_type(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| action_name(action) == "c2pa.created" && action.get("digitalSourceType").is_some(),
+        |data, i| {
+            data["actions"][i]
+                .as_object_mut()
+                .map(|a| a.remove("digitalSourceType"));
+            "c2pa.created action without its digitalSourceType".to_string()
+        },
+    )
+}
+
+fn agent_and_index(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| {
+            action.get("softwareAgent").is_some() != action.get("softwareAgentIndex").is_some()
+        },
+        |data, i| {
+            let action = data["actions"][i].clone();
+            if let Some(index) = action.get("softwareAgentIndex") {
+                let agent = index
+                    .as_u64()
+                    .and_then(|k| data.get("softwareAgents")?.get(k as usize))
+                    .cloned()
+                    .unwrap_or_else(|| json!({ "name": "c2pa-testfile-maker" }));
+                data["actions"][i]["softwareAgent"] = agent;
+            } else {
+                let agents = data.as_object_mut().and_then(|d| {
+                    d.entry("softwareAgents")
+                        .or_insert_with(|| json!([]))
+                        .as_array_mut()
+                });
+                if let Some(agents) = agents {
+                    agents.push(action["softwareAgent"].clone());
+                    let index = agents.len() - 1;
+                    data["actions"][i]["softwareAgentIndex"] = json!(index);
+                }
+            }
+            format!(
+                "{} action with both softwareAgent and softwareAgentIndex",
+                action_name(&action)
+            )
+        },
+    )
+}
+
+fn agent_index_out_of_range(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| action.get("softwareAgentIndex").is_some(),
+        |data, i| {
+            let count = data
+                .get("softwareAgents")
+                .and_then(|v| v.as_array())
+                .map_or(0, Vec::len);
+            data["actions"][i]["softwareAgentIndex"] = json!(count);
+            format!(
+                "{} action with softwareAgentIndex {} past the end of softwareAgents",
+                action_name(&data["actions"][i]),
+                count
+            )
+        },
+    )
+}
+
+fn bad_redaction_reason(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| action_name(action) == "c2pa.redacted",
+        |data, i| {
+            data["actions"][i]["reason"] = json!(INVALID_REASON);
+            format!(
+                "c2pa.redacted action with the invalid reason {}",
+                INVALID_REASON
+            )
+        },
+    )
+}
+
+fn no_redaction_reason(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| action_name(action) == "c2pa.redacted" && action.get("reason").is_some(),
+        |data, i| {
+            data["actions"][i]
+                .as_object_mut()
+                .map(|a| a.remove("reason"));
+            "c2pa.redacted action without its reason".to_string()
+        },
+    )
+}
+
+fn missing_ingredient(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| ingredient_ids(action).is_some(),
+        |data, i| {
+            let action = &mut data["actions"][i];
+            if let Some(ids) = action
+                .pointer_mut("/parameters/ingredientIds")
+                .and_then(|v| v.as_array_mut())
+            {
+                for id in ids.iter_mut() {
+                    *id = json!(format!("missing-{}", id.as_str().unwrap_or_default()));
+                }
+            }
+            format!(
+                "{} action whose ingredientIds name no ingredient",
+                action_name(action)
+            )
+        },
+    )
+}
+
+fn no_ingredient_ids(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_action(
+        manifest,
+        |action| ingredient_ids(action).is_some(),
+        |data, i| {
+            let action = &mut data["actions"][i];
+            action["parameters"]
+                .as_object_mut()
+                .map(|p| p.remove("ingredientIds"));
+            format!("{} action without its ingredientIds", action_name(action))
+        },
+    )
+}
+
+fn no_parameters(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    // Parameters holding only ingredientIds are covered by no-ingredient-ids
+    per_action(
+        manifest,
+        |action| {
+            action
+                .get("parameters")
+                .and_then(|v| v.as_object())
+                .is_some_and(|p| p.keys().any(|k| k != "ingredientIds"))
+        },
+        |data, i| {
+            let action = &mut data["actions"][i];
+            action.as_object_mut().map(|a| a.remove("parameters"));
+            format!("{} action without its parameters", action_name(action))
+        },
+    )
+}
+
+fn no_ingredients(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    let referenced = manifest
+        .get("assertions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|a| is_actions(a))
+        .filter_map(|a| a.pointer("/data/actions").and_then(|v| v.as_array()))
+        .flatten()
+        .any(|action| ingredient_ids(action).is_some());
+    let has_ingredients = ["ingredients", "ingredients_from_files"].iter().any(|key| {
+        manifest
+            .get(*key)
+            .and_then(|v| v.as_array())
+            .is_some_and(|list| !list.is_empty())
+    });
+    if !referenced || !has_ingredients {
+        return Vec::new();
+    }
+    let mut variant = manifest.clone();
+    if let Some(object) = variant.as_object_mut() {
+        object.remove("ingredients");
+        object.remove("ingredients_from_files");
+    }
+    vec![(
+        "Actions reference ingredients that were removed from the manifest".to_string(),
+        variant,
+    )]
+}
+
+fn reorder_actions(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_actions_list(
+        manifest,
+        |actions| actions.len() > 1 && INCEPTION_ACTIONS.contains(&action_name(&actions[0])),
+        |actions| {
+            let first = actions.remove(0);
+            let description = format!(
+                "{} moved from the first to the last action",
+                action_name(&first)
+            );
+            actions.push(first);
+            description
+        },
+    )
+}
+
+fn duplicate_inception(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    per_actions_list(
+        manifest,
+        |actions| {
+            actions
+                .first()
+                .is_some_and(|a| INCEPTION_ACTIONS.contains(&action_name(a)))
+        },
+        |actions| {
+            let first = actions[0].clone();
+            let description = format!(
+                "{} action repeated as the second action",
+                action_name(&first)
+            );
+            actions.insert(1, first);
+            description
+        },
+    )
+}
+
+fn soft_binding_indexes(manifest: &JsonValue) -> Vec<usize> {
+    manifest
+        .get("assertions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter(|(_, a)| a.get("label").and_then(|v| v.as_str()) == Some("c2pa.soft-binding"))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn no_soft_binding(manifest: &JsonValue) -> Vec<(String, JsonValue)> {
+    let bound = manifest
+        .get("assertions")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter(|a| is_actions(a))
+        .filter_map(|a| a.pointer("/data/actions").and_then(|v| v.as_array()))
+        .flatten()
+        .any(|action| action_name(action) == "c2pa.watermarked.bound");
+    let indexes = soft_binding_indexes(manifest);
+    if !bound || indexes.is_empty() {
+        return Vec::new();
+    }
+    let mut variant = manifest.clone();
+    if let Some(assertions) = variant["assertions"].as_array_mut() {
+        for i in indexes.into_iter().rev() {
+            assertions.remove(i);
+        }
+    }
+    vec![(
+        "c2pa.watermarked.bound action without a c2pa.soft-binding assertion".to_string(),
+        variant,
+    )]
+}
+
+fn soft_binding_without(manifest: &JsonValue, field: &str) -> Vec<(String, JsonValue)> {
+    soft_binding_indexes(manifest)
+        .into_iter()
+        .filter(|&i| manifest["assertions"][i]["data"].get(field).is_some())
+        .map(|i| {
+            let mut variant = manifest.clone();
+            variant["assertions"][i]["data"]
+                .as_object_mut()
+                .map(|d| d.remove(field));
+            (
+                format!("c2pa.soft-binding assertion without its {} field", field),
+                variant,
+            )
+        })
+        .collect()
+}
+
+/// A negative variant of a positive manifest
+#[derive(Debug, Serialize)]
+pub struct Variant {
+    #[serde(rename = "Test ID")]
+    pub id: String,
+    #[serde(rename = "Manifest")]
+    pub manifest_file: String,
+    #[serde(rename = "Expected Result")]
+    pub expected: &'static str,
+    #[serde(rename = "Source")]
+    pub source: String,
+    #[serde(rename = "Rule")]
+    pub rule: &'static str,
+    #[serde(rename = "Notes")]
+    pub description: String,
+    #[serde(skip)]
+    pub manifest: JsonValue,
+}
+
+/// All variants of a manifest, with test IDs built from `stem`
+fn variants_of(manifest: &JsonValue, stem: &str, source: &str) -> Vec<Variant> {
+    let base = stem.strip_prefix("p-").unwrap_or(stem);
+    let mut variants = Vec::new();
+    for (rule, mutation) in RULES {
+        let made = mutation(manifest);
+        let numbered = made.len() > 1;
+        for (n, (description, mut variant)) in made.into_iter().enumerate() {
+            let id = if numbered {
+                format!("n-{}-{}-{}", base, rule, n + 1)
+            } else {
+                format!("n-{}-{}", base, rule)
+            };
+            if variant.get("title").is_some() {
+                variant["title"] = json!(id);
+            }
+            variants.push(Variant {
+                manifest_file: format!("{}.json", id),
+                id,
+                expected: "NEGATIVE",
+                source: source.to_string(),
+                rule,
+                description,
+                manifest: variant,
+            });
+        }
+    }
+    variants
+}
+
+/// Make ingredient file paths absolute, so the variants can be written to another directory
+fn absolute_ingredient_paths(manifest: &mut JsonValue, base_dir: &Path) {
+    let Some(ingredients) = manifest
+        .get_mut("ingredients_from_files")
+        .and_then(|v| v.as_array_mut())
+    else {
+        return;
+    };
+    for ingredient in ingredients {
+        if let Some(path) = ingredient.get("file_path").and_then(|v| v.as_str()) {
+            if Path::new(path).is_relative() {
+                let path = base_dir.join(path);
+                let path = fs::canonicalize(&path).unwrap_or(path);
+                ingredient["file_path"] = json!(path.display().to_string());
+            }
+        }
+    }
+}
+
+/// Manifest JSON with the four-space indentation of the testset files
+fn to_pretty_json(value: &JsonValue) -> Result<String> {
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(out)?)
+}
+
+/// Write the negative variants of every manifest and a `variants.csv` test plan
+pub fn run_mutate(manifests: &[PathBuf], output_dir: &Path) -> Result<()> {
+    fs::create_dir_all(output_dir).context("Failed to create output directory")?;
+    let mut all = Vec::new();
+    let mut ids = HashSet::new();
+
+    for path in manifests {
+        let json =
+            fs::read_to_string(path).context(format!("Failed to read manifest: {:?}", path))?;
+        let mut manifest: JsonValue =
+            serde_json::from_str(&json).context(format!("Failed to parse manifest: {:?}", path))?;
+        absolute_ingredient_paths(&mut manifest, path.parent().unwrap_or(Path::new(".")));
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .context(format!("Invalid manifest file name: {:?}", path))?;
+        let source = path.file_name().and_then(|s| s.to_str()).unwrap_or(stem);
+
+        println!("=== Variants of {} ===", source);
+        let variants = variants_of(&manifest, stem, source);
+        if variants.is_empty() {
+            println!("  ⚠ No mutation rule applies");
+        }
+        for variant in variants {
+            if !ids.insert(variant.id.clone()) {
+                anyhow::bail!(
+                    "Two manifests make the variant {}; rename one of them",
+                    variant.id
+                );
+            }
+            let output = output_dir.join(&variant.manifest_file);
+            fs::write(&output, to_pretty_json(&variant.manifest)? + "\n")
+                .context(format!("Failed to write {:?}", output))?;
+            println!("  ✓ {}: {}", variant.id, variant.description);
+            all.push(variant);
+        }
+    }
+
+    let plan_path = output_dir.join("variants.csv");
+    let mut writer =
+        csv::Writer::from_path(&plan_path).context(format!("Failed to create {:?}", plan_path))?;
+    for variant in &all {
+        writer
+            .serialize(variant)
+            .context("Failed to write variants.csv")?;
+    }
+    writer.flush().context("Failed to write variants.csv")?;
+
+    println!("\n=== Mutation Summary ===");
+    println!("  Manifests: {}", manifests.len());
+    println!("  Variants: {}", all.len());
+    println!("  Test plan: {:?} (sign it with --plan)", plan_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> JsonValue {
+        json!({
+            "title": "p-sample",
+            "assertions": [{
+                "label": "c2pa.actions.v2",
+                "data": {
+                    "softwareAgents": [{ "name": "Editor" }],
+                    "actions": [
+                        {
+                            "action": "c2pa.created",
+                            "digitalSourceType": "http://cv.iptc.org/newscodes/digitalsourcetype/digitalCapture",
+                            "softwareAgentIndex": 0
+                        },
+                        {
+                            "action": "c2pa.redacted",
+                            "reason": "c2pa.PII.present",
+                            "parameters": { "ingredientIds": ["COMPONENT"] }
+                        }
+                    ]
+                }
+            }],
+            "ingredients": [{ "label": "COMPONENT", "relationship": "componentOf" }]
+        })
+    }
+
+    fn variant<'a>(variants: &'a [Variant], id: &str) -> &'a Variant {
+        variants
+            .iter()
+            .find(|v| v.id == id)
+            .unwrap_or_else(|| panic!("no variant {}", id))
+    }
+
+    #[test]
+    fn test_variants_of_sample() {
+        let variants = variants_of(&manifest(), "p-sample", "p-sample.json");
+        let ids: Vec<&str> = variants.iter().map(|v| v.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "n-sample-no-dst",
+                "n-sample-agent-and-index",
+                "n-sample-agent-index-out-of-range",
+                "n-sample-bad-reason",
+                "n-sample-no-reason",
+                "n-sample-missing-ingredient",
+                "n-sample-no-ingredient-ids",
+                "n-sample-no-ingredients",
+                "n-sample-reorder",
+                "n-sample-duplicate-inception",
+            ]
+        );
+        assert!(variants
+            .iter()
+            .all(|v| v.manifest["title"] == v.id.as_str()));
+
+        let data = |id| variant(&variants, id).manifest["assertions"][0]["data"].clone();
+        assert!(data("n-sample-no-dst")["actions"][0]
+            .get("digitalSourceType")
+            .is_none());
+        assert_eq!(
+            data("n-sample-agent-and-index")["actions"][0]["softwareAgent"]["name"],
+            "Editor"
+        );
+        assert_eq!(
+            data("n-sample-agent-index-out-of-range")["actions"][0]["softwareAgentIndex"],
+            1
+        );
+        assert_eq!(
+            data("n-sample-bad-reason")["actions"][1]["reason"],
+            INVALID_REASON
+        );
+        assert_eq!(
+            data("n-sample-missing-ingredient")["actions"][1]["parameters"]["ingredientIds"][0],
+            "missing-COMPONENT"
+        );
+        assert_eq!(
+            data("n-sample-reorder")["actions"][1]["action"],
+            "c2pa.created"
+        );
+        assert_eq!(
+            data("n-sample-duplicate-inception")["actions"][1]["action"],
+            "c2pa.created"
+        );
+        assert!(variant(&variants, "n-sample-no-ingredients")
+            .manifest
+            .get("ingredients")
+            .is_none());
+    }
+
+    #[test]
+    fn test_agent_and_index_adds_agent_list() {
+        let manifest = json!({
+            "assertions": [{
+                "label": "c2pa.actions",
+                "data": { "actions": [
+                    { "action": "c2pa.created", "softwareAgent": { "name": "A" } },
+                    { "action": "c2pa.edited", "softwareAgent": { "name": "B" } }
+                ]}
+            }]
+        });
+        let variants = variants_of(&manifest, "p-agents", "p-agents.json");
+        let second = variant(&variants, "n-agents-agent-and-index-2");
+        let data = &second.manifest["assertions"][0]["data"];
+        assert_eq!(data["softwareAgents"][0]["name"], "B");
+        assert_eq!(data["actions"][1]["softwareAgentIndex"], 0);
+        assert!(data["actions"][0].get("softwareAgentIndex").is_none());
+    }
+
+    #[test]
+    fn test_soft_binding_rules() {
+        let manifest = json!({
+            "assertions": [
+                { "label": "c2pa.actions", "data": { "actions": [
+                    { "action": "c2pa.watermarked.bound", "parameters": {} }
+                ]}},
+                { "label": "c2pa.soft-binding", "data": { "alg": "com.example", "blocks": [] } }
+            ]
+        });
+        let variants = variants_of(&manifest, "p-wm", "p-wm.json");
+        assert_eq!(
+            variant(&variants, "n-wm-no-soft-binding").manifest["assertions"]
+                .as_array()
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            variant(&variants, "n-wm-soft-binding-no-alg").manifest["assertions"][1]["data"]
+                .get("alg")
+                .is_none()
+        );
+        assert!(variants
+            .iter()
+            .any(|v| v.id == "n-wm-soft-binding-no-blocks"));
+    }
+}
@@ -0,0 +1,219 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Normalization of extracted manifest JSON so that two extractions can be compared.
+//!
+//! Every signing run produces new manifest URNs, instance IDs, signing times and
+//! signature bytes. These values are replaced by stable placeholders so that only
+//! meaningful differences remain.
+
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+
+/// Identifier prefixes that are followed by a generated UUID
+const ID_PREFIXES: &[&str] = &["urn:c2pa:", "urn:uuid:", "xmp:iid:", "xmp.iid:", "xmp:did:"];
+
+/// Keys whose values are instance identifiers, even when they are not UUID-shaped
+const INSTANCE_ID_KEYS: &[&str] = &["instance_id", "instanceID", "instanceId"];
+
+/// Keys whose values are timestamps that change on every run
+const TIME_KEYS: &[&str] = &["time", "when", "signing_time", "timestamp"];
+
+/// Keys whose string or byte-array values are hashes, padding or signature bytes
+const BYTES_KEYS: &[&str] = &["hash", "pad", "pad1", "pad2", "signature"];
+
+/// Length of a hyphenated UUID string
+const UUID_LEN: usize = 36;
+
+/// Normalize a manifest store JSON document (standard or JPEG Trust format)
+///
+/// Identifiers are numbered in the order they are first seen, starting with the
+/// active manifest, so the same store signed twice normalizes to the same document.
+pub fn normalize_manifest_json(value: &JsonValue) -> JsonValue {
+    let mut normalizer = Normalizer::default();
+
+    // Number the active manifest and everything it references first, so that the
+    // numbering does not depend on the (random) order of manifest labels
+    if let Some(active) = value.get("active_manifest").and_then(|v| v.as_str()) {
+        normalizer.normalize_str(active);
+        if let Some(manifest) = value.get("manifests").and_then(|m| m.get(active)) {
+            normalizer.normalize(manifest);
+        }
+    }
+
+    normalizer.normalize(value)
+}
+
+#[derive(Default)]
+struct Normalizer {
+    /// Maps an original identifier to its placeholder number
+    ids: HashMap<String, usize>,
+}
+
+impl Normalizer {
+    fn normalize(&mut self, value: &JsonValue) -> JsonValue {
+        match value {
+            JsonValue::Object(map) => {
+                let mut normalized = Map::new();
+                for (key, value) in map {
+                    let value = self.normalize_entry(key, value);
+                    normalized.insert(self.normalize_str(key), value);
+                }
+                JsonValue::Object(normalized)
+            }
+            JsonValue::Array(items) => {
+                JsonValue::Array(items.iter().map(|v| self.normalize(v)).collect())
+            }
+            JsonValue::String(s) => JsonValue::String(self.normalize_str(s)),
+            other => other.clone(),
+        }
+    }
+
+    /// Normalize an object entry, taking the key into account
+    fn normalize_entry(&mut self, key: &str, value: &JsonValue) -> JsonValue {
+        let is_bytes = matches!(value, JsonValue::String(_) | JsonValue::Array(_));
+
+        if TIME_KEYS.contains(&key) && value.is_string() {
+            JsonValue::String("<time>".to_string())
+        } else if BYTES_KEYS.contains(&key) && is_bytes {
+            JsonValue::String(format!("<{}>", key))
+        } else if INSTANCE_ID_KEYS.contains(&key) {
+            match value.as_str() {
+                Some(id) if self.normalize_str(id) == id => {
+                    JsonValue::String(format!("<id-{}>", self.id_number(id)))
+                }
+                _ => self.normalize(value),
+            }
+        } else {
+            self.normalize(value)
+        }
+    }
+
+    /// Replace every generated identifier inside a string with its placeholder
+    fn normalize_str(&mut self, s: &str) -> String {
+        let mut result = String::with_capacity(s.len());
+        let mut rest = s;
+
+        while let Some((start, prefix)) = find_id_prefix(rest) {
+            let id_start = start + prefix.len();
+            match rest.get(id_start..id_start + UUID_LEN) {
+                Some(candidate) if is_uuid(candidate) => {
+                    result.push_str(&rest[..id_start]);
+                    result.push_str(&format!("<id-{}>", self.id_number(candidate)));
+                    rest = &rest[id_start + UUID_LEN..];
+                }
+                _ => {
+                    result.push_str(&rest[..id_start]);
+                    rest = &rest[id_start..];
+                }
+            }
+        }
+
+        result.push_str(rest);
+        result
+    }
+
+    fn id_number(&mut self, id: &str) -> usize {
+        let next = self.ids.len() + 1;
+        *self.ids.entry(id.to_lowercase()).or_insert(next)
+    }
+}
+
+/// Find the earliest identifier prefix in a string
+fn find_id_prefix(s: &str) -> Option<(usize, &'static str)> {
+    ID_PREFIXES
+        .iter()
+        .filter_map(|prefix| s.find(prefix).map(|pos| (pos, *prefix)))
+        .min_by_key(|(pos, _)| *pos)
+}
+
+/// Check whether a string is a hyphenated UUID (8-4-4-4-12 hex digits)
+fn is_uuid(s: &str) -> bool {
+    s.len() == UUID_LEN
+        && s.char_indices().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_replaces_manifest_labels() {
+        let doc = json!({
+            "active_manifest": "urn:c2pa:3fad1ead-8ed5-44d0-873b-ea5f58adea82",
+            "manifests": {
+                "urn:c2pa:3fad1ead-8ed5-44d0-873b-ea5f58adea82": {
+                    "instance_id": "xmp:iid:0a1b2c3d-0000-4000-8000-123456789abc",
+                    "signature_info": { "time": "2025-01-01T00:00:00+00:00" }
+                }
+            }
+        });
+
+        let normalized = normalize_manifest_json(&doc);
+
+        assert_eq!(normalized["active_manifest"], "urn:c2pa:<id-1>");
+        let manifest = &normalized["manifests"]["urn:c2pa:<id-1>"];
+        assert_eq!(manifest["instance_id"], "xmp:iid:<id-2>");
+        assert_eq!(manifest["signature_info"]["time"], "<time>");
+    }
+
+    #[test]
+    fn test_normalize_is_stable_across_runs() {
+        let first = json!({
+            "active_manifest": "urn:c2pa:11111111-1111-4111-8111-111111111111",
+            "manifests": {
+                "urn:c2pa:11111111-1111-4111-8111-111111111111": {
+                    "ingredients": [{ "active_manifest": "urn:c2pa:ffffffff-ffff-4fff-8fff-ffffffffffff" }]
+                },
+                "urn:c2pa:ffffffff-ffff-4fff-8fff-ffffffffffff": { "title": "parent" }
+            }
+        });
+        let second = json!({
+            "active_manifest": "urn:c2pa:99999999-9999-4999-8999-999999999999",
+            "manifests": {
+                "urn:c2pa:99999999-9999-4999-8999-999999999999": {
+                    "ingredients": [{ "active_manifest": "urn:c2pa:00000000-0000-4000-8000-000000000000" }]
+                },
+                "urn:c2pa:00000000-0000-4000-8000-000000000000": { "title": "parent" }
+            }
+        });
+
+        assert_eq!(
+            normalize_manifest_json(&first),
+            normalize_manifest_json(&second)
+        );
+    }
+
+    #[test]
+    fn test_normalize_replaces_hashes_and_signatures() {
+        let doc = json!({
+            "assertions": [{ "label": "c2pa.hash.data", "data": { "hash": "AAEC", "pad": [0, 0] } }],
+            "signature": "c2lnbmF0dXJl"
+        });
+
+        let normalized = normalize_manifest_json(&doc);
+
+        assert_eq!(normalized["assertions"][0]["data"]["hash"], "<hash>");
+        assert_eq!(normalized["assertions"][0]["data"]["pad"], "<pad>");
+        assert_eq!(normalized["signature"], "<signature>");
+    }
+
+    #[test]
+    fn test_normalize_keeps_non_uuid_strings() {
+        let doc = json!({ "title": "urn:c2pa:not-a-uuid", "format": "image/jpeg" });
+        assert_eq!(normalize_manifest_json(&doc), doc);
+    }
+}
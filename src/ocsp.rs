@@ -0,0 +1,434 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! OCSP stapling for the signer certificate.
+//!
+//! The OCSP response is stored in the COSE `rVals` header of the signature, as C2PA
+//! credential revocation information. It is either fetched from an OCSP responder or
+//! produced by a local responder stand-in that signs an RFC 6960 response with a test
+//! CA (or delegated responder) key, so the signer can be reported as good, revoked or
+//! unknown without running any infrastructure.
+
+use anyhow::{Context, Result};
+use c2pa::{Signer, SigningAlg};
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use x509_parser::prelude::*;
+
+/// sha1 (CertID hash algorithm)
+const OID_SHA1: &[u8] = &[0x06, 0x05, 0x2B, 0x0E, 0x03, 0x02, 0x1A];
+/// id-pkix-ocsp-basic
+const OID_OCSP_BASIC: &[u8] = &[
+    0x06, 0x09, 0x2B, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01, 0x01,
+];
+/// ecdsa-with-SHA256
+const OID_ECDSA_SHA256: &[u8] = &[0x06, 0x08, 0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x04, 0x03, 0x02];
+/// sha256WithRSAEncryption
+const OID_RSA_SHA256: &[u8] = &[
+    0x06, 0x09, 0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B,
+];
+/// id-Ed25519
+const OID_ED25519: &[u8] = &[0x06, 0x03, 0x2B, 0x65, 0x70];
+const DER_NULL: &[u8] = &[0x05, 0x00];
+
+/// How long a generated response is valid (nextUpdate - thisUpdate)
+const VALIDITY_SECS: i64 = 7 * 24 * 60 * 60;
+/// How long before the response a revoked certificate was revoked
+const REVOKED_BEFORE_SECS: i64 = 24 * 60 * 60;
+
+/// Certificate status reported by the local responder
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum CertStatus {
+    Good,
+    Revoked,
+    Unknown,
+}
+
+/// Where the OCSP response comes from
+pub enum OcspSource<'a> {
+    /// POST an OCSP request to this responder URL
+    Responder(&'a str),
+    /// Generate the response locally, signed with the responder certificate and key
+    Local {
+        status: CertStatus,
+        responder_cert: &'a Path,
+        responder_key: &'a Path,
+    },
+}
+
+/// Encode a DER length
+fn der_length(len: usize) -> Vec<u8> {
+    if len < 0x80 {
+        return vec![len as u8];
+    }
+    let bytes: Vec<u8> = len
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|&b| b == 0)
+        .collect();
+    let mut out = vec![0x80 | bytes.len() as u8];
+    out.extend(bytes);
+    out
+}
+
+/// Encode a DER tag-length-value
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    out.extend(der_length(content.len()));
+    out.extend_from_slice(content);
+    out
+}
+
+/// Encode a SEQUENCE of already encoded elements
+fn sequence(elements: &[&[u8]]) -> Vec<u8> {
+    tlv(0x30, &elements.concat())
+}
+
+/// Encode an unsigned INTEGER from big-endian bytes
+fn integer(value: &[u8]) -> Vec<u8> {
+    let start = value
+        .iter()
+        .position(|&b| b != 0)
+        .unwrap_or(value.len().saturating_sub(1));
+    let mut content = value[start..].to_vec();
+    if content.is_empty() || content[0] & 0x80 != 0 {
+        content.insert(0, 0);
+    }
+    tlv(0x02, &content)
+}
+
+/// Format a Unix time as a GeneralizedTime value (YYYYMMDDHHMMSSZ)
+fn generalized_time(unix_secs: i64) -> Vec<u8> {
+    let [year, month, day, hour, minute, second] = crate::time::utc_datetime(unix_secs);
+    let text = format!(
+        "{:04}{:02}{:02}{:02}{:02}{:02}Z",
+        year, month, day, hour, minute, second
+    );
+    tlv(0x18, text.as_bytes())
+}
+
+/// Read every certificate in a PEM file, in order
+fn read_pem_certs(path: &Path) -> Result<Vec<Vec<u8>>> {
+    let data = fs::read(path).context(format!("Failed to read certificate file: {:?}", path))?;
+    let certs: Vec<Vec<u8>> = ::pem::parse_many(&data)
+        .map_err(|e| anyhow::anyhow!("Failed to parse certificate PEM {:?}: {}", path, e))?
+        .into_iter()
+        .filter(|pem| pem.tag() == "CERTIFICATE")
+        .map(|pem| pem.into_contents())
+        .collect();
+    if certs.is_empty() {
+        anyhow::bail!("No certificate found in {:?}", path);
+    }
+    Ok(certs)
+}
+
+fn parse_cert(der: &[u8]) -> Result<X509Certificate<'_>> {
+    X509Certificate::from_der(der)
+        .map(|(_, cert)| cert)
+        .map_err(|e| anyhow::anyhow!("Failed to parse X.509 certificate: {}", e))
+}
+
+/// Build the CertID identifying `cert` (issued by `issuer`) in OCSP requests and responses
+fn cert_id(cert: &X509Certificate, issuer: &X509Certificate) -> Vec<u8> {
+    let name_hash = Sha1::digest(issuer.subject().as_raw());
+    let key_hash = Sha1::digest(&issuer.public_key().subject_public_key.data);
+    sequence(&[
+        &sequence(&[OID_SHA1, DER_NULL]),
+        &tlv(0x04, &name_hash),
+        &tlv(0x04, &key_hash),
+        &integer(cert.raw_serial()),
+    ])
+}
+
+/// Signer certificate and issuer from a PEM chain (signer first)
+fn signer_and_issuer(cert_chain: &[Vec<u8>]) -> Result<(X509Certificate<'_>, X509Certificate<'_>)> {
+    let [signer, issuer, ..] = cert_chain else {
+        anyhow::bail!(
+            "OCSP needs the issuer certificate: the --cert chain must contain the signer certificate followed by its issuer"
+        );
+    };
+    Ok((parse_cert(signer)?, parse_cert(issuer)?))
+}
+
+/// Build an unsigned OCSPRequest for one certificate
+fn build_request(cert_id: &[u8]) -> Vec<u8> {
+    // OCSPRequest { TBSRequest { requestList { Request { reqCert } } } }
+    sequence(&[&sequence(&[&sequence(&[&sequence(&[cert_id])])])])
+}
+
+/// Read one DER element at `pos`: (tag, content start, content end)
+fn read_element(data: &[u8], pos: usize) -> Option<(u8, usize, usize)> {
+    let tag = *data.get(pos)?;
+    let first = *data.get(pos + 1)?;
+    let (len, header) = if first < 0x80 {
+        (first as usize, 2)
+    } else {
+        let count = (first & 0x7F) as usize;
+        let bytes = data.get(pos + 2..pos + 2 + count)?;
+        (
+            bytes.iter().fold(0usize, |len, &b| (len << 8) | b as usize),
+            2 + count,
+        )
+    };
+    let start = pos + header;
+    let end = start.checked_add(len)?;
+    (end <= data.len()).then_some((tag, start, end))
+}
+
+/// OCSPResponseStatus of a DER OCSPResponse (0 = successful)
+fn response_status(response: &[u8]) -> Option<u8> {
+    let (tag, start, _) = read_element(response, 0)?;
+    if tag != 0x30 {
+        return None;
+    }
+    let (tag, status_start, status_end) = read_element(response, start)?;
+    (tag == 0x0A && status_end == status_start + 1).then(|| response[status_start])
+}
+
+/// Sign `tbs` with the responder key, returning (AlgorithmIdentifier, signature)
+fn sign_response(
+    tbs: &[u8],
+    responder_cert: &Path,
+    responder_key: &Path,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let key = fs::read(responder_key).context("Failed to read OCSP responder key")?;
+    let alg = crate::detect_signing_algorithm(responder_cert)?;
+    let sign_error = |e: c2pa::Error| anyhow::anyhow!("Failed to sign OCSP response: {}", e);
+
+    match alg {
+        SigningAlg::Ed25519 => Ok((
+            sequence(&[OID_ED25519]),
+            crate::ed25519_sign(tbs, &key).map_err(sign_error)?,
+        )),
+        SigningAlg::Es256 => {
+            let raw = crate::ecdsa_sign(tbs, &key).map_err(sign_error)?;
+            let (r, s) = raw.split_at(raw.len() / 2);
+            Ok((
+                sequence(&[OID_ECDSA_SHA256]),
+                sequence(&[&integer(r), &integer(s)]),
+            ))
+        }
+        SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512 => Ok((
+            sequence(&[OID_RSA_SHA256, DER_NULL]),
+            crate::rsa_sign(tbs, &key).map_err(sign_error)?,
+        )),
+        other => anyhow::bail!(
+            "Unsupported OCSP responder key algorithm: {:?} (use Ed25519, P-256 or RSA)",
+            other
+        ),
+    }
+}
+
+/// Generate a signed OCSPResponse for `cert_id` at time `now`
+fn build_response(
+    cert_id: &[u8],
+    status: CertStatus,
+    now: i64,
+    responder_cert: &Path,
+    responder_key: &Path,
+) -> Result<Vec<u8>> {
+    let responder_certs = read_pem_certs(responder_cert)?;
+    let responder = parse_cert(&responder_certs[0])?;
+
+    let cert_status = match status {
+        CertStatus::Good => vec![0x80, 0x00],
+        // revoked [1] IMPLICIT RevokedInfo { revocationTime, [0] CRLReason keyCompromise }
+        CertStatus::Revoked => tlv(
+            0xA1,
+            &[
+                generalized_time(now - REVOKED_BEFORE_SECS),
+                tlv(0xA0, &[0x0A, 0x01, 0x01]),
+            ]
+            .concat(),
+        ),
+        CertStatus::Unknown => vec![0x82, 0x00],
+    };
+
+    let single_response = sequence(&[
+        cert_id,
+        &cert_status,
+        &generalized_time(now),
+        &tlv(0xA0, &generalized_time(now + VALIDITY_SECS)),
+    ]);
+    let tbs_response_data = sequence(&[
+        // responderID byName [1]
+        &tlv(0xA1, responder.subject().as_raw()),
+        &generalized_time(now),
+        &sequence(&[&single_response]),
+    ]);
+
+    let (signature_alg, signature) =
+        sign_response(&tbs_response_data, responder_cert, responder_key)?;
+
+    // Include the responder chain so delegated responders can be verified
+    let certs: Vec<&[u8]> = responder_certs.iter().map(Vec::as_slice).collect();
+    let basic_response = sequence(&[
+        &tbs_response_data,
+        &signature_alg,
+        &tlv(0x03, &[&[0u8][..], &signature].concat()),
+        &tlv(0xA0, &sequence(&certs)),
+    ]);
+
+    Ok(sequence(&[
+        &[0x0A, 0x01, 0x00],
+        &tlv(
+            0xA0,
+            &sequence(&[OID_OCSP_BASIC, &tlv(0x04, &basic_response)]),
+        ),
+    ]))
+}
+
+/// POST an OCSP request to a responder and return the DER response
+fn fetch_response(url: &str, request: &[u8]) -> Result<Vec<u8>> {
+    let response = ureq::post(url)
+        .set("Content-Type", "application/ocsp-request")
+        .set("Accept", "application/ocsp-response")
+        .send_bytes(request)
+        .context(format!("OCSP request to {} failed", url))?;
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .take(1024 * 1024)
+        .read_to_end(&mut body)
+        .context("Failed to read OCSP response")?;
+    Ok(body)
+}
+
+/// Get the OCSP response for the signer certificate in `cert_chain_path`
+pub fn ocsp_response(cert_chain_path: &Path, source: &OcspSource) -> Result<Vec<u8>> {
+    let chain = read_pem_certs(cert_chain_path)?;
+    let (signer, issuer) = signer_and_issuer(&chain)?;
+    let id = cert_id(&signer, &issuer);
+
+    let response = match source {
+        OcspSource::Responder(url) => {
+            status!("  Fetching OCSP response from {}", url);
+            fetch_response(url, &build_request(&id))?
+        }
+        OcspSource::Local {
+            status,
+            responder_cert,
+            responder_key,
+        } => {
+            status!("  Generating OCSP response locally (status: {:?})", status);
+            let now = crate::time::unix_time_now()?;
+            build_response(&id, *status, now, responder_cert, responder_key)?
+        }
+    };
+
+    match response_status(&response) {
+        Some(0) => Ok(response),
+        Some(status) => anyhow::bail!("OCSP responder returned error status {}", status),
+        None => anyhow::bail!("OCSP responder did not return a DER OCSPResponse"),
+    }
+}
+
+/// Signer that staples an OCSP response to the signature
+pub struct StapledSigner {
+    inner: Box<dyn Signer>,
+    ocsp_response: Vec<u8>,
+}
+
+impl StapledSigner {
+    pub fn new(inner: Box<dyn Signer>, ocsp_response: Vec<u8>) -> Self {
+        StapledSigner {
+            inner,
+            ocsp_response,
+        }
+    }
+}
+
+impl Signer for StapledSigner {
+    fn sign(&self, data: &[u8]) -> c2pa::Result<Vec<u8>> {
+        self.inner.sign(data)
+    }
+
+    fn alg(&self) -> SigningAlg {
+        self.inner.alg()
+    }
+
+    fn certs(&self) -> c2pa::Result<Vec<Vec<u8>>> {
+        self.inner.certs()
+    }
+
+    fn reserve_size(&self) -> usize {
+        // The response is stored in the signature box next to the signature
+        self.inner.reserve_size() + self.ocsp_response.len() + 64
+    }
+
+    fn time_authority_url(&self) -> Option<String> {
+        self.inner.time_authority_url()
+    }
+
+    fn ocsp_val(&self) -> Option<Vec<u8>> {
+        Some(self.ocsp_response.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_chain() -> Vec<Vec<u8>> {
+        read_pem_certs(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/certs/ed25519.pub"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_der_encoding() {
+        assert_eq!(der_length(0x7F), vec![0x7F]);
+        assert_eq!(der_length(0x80), vec![0x81, 0x80]);
+        assert_eq!(der_length(0x1234), vec![0x82, 0x12, 0x34]);
+        assert_eq!(integer(&[0x00, 0x00, 0x05]), vec![0x02, 0x01, 0x05]);
+        assert_eq!(integer(&[0x80]), vec![0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(integer(&[0x00]), vec![0x02, 0x01, 0x00]);
+        assert_eq!(generalized_time(0), tlv(0x18, b"19700101000000Z"));
+        assert_eq!(
+            generalized_time(1_709_210_096),
+            tlv(0x18, b"20240229123456Z")
+        );
+    }
+
+    #[test]
+    fn test_cert_id_and_request() {
+        let chain = fixture_chain();
+        let (signer, issuer) = signer_and_issuer(&chain).unwrap();
+        let id = cert_id(&signer, &issuer);
+
+        let (tag, start, end) = read_element(&id, 0).unwrap();
+        assert_eq!(tag, 0x30);
+        assert_eq!(end, id.len());
+        // hashAlgorithm, then the 20-byte SHA-1 issuer name hash
+        let (_, _, alg_end) = read_element(&id, start).unwrap();
+        let (tag, hash_start, hash_end) = read_element(&id, alg_end).unwrap();
+        assert_eq!(tag, 0x04);
+        assert_eq!(hash_end - hash_start, 20);
+
+        let request = build_request(&id);
+        assert!(request.windows(id.len()).any(|w| w == id.as_slice()));
+        assert!(signer_and_issuer(&chain[..1]).is_err());
+    }
+
+    #[test]
+    fn test_response_status() {
+        let success = sequence(&[&[0x0A, 0x01, 0x00], &tlv(0xA0, &[])]);
+        assert_eq!(response_status(&success), Some(0));
+        // tryLater, no responseBytes
+        assert_eq!(response_status(&sequence(&[&[0x0A, 0x01, 0x03]])), Some(3));
+        assert_eq!(response_status(b"<html>"), None);
+    }
+}
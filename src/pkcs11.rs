@@ -0,0 +1,192 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Signing with private keys held in a PKCS#11 token (HSM, SoftHSM).
+//!
+//! The key never leaves the token: the signer opens one session, logs in with the PIN
+//! from `C2PA_PKCS11_PIN` and asks the token to sign each claim.
+
+use anyhow::{Context, Result};
+use c2pa::{CallbackSigner, SigningAlg};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::mechanism::rsa::{PkcsMgfType, PkcsPssParams};
+use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Environment variable holding the token user PIN
+pub const PIN_ENV_VAR: &str = "C2PA_PKCS11_PIN";
+
+/// Location of a private key in a PKCS#11 token
+#[derive(Clone, Copy, Debug)]
+pub struct Pkcs11Key<'a> {
+    /// PKCS#11 module (shared library), e.g. libsofthsm2.so
+    pub module: &'a Path,
+    /// Slot ID; the first slot with a token when not given
+    pub slot: Option<u64>,
+    /// CKA_LABEL of the private key
+    pub label: &'a str,
+}
+
+/// Open a logged-in session on the token and find the private key
+fn open_key(key: &Pkcs11Key) -> Result<(Session, ObjectHandle)> {
+    let pkcs11 = Pkcs11::new(key.module)
+        .context(format!("Failed to load PKCS#11 module: {:?}", key.module))?;
+    pkcs11
+        .initialize(CInitializeArgs::OsThreads)
+        .context("Failed to initialize PKCS#11 module")?;
+
+    let slots = pkcs11
+        .get_slots_with_token()
+        .context("Failed to list PKCS#11 slots")?;
+    let slot = match key.slot {
+        Some(id) => slots
+            .into_iter()
+            .find(|slot| slot.id() == id)
+            .context(format!("No token in PKCS#11 slot {}", id))?,
+        None => slots
+            .into_iter()
+            .next()
+            .context("No PKCS#11 slot with a token found")?,
+    };
+
+    let session = pkcs11
+        .open_ro_session(slot)
+        .context(format!("Failed to open a session on slot {}", slot.id()))?;
+    if let Ok(pin) = std::env::var(PIN_ENV_VAR) {
+        session
+            .login(UserType::User, Some(&AuthPin::new(pin)))
+            .context("PKCS#11 login failed")?;
+    }
+
+    let handles = session
+        .find_objects(&[
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::Label(key.label.as_bytes().to_vec()),
+        ])
+        .context("Failed to search the token for the private key")?;
+    let handle = match handles.as_slice() {
+        [handle] => *handle,
+        [] => anyhow::bail!(
+            "No private key labelled {:?} in slot {} (is {} set?)",
+            key.label,
+            slot.id(),
+            PIN_ENV_VAR
+        ),
+        _ => anyhow::bail!(
+            "{} private keys labelled {:?} in slot {}",
+            handles.len(),
+            key.label,
+            slot.id()
+        ),
+    };
+
+    Ok((session, handle))
+}
+
+/// Single-part RSA-PSS mechanism for a C2PA PS* algorithm: MGF1 with the same hash and
+/// a salt as long as the digest. The token hashes the data.
+fn pss_mechanism(signing_alg: SigningAlg) -> Option<Mechanism<'static>> {
+    let params = |hash_alg, mgf, s_len: u64| PkcsPssParams {
+        hash_alg,
+        mgf,
+        s_len: s_len.into(),
+    };
+    match signing_alg {
+        SigningAlg::Ps256 => Some(Mechanism::Sha256RsaPkcsPss(params(
+            MechanismType::SHA256,
+            PkcsMgfType::MGF1_SHA256,
+            32,
+        ))),
+        SigningAlg::Ps384 => Some(Mechanism::Sha384RsaPkcsPss(params(
+            MechanismType::SHA384,
+            PkcsMgfType::MGF1_SHA384,
+            48,
+        ))),
+        SigningAlg::Ps512 => Some(Mechanism::Sha512RsaPkcsPss(params(
+            MechanismType::SHA512,
+            PkcsMgfType::MGF1_SHA512,
+            64,
+        ))),
+        _ => None,
+    }
+}
+
+/// Sign data with the token key
+///
+/// ECDSA data is hashed locally and signed with CKM_ECDSA, which every token supports
+/// and which returns the raw r || s form COSE expects.
+fn token_sign(
+    session: &Mutex<Session>,
+    handle: ObjectHandle,
+    signing_alg: SigningAlg,
+    data: &[u8],
+) -> c2pa::Result<Vec<u8>> {
+    use c2pa::crypto::raw_signature::RawSignerError;
+
+    let session = session
+        .lock()
+        .map_err(|_| RawSignerError::InternalError("PKCS#11 session poisoned".to_string()))?;
+
+    let (mechanism, input) = match signing_alg {
+        SigningAlg::Es256 => (Mechanism::Ecdsa, Sha256::digest(data).to_vec()),
+        SigningAlg::Es384 => (Mechanism::Ecdsa, Sha384::digest(data).to_vec()),
+        SigningAlg::Es512 => (Mechanism::Ecdsa, Sha512::digest(data).to_vec()),
+        SigningAlg::Ed25519 => (Mechanism::Eddsa, data.to_vec()),
+        SigningAlg::Ps256 | SigningAlg::Ps384 | SigningAlg::Ps512 => (
+            pss_mechanism(signing_alg).expect("PS* algorithms have a PSS mechanism"),
+            data.to_vec(),
+        ),
+    };
+
+    session
+        .sign(&mechanism, handle, &input)
+        .map_err(|e| RawSignerError::InternalError(format!("PKCS#11 signing failed: {}", e)).into())
+}
+
+/// Create a callback signer that signs with a key held in a PKCS#11 token
+pub fn create_pkcs11_signer(
+    key: &Pkcs11Key,
+    cert_path: &Path,
+    signing_alg: SigningAlg,
+) -> Result<CallbackSigner> {
+    let cert_data = fs::read(cert_path).context("Failed to read certificate file")?;
+    let (session, handle) = open_key(key)?;
+    let session = Mutex::new(session);
+
+    let token_signer =
+        move |_context: *const (), data: &[u8]| token_sign(&session, handle, signing_alg, data);
+    Ok(CallbackSigner::new(token_signer, signing_alg, cert_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pss_mechanism_matches_c2pa() {
+        assert!(matches!(
+            pss_mechanism(SigningAlg::Ps384),
+            Some(Mechanism::Sha384RsaPkcsPss(params))
+                if params.hash_alg == MechanismType::SHA384
+                    && params.mgf == PkcsMgfType::MGF1_SHA384
+        ));
+
+        assert!(pss_mechanism(SigningAlg::Es256).is_none());
+        assert!(pss_mechanism(SigningAlg::Ed25519).is_none());
+    }
+}
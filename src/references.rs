@@ -0,0 +1,337 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! `c2pa.cloud-data` and `c2pa.external-reference` assertions backed by local files.
+//!
+//! An assertion of either kind whose data has a `file_path` field gets its hash, size and
+//! content type computed from that file, and its `location.url` pointed at the local HTTP
+//! stand-in when `--serve-references` is given. The stand-in serves each referenced file
+//! under its file name so validators can fetch the data and check it against the hash.
+
+use anyhow::{Context, Result};
+use ciborium::Value as CborValue;
+use serde_json::{json, Value as JsonValue};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tiny_http::{Header, Response, Server};
+
+/// Assertion labels whose data points at external content
+const REFERENCE_LABELS: &[&str] = &["c2pa.cloud-data", "c2pa.external-reference"];
+
+/// An assertion whose data references a local file
+struct FileReference<'a> {
+    label: &'a str,
+    data: &'a JsonValue,
+    path: PathBuf,
+}
+
+/// Assertions in the manifest that reference local files
+fn file_references<'a>(manifest: &'a JsonValue, base_dir: &Path) -> Vec<FileReference<'a>> {
+    let Some(assertions) = manifest.get("assertions").and_then(|v| v.as_array()) else {
+        return Vec::new();
+    };
+    assertions
+        .iter()
+        .filter_map(|assertion| {
+            let label = assertion.get("label")?.as_str()?;
+            if !REFERENCE_LABELS.contains(&label) {
+                return None;
+            }
+            let data = assertion.get("data")?;
+            let file_path = data.get("file_path")?.as_str()?;
+            let path = if Path::new(file_path).is_absolute() {
+                PathBuf::from(file_path)
+            } else {
+                base_dir.join(file_path)
+            };
+            Some(FileReference { label, data, path })
+        })
+        .collect()
+}
+
+/// Name a referenced file is served under
+fn served_name(path: &Path) -> Result<String> {
+    path.file_name()
+        .and_then(|s| s.to_str())
+        .map(str::to_string)
+        .context(format!("Referenced file has no usable name: {:?}", path))
+}
+
+/// Content type of a referenced file, from its extension
+fn content_type_for(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    match extension.to_lowercase().as_str() {
+        "json" => "application/json",
+        "cbor" => "application/cbor",
+        "txt" => "text/plain",
+        other => crate::extension_to_mime(other).unwrap_or("application/octet-stream"),
+    }
+}
+
+/// Hash data with a C2PA hash algorithm name
+fn hash(alg: &str, data: &[u8]) -> Result<Vec<u8>> {
+    Ok(match alg {
+        "sha256" => Sha256::digest(data).to_vec(),
+        "sha384" => Sha384::digest(data).to_vec(),
+        "sha512" => Sha512::digest(data).to_vec(),
+        other => anyhow::bail!("Unsupported reference hash algorithm: {}", other),
+    })
+}
+
+/// Replace or append a text-keyed entry of a CBOR map
+fn set_field(map: &mut Vec<(CborValue, CborValue)>, key: &str, value: CborValue) {
+    match map.iter_mut().find(|(k, _)| k.as_text() == Some(key)) {
+        Some((_, existing)) => *existing = value,
+        None => map.push((key.into(), value)),
+    }
+}
+
+/// Assertion data for one reference, with the file's hash, size and content type filled in
+fn resolve(reference: &FileReference, base_url: Option<&str>) -> Result<CborValue> {
+    let data = fs::read(&reference.path).context(format!(
+        "Failed to read referenced file: {:?}",
+        reference.path
+    ))?;
+    let content_type = reference
+        .data
+        .get("content_type")
+        .and_then(|v| v.as_str())
+        .unwrap_or_else(|| content_type_for(&reference.path));
+
+    let mut fields = reference.data.clone();
+    let object = fields
+        .as_object_mut()
+        .context(format!("{} data must be an object", reference.label))?;
+    object.remove("file_path");
+
+    let mut location = object
+        .remove("location")
+        .unwrap_or_else(|| json!({}))
+        .as_object()
+        .cloned()
+        .context(format!("{} location must be an object", reference.label))?;
+    let alg = location
+        .get("alg")
+        .and_then(|v| v.as_str())
+        .unwrap_or("sha256")
+        .to_string();
+    match base_url {
+        Some(base_url) => {
+            let url = format!("{}/{}", base_url, served_name(&reference.path)?);
+            location.insert("url".into(), url.into());
+        }
+        None if location.contains_key("url") => {}
+        None => anyhow::bail!(
+            "{} referencing {:?} needs a location url or --serve-references",
+            reference.label,
+            reference.path
+        ),
+    }
+    location.insert("alg".into(), alg.as_str().into());
+    location.remove("hash");
+
+    // cloud-data describes the content itself; external-reference describes it in the location
+    if reference.label == "c2pa.cloud-data" {
+        object.insert("size".into(), data.len().into());
+        object.insert("content_type".into(), content_type.into());
+    } else {
+        object.remove("content_type");
+        location.insert("size".into(), data.len().into());
+        location.insert("dc:format".into(), content_type.into());
+    }
+
+    // The hash is a CBOR byte string, which the JSON manifest can't express
+    let mut location = CborValue::serialized(&location).context("Failed to encode location")?;
+    if let CborValue::Map(map) = &mut location {
+        set_field(map, "hash", CborValue::Bytes(hash(&alg, &data)?));
+    }
+    let mut value = CborValue::serialized(&fields).context("Failed to encode assertion")?;
+    if let CborValue::Map(map) = &mut value {
+        set_field(map, "location", location);
+    }
+    Ok(value)
+}
+
+/// Take the file-backed reference assertions out of the manifest JSON.
+///
+/// Returns the manifest without them and the resolved assertions, ready for
+/// `Builder::add_assertion`. `base_url` is the stand-in's address, if one is serving.
+pub fn resolve_references(
+    manifest_json: &str,
+    base_dir: &Path,
+    base_url: Option<&str>,
+) -> Result<(String, Vec<(String, CborValue)>)> {
+    let mut manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let references = file_references(&manifest, base_dir);
+    if references.is_empty() {
+        return Ok((manifest_json.to_string(), Vec::new()));
+    }
+
+    let mut resolved = Vec::new();
+    for reference in &references {
+        let value = resolve(reference, base_url)
+            .context(format!("Failed to resolve {} assertion", reference.label))?;
+        resolved.push((reference.label.to_string(), value));
+    }
+
+    if let Some(assertions) = manifest
+        .get_mut("assertions")
+        .and_then(|v| v.as_array_mut())
+    {
+        assertions.retain(|assertion| {
+            let label = assertion.get("label").and_then(|v| v.as_str());
+            let has_file = assertion.pointer("/data/file_path").is_some();
+            !(has_file && label.is_some_and(|label| REFERENCE_LABELS.contains(&label)))
+        });
+    }
+    Ok((manifest.to_string(), resolved))
+}
+
+/// Files the stand-in serves for a manifest, keyed by served name
+pub fn referenced_files(manifest_json: &str, base_dir: &Path) -> Result<BTreeMap<String, PathBuf>> {
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let mut files = BTreeMap::new();
+    for reference in file_references(&manifest, base_dir) {
+        let name = served_name(&reference.path)?;
+        if let Some(other) = files.insert(name.clone(), reference.path.clone()) {
+            if other != reference.path {
+                anyhow::bail!(
+                    "Referenced files {:?} and {:?} would both be served as /{}",
+                    other,
+                    reference.path,
+                    name
+                );
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Bind the stand-in; the returned base URL is what signed manifests point at
+pub fn bind(addr: &str) -> Result<(Server, String)> {
+    let server =
+        Server::http(addr).map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", addr, e))?;
+    let base_url = format!("http://{}", server.server_addr());
+    Ok((server, base_url))
+}
+
+/// Serve the referenced files until the process is stopped
+pub fn serve_files(
+    server: Server,
+    base_url: &str,
+    files: &BTreeMap<String, PathBuf>,
+) -> Result<()> {
+    status!("\n=== Reference stand-in ===");
+    for (name, path) in files {
+        status!("  {}/{} -> {:?}", base_url, name, path);
+    }
+    // Printed on its own line so harnesses binding to port 0 can find the address; it goes
+    // to stderr with the rest of the status output when the signed asset is written to stdout
+    status!("Serving references on {}", base_url);
+
+    for request in server.incoming_requests() {
+        let name = request.url().trim_start_matches('/').to_string();
+        let response = match files.get(&name).map(|path| (path, fs::read(path))) {
+            Some((path, Ok(data))) => {
+                let content_type = content_type_for(path);
+                let mut response = Response::from_data(data);
+                if let Ok(header) = Header::from_bytes("Content-Type", content_type.as_bytes()) {
+                    response = response.with_header(header);
+                }
+                response
+            }
+            Some((_, Err(e))) => Response::from_string(e.to_string()).with_status_code(500),
+            None => Response::from_string("Not found").with_status_code(404),
+        };
+        status!(
+            "  {} {} -> {}",
+            request.method(),
+            request.url(),
+            response.status_code().0
+        );
+        if let Err(e) = request.respond(response) {
+            eprintln!("  Failed to send response: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map_field<'a>(value: &'a CborValue, key: &str) -> Option<&'a CborValue> {
+        value
+            .as_map()?
+            .iter()
+            .find(|(k, _)| k.as_text() == Some(key))
+            .map(|(_, v)| v)
+    }
+
+    #[test]
+    fn test_resolve_references() {
+        let dir = std::env::temp_dir().join("c2pa_references_unit");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("data.json"), b"{\"a\":1}").unwrap();
+        let manifest = json!({
+            "assertions": [
+                {"label": "c2pa.actions", "data": {"actions": []}},
+                {"label": "c2pa.cloud-data", "data": {
+                    "label": "c2pa.actions", "file_path": "data.json"
+                }},
+                {"label": "c2pa.external-reference", "data": {
+                    "file_path": "data.json", "location": {"url": "https://example.com/x"}
+                }}
+            ]
+        })
+        .to_string();
+
+        let (remaining, resolved) =
+            resolve_references(&manifest, &dir, Some("http://127.0.0.1:9")).unwrap();
+        let remaining: JsonValue = serde_json::from_str(&remaining).unwrap();
+        assert_eq!(remaining["assertions"].as_array().unwrap().len(), 1);
+        assert_eq!(resolved.len(), 2);
+
+        let digest = Sha256::digest(b"{\"a\":1}").to_vec();
+        let cloud = &resolved[0].1;
+        assert_eq!(map_field(cloud, "size"), Some(&CborValue::from(7)));
+        assert_eq!(
+            map_field(cloud, "content_type").and_then(|v| v.as_text()),
+            Some("application/json")
+        );
+        assert!(map_field(cloud, "file_path").is_none());
+        let location = map_field(cloud, "location").unwrap();
+        assert_eq!(map_field(location, "hash"), Some(&CborValue::Bytes(digest)));
+        assert_eq!(
+            map_field(location, "url").and_then(|v| v.as_text()),
+            Some("http://127.0.0.1:9/data.json")
+        );
+
+        let external = map_field(&resolved[1].1, "location").unwrap();
+        assert_eq!(
+            map_field(external, "dc:format").and_then(|v| v.as_text()),
+            Some("application/json")
+        );
+
+        // Without the stand-in, the cloud-data entry has no url to point at
+        assert!(resolve_references(&manifest, &dir, None).is_err());
+        assert_eq!(referenced_files(&manifest, &dir).unwrap().len(), 1);
+    }
+}
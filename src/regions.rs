@@ -0,0 +1,313 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Region-of-interest checks against the asset's pixel dimensions.
+//!
+//! Every `region` list in the manifest (actions v2 `changes`, soft binding scopes,
+//! ingredient regions of interest, ...) is a list of ranges. Spatial ranges are checked
+//! for well-formed shapes that lie inside the image, in pixel or percent units. Other
+//! range types (temporal, frame, textual, identified) are not checked.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use std::io::Cursor;
+
+/// Pixel dimensions of an encoded image, if the image crate recognizes its format
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
+/// Numeric field of a JSON object
+fn number(value: &JsonValue, key: &str) -> Option<f64> {
+    value.get(key).and_then(|v| v.as_f64())
+}
+
+/// Check one shape; returns a description of the problem
+fn check_shape(shape: &JsonValue, width: u32, height: u32) -> Option<String> {
+    let (max_x, max_y, unit) = match shape.get("unit").and_then(|v| v.as_str()) {
+        Some("pixel") => (f64::from(width), f64::from(height), "pixel"),
+        Some("percent") => (100.0, 100.0, "percent"),
+        Some(other) => return Some(format!("unknown unit {:?}", other)),
+        None => return Some("shape has no unit".to_string()),
+    };
+    let inside = |x: f64, y: f64| (0.0..=max_x).contains(&x) && (0.0..=max_y).contains(&y);
+    let bounds = if unit == "pixel" {
+        format!("the {}x{} image", width, height)
+    } else {
+        "0-100%".to_string()
+    };
+
+    let origin = shape.get("origin");
+    let origin = origin.and_then(|o| Some((number(o, "x")?, number(o, "y")?)));
+    match shape.get("type").and_then(|v| v.as_str()) {
+        Some("rectangle") => {
+            let (Some((x, y)), Some(w), Some(h)) =
+                (origin, number(shape, "width"), number(shape, "height"))
+            else {
+                return Some("rectangle needs origin.x, origin.y, width and height".to_string());
+            };
+            if w <= 0.0 || h <= 0.0 {
+                return Some(format!("rectangle has a non-positive size {}x{}", w, h));
+            }
+            (!inside(x, y) || !inside(x + w, y + h)).then(|| {
+                format!(
+                    "rectangle {}x{} at ({}, {}) {} extends beyond {}",
+                    w, h, x, y, unit, bounds
+                )
+            })
+        }
+        Some("circle") => {
+            // The origin is the center and the width the diameter
+            let (Some((x, y)), Some(diameter)) = (origin, number(shape, "width")) else {
+                return Some("circle needs origin.x, origin.y and width (diameter)".to_string());
+            };
+            if diameter <= 0.0 {
+                return Some(format!("circle has a non-positive diameter {}", diameter));
+            }
+            let r = diameter / 2.0;
+            (!inside(x - r, y - r) || !inside(x + r, y + r)).then(|| {
+                format!(
+                    "circle of diameter {} centered at ({}, {}) {} extends beyond {}",
+                    diameter, x, y, unit, bounds
+                )
+            })
+        }
+        Some("polygon") => {
+            let Some(vertices) = shape.get("vertices").and_then(|v| v.as_array()) else {
+                return Some("polygon has no vertices".to_string());
+            };
+            if vertices.len() < 3 {
+                return Some(format!(
+                    "polygon has {} vertices (at least 3 needed)",
+                    vertices.len()
+                ));
+            }
+            for vertex in vertices {
+                let (Some(x), Some(y)) = (number(vertex, "x"), number(vertex, "y")) else {
+                    return Some("polygon vertex needs x and y".to_string());
+                };
+                if !inside(x, y) {
+                    return Some(format!(
+                        "polygon vertex ({}, {}) {} is outside {}",
+                        x, y, unit, bounds
+                    ));
+                }
+            }
+            None
+        }
+        Some(other) => Some(format!("unknown shape type {:?}", other)),
+        None => Some("shape has no type".to_string()),
+    }
+}
+
+/// Check the ranges of one region list
+fn check_ranges(ranges: &[JsonValue], path: &str, dims: (u32, u32), problems: &mut Vec<String>) {
+    for (index, range) in ranges.iter().enumerate() {
+        let path = format!("{}/{}", path, index);
+        let problem = match range.get("type").and_then(|v| v.as_str()) {
+            Some("spatial") => match range.get("shape") {
+                Some(shape) => check_shape(shape, dims.0, dims.1),
+                None => Some("spatial range has no shape".to_string()),
+            },
+            Some(_) => None,
+            None => Some("range has no type".to_string()),
+        };
+        if let Some(problem) = problem {
+            problems.push(format!("{}: {}", path, problem));
+        }
+    }
+}
+
+/// Find every `region` list below `value`
+fn walk(value: &JsonValue, path: &str, dims: (u32, u32), problems: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(fields) => {
+            for (key, child) in fields {
+                let child_path = format!("{}/{}", path, key);
+                match child {
+                    JsonValue::Array(ranges) if key == "region" => {
+                        check_ranges(ranges, &child_path, dims, problems)
+                    }
+                    _ => walk(child, &child_path, dims, problems),
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                walk(item, &format!("{}/{}", path, index), dims, problems);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Problems with the spatial regions of a manifest definition for a width x height image.
+/// Locations are given as JSON pointers, starting at the assertion label for assertions.
+pub fn check_regions(manifest: &JsonValue, width: u32, height: u32) -> Vec<String> {
+    let mut problems = Vec::new();
+    let Some(fields) = manifest.as_object() else {
+        return problems;
+    };
+    for (key, value) in fields {
+        match (key.as_str(), value.as_array()) {
+            ("assertions", Some(assertions)) => {
+                for assertion in assertions {
+                    let label = assertion
+                        .get("label")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("assertion");
+                    if let Some(data) = assertion.get("data") {
+                        walk(data, label, (width, height), &mut problems);
+                    }
+                }
+            }
+            _ => walk(value, &format!("/{}", key), (width, height), &mut problems),
+        }
+    }
+    problems
+}
+
+/// Check the manifest's regions against the asset dimensions, warning about problems or,
+/// when `strict`, failing. Assets whose dimensions are unknown are not checked.
+pub fn check_manifest_regions(
+    manifest_json: &str,
+    dimensions: Option<(u32, u32)>,
+    strict: bool,
+) -> Result<()> {
+    let Some((width, height)) = dimensions else {
+        return Ok(());
+    };
+    let manifest: JsonValue =
+        serde_json::from_str(manifest_json).context("Failed to parse manifest JSON")?;
+    let problems = check_regions(&manifest, width, height);
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    if strict {
+        anyhow::bail!(
+            "{} invalid region(s) for the {}x{} image:\n  {}",
+            problems.len(),
+            width,
+            height,
+            problems.join("\n  ")
+        );
+    }
+    status!(
+        "  ⚠ {} invalid region(s) for the {}x{} image:",
+        problems.len(),
+        width,
+        height
+    );
+    for problem in &problems {
+        status!("    - {}", problem);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn spatial(shape: JsonValue) -> JsonValue {
+        json!({ "type": "spatial", "shape": shape })
+    }
+
+    fn rectangle(unit: &str, x: f64, y: f64, w: f64, h: f64) -> JsonValue {
+        spatial(json!({
+            "type": "rectangle",
+            "unit": unit,
+            "origin": { "x": x, "y": y },
+            "width": w,
+            "height": h,
+        }))
+    }
+
+    fn actions_with_regions(ranges: Vec<JsonValue>) -> JsonValue {
+        json!({
+            "assertions": [{
+                "label": "c2pa.actions.v2",
+                "data": {
+                    "actions": [{ "action": "c2pa.edited", "changes": [{ "region": ranges }] }]
+                }
+            }]
+        })
+    }
+
+    #[test]
+    fn test_rectangles_in_pixels_and_percent() {
+        let manifest = actions_with_regions(vec![
+            rectangle("pixel", 100.0, 150.0, 400.0, 200.0),
+            rectangle("pixel", 100.0, 150.0, 400.0, 300.0),
+            rectangle("percent", 20.0, 10.0, 90.0, 50.0),
+            rectangle("percent", 0.0, 0.0, 100.0, 100.0),
+            rectangle("pixel", 10.0, 10.0, 0.0, 5.0),
+        ]);
+        let problems = check_regions(&manifest, 710, 430);
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems[0].starts_with("c2pa.actions.v2/actions/0/changes/0/region/1:"));
+        assert!(problems[0].contains("beyond the 710x430 image"));
+        assert!(problems[1].contains("region/2") && problems[1].contains("0-100%"));
+        assert!(problems[2].contains("non-positive size"));
+    }
+
+    #[test]
+    fn test_circles_polygons_and_malformed_shapes() {
+        let circle = |x: u32| {
+            spatial(json!({
+                "type": "circle", "unit": "pixel", "origin": { "x": x, "y": 50 }, "width": 100
+            }))
+        };
+        let polygon = |unit: &str, vertices: JsonValue| {
+            spatial(json!({ "type": "polygon", "unit": unit, "vertices": vertices }))
+        };
+        let manifest = actions_with_regions(vec![
+            circle(50),
+            circle(20),
+            polygon(
+                "percent",
+                json!([{ "x": 0, "y": 0 }, { "x": 50, "y": 100 }, { "x": 100, "y": 0 }]),
+            ),
+            polygon("pixel", json!([{ "x": 0, "y": 0 }])),
+            spatial(json!({ "type": "rectangle", "unit": "inch", "origin": { "x": 0, "y": 0 } })),
+            json!({ "type": "spatial" }),
+            json!({ "type": "temporal", "time": { "start": "0", "end": "1" } }),
+        ]);
+        let problems = check_regions(&manifest, 200, 100);
+        let failing: Vec<&str> = problems
+            .iter()
+            .map(|p| p.split(':').next().unwrap().rsplit('/').next().unwrap())
+            .collect();
+        assert_eq!(failing, ["1", "3", "4", "5"], "{:?}", problems);
+    }
+
+    #[test]
+    fn test_regions_outside_assertions() {
+        // Ingredient regions of interest are region maps too; edit rectangles are not ranges
+        let manifest = json!({
+            "ingredients": [{
+                "regionOfInterest": { "region": [rectangle("pixel", 0.0, 0.0, 300.0, 10.0)] }
+            }],
+            "edits": [{
+                "type": "crop", "region": { "x": 0, "y": 0, "width": 5000, "height": 5000 }
+            }]
+        });
+        let problems = check_regions(&manifest, 200, 100);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("/ingredients/0/regionOfInterest/region/0:"));
+    }
+}
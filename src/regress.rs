@@ -0,0 +1,258 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Golden-file regression testing of extracted manifests.
+//!
+//! Each manifest is signed onto a reference asset, then extracted in both the standard
+//! and JPEG Trust formats. The normalized JSON is compared against stored goldens, so
+//! any change in how c2pa-rs serializes assertions shows up as a diff.
+
+use anyhow::{Context, Result};
+use c2pa::SigningAlg;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::diff::{diff_manifest_stores, print_differences};
+use crate::normalize::normalize_manifest_json;
+use crate::{KeySource, ProcessingConfig};
+
+/// Configuration for a regression run
+pub struct RegressConfig<'a> {
+    /// Asset every manifest is signed onto
+    pub asset: &'a Path,
+    /// Directory holding the golden JSON files
+    pub goldens_dir: &'a Path,
+    /// Directory for the signed assets
+    pub output_dir: &'a Path,
+    pub cert: &'a Path,
+    pub key: KeySource<'a>,
+    pub signing_alg: SigningAlg,
+    pub allow_self_signed: bool,
+    /// Write the current output as the new goldens instead of comparing
+    pub update_goldens: bool,
+}
+
+/// Outcome of comparing one extraction against its golden file
+enum GoldenResult {
+    Matched,
+    Updated,
+    Missing,
+    Differs(usize),
+}
+
+/// Path of the golden file for a manifest and extraction format
+fn golden_path(goldens_dir: &Path, manifest_stem: &str, use_jpt_format: bool) -> PathBuf {
+    let suffix = if use_jpt_format { "_jpt.json" } else { ".json" };
+    goldens_dir.join(format!("{}{}", manifest_stem, suffix))
+}
+
+/// Compare (or update) a normalized extraction against its golden file
+fn check_golden(golden: &Path, current: &JsonValue, update: bool) -> Result<GoldenResult> {
+    if update {
+        let pretty = serde_json::to_string_pretty(current).context("Failed to format JSON")?;
+        fs::write(golden, pretty + "\n")
+            .context(format!("Failed to write golden file: {:?}", golden))?;
+        return Ok(GoldenResult::Updated);
+    }
+
+    if !golden.exists() {
+        return Ok(GoldenResult::Missing);
+    }
+
+    let expected: JsonValue = serde_json::from_str(
+        &fs::read_to_string(golden).context(format!("Failed to read golden file: {:?}", golden))?,
+    )
+    .context(format!("Failed to parse golden file: {:?}", golden))?;
+
+    let differences = diff_manifest_stores(&expected, current);
+    if differences.is_empty() {
+        Ok(GoldenResult::Matched)
+    } else {
+        print_differences(&differences);
+        Ok(GoldenResult::Differs(differences.len()))
+    }
+}
+
+/// Sign, extract and compare a single manifest; returns true if all goldens matched
+fn regress_manifest(manifest_path: &Path, config: &RegressConfig) -> Result<bool> {
+    let manifest_stem = manifest_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .context("Manifest file has no filename")?;
+    let asset_ext = config
+        .asset
+        .extension()
+        .and_then(|s| s.to_str())
+        .context("Asset file has no extension")?;
+
+    let manifest_json = fs::read_to_string(manifest_path)
+        .context(format!("Failed to read manifest: {:?}", manifest_path))?;
+    let ingredients_base_dir = manifest_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let signed_path = config
+        .output_dir
+        .join(format!("{}.{}", manifest_stem, asset_ext));
+
+    let processing_config = ProcessingConfig {
+        allow_self_signed: config.allow_self_signed,
+        ..ProcessingConfig::new(
+            &manifest_json,
+            ingredients_base_dir,
+            config.cert,
+            config.key,
+            config.signing_alg,
+        )
+    };
+    crate::process_single_file(config.asset, &signed_path, &processing_config)?;
+
+    let mut all_matched = true;
+    for use_jpt_format in [false, true] {
+        let store = crate::read_manifest_store(&signed_path, use_jpt_format)?;
+        let extracted: JsonValue =
+            serde_json::from_str(&store.json).context("Failed to parse manifest JSON")?;
+        let current = normalize_manifest_json(&extracted);
+
+        let golden = golden_path(config.goldens_dir, manifest_stem, use_jpt_format);
+        let format_name = if use_jpt_format {
+            "JPEG Trust"
+        } else {
+            "standard"
+        };
+
+        match check_golden(&golden, &current, config.update_goldens)? {
+            GoldenResult::Matched => println!("  ✓ {} extraction matches golden", format_name),
+            GoldenResult::Updated => println!("  ✓ Updated golden: {:?}", golden),
+            GoldenResult::Missing => {
+                println!("  ✗ Missing golden: {:?}", golden);
+                all_matched = false;
+            }
+            GoldenResult::Differs(count) => {
+                println!(
+                    "  ✗ {} extraction differs from golden: {} difference(s)",
+                    format_name, count
+                );
+                all_matched = false;
+            }
+        }
+    }
+
+    Ok(all_matched)
+}
+
+/// Run the regression suite over a set of manifest files
+pub fn run_regression(manifest_paths: &[PathBuf], config: &RegressConfig) -> Result<()> {
+    println!("=== Golden-file regression ===");
+    println!("  Asset: {:?}", config.asset);
+    println!("  Goldens: {:?}", config.goldens_dir);
+    if config.update_goldens {
+        println!("  Mode: updating goldens");
+    }
+
+    if !config.asset.exists() {
+        anyhow::bail!("Regression asset does not exist: {:?}", config.asset);
+    }
+
+    fs::create_dir_all(config.goldens_dir).context("Failed to create goldens directory")?;
+    fs::create_dir_all(config.output_dir).context("Failed to create output directory")?;
+
+    let mut passed = 0;
+    let mut failed = Vec::new();
+
+    for manifest_path in manifest_paths {
+        match regress_manifest(manifest_path, config) {
+            Ok(true) => passed += 1,
+            Ok(false) => failed.push(manifest_path.clone()),
+            Err(e) => {
+                eprintln!("Error processing {:?}: {:#}", manifest_path, e);
+                failed.push(manifest_path.clone());
+            }
+        }
+    }
+
+    println!("\n=== Regression Summary ===");
+    println!("  Passed: {}", passed);
+    println!("  Failed: {}", failed.len());
+    println!("  Total: {}", manifest_paths.len());
+
+    if !failed.is_empty() {
+        println!("\n=== Manifests with Regressions ===");
+        for path in &failed {
+            println!("  {:?}", path);
+        }
+        anyhow::bail!(
+            "{} manifest(s) differ from their goldens (re-run with --update-goldens to accept)",
+            failed.len()
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_golden(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("c2pa_regress_unit_tests");
+        fs::create_dir_all(&dir).expect("Failed to create temp dir");
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_golden_path_suffixes() {
+        let dir = Path::new("goldens");
+        assert_eq!(
+            golden_path(dir, "p-actions-created", false),
+            dir.join("p-actions-created.json")
+        );
+        assert_eq!(
+            golden_path(dir, "p-actions-created", true),
+            dir.join("p-actions-created_jpt.json")
+        );
+    }
+
+    #[test]
+    fn test_check_golden_missing_then_updated_then_matched() {
+        let golden = temp_golden("roundtrip.json");
+        let current = json!({ "manifests": [{ "assertions": { "c2pa.actions": {} } }] });
+
+        assert!(matches!(
+            check_golden(&golden, &current, false).unwrap(),
+            GoldenResult::Missing
+        ));
+        assert!(matches!(
+            check_golden(&golden, &current, true).unwrap(),
+            GoldenResult::Updated
+        ));
+        assert!(matches!(
+            check_golden(&golden, &current, false).unwrap(),
+            GoldenResult::Matched
+        ));
+    }
+
+    #[test]
+    fn test_check_golden_reports_differences() {
+        let golden = temp_golden("differs.json");
+        let expected = json!({ "manifests": [{ "assertions": { "c2pa.actions": { "v": 1 } } }] });
+        let current = json!({ "manifests": [{ "assertions": { "c2pa.actions": { "v": 2 } } }] });
+
+        check_golden(&golden, &expected, true).unwrap();
+        assert!(matches!(
+            check_golden(&golden, &current, false).unwrap(),
+            GoldenResult::Differs(1)
+        ));
+    }
+}
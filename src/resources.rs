@@ -0,0 +1,251 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Export of binary resources referenced by a manifest store.
+//!
+//! `Reader::json()` only lists resources as `{ "format", "identifier" }` references
+//! (claim and ingredient thumbnails, `c2pa.icon`s, data boxes, ingredient manifest data).
+//! This writes each one to disk and points the extracted JSON at the written files.
+
+use anyhow::{Context, Result};
+use c2pa::Reader;
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Collect every resource reference (identifier -> format) in a manifest store JSON
+fn collect_resource_refs(value: &JsonValue, refs: &mut BTreeMap<String, String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let (Some(JsonValue::String(identifier)), Some(JsonValue::String(format))) =
+                (map.get("identifier"), map.get("format"))
+            {
+                refs.entry(identifier.clone())
+                    .or_insert_with(|| format.clone());
+            }
+            for child in map.values() {
+                collect_resource_refs(child, refs);
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                collect_resource_refs(item, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// File extension for a resource format (MIME type or bare extension)
+fn extension_for_format(format: &str) -> String {
+    match format.to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" | "jpeg" => "jpg".to_string(),
+        "image/svg+xml" => "svg".to_string(),
+        "application/json" => "json".to_string(),
+        "application/cbor" => "cbor".to_string(),
+        "application/c2pa" | "application/x-c2pa-manifest-store" => "c2pa".to_string(),
+        "text/plain" => "txt".to_string(),
+        "application/octet-stream" => "bin".to_string(),
+        other => {
+            let subtype = other.rsplit('/').next().unwrap_or(other);
+            let ext: String = subtype
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric())
+                .collect();
+            if ext.is_empty() {
+                "bin".to_string()
+            } else {
+                ext
+            }
+        }
+    }
+}
+
+/// Predictable file name for a resource identifier
+///
+/// JUMBF URIs such as `self#jumbf=/c2pa/urn:c2pa:.../c2pa.assertions/c2pa.thumbnail.claim.jpeg`
+/// become `urn_c2pa_...__c2pa.assertions_c2pa.thumbnail.claim.jpeg`.
+fn resource_file_name(identifier: &str, format: &str) -> String {
+    let path = identifier
+        .strip_prefix("self#jumbf=")
+        .unwrap_or(identifier)
+        .trim_start_matches('/');
+    let path = path.strip_prefix("c2pa/").unwrap_or(path);
+
+    let parts: Vec<String> = path
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // Keep the manifest label separate from the path inside the manifest
+    let mut name = match parts.split_first() {
+        Some((label, rest)) if label.starts_with("urn_") && !rest.is_empty() => {
+            format!("{}__{}", label, rest.join("_"))
+        }
+        _ => parts.join("_"),
+    };
+    if name.is_empty() {
+        name = "resource".to_string();
+    }
+
+    let ext = extension_for_format(format);
+    let lower = name.to_ascii_lowercase();
+    if !(lower.ends_with(&format!(".{}", ext)) || (ext == "jpg" && lower.ends_with(".jpeg"))) {
+        name = format!("{}.{}", name, ext);
+    }
+    name
+}
+
+/// Replace the identifiers of exported resources with the paths of the written files
+fn rewrite_identifiers(value: &mut JsonValue, paths: &BTreeMap<String, String>) {
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::String(identifier)) = map.get_mut("identifier") {
+                if let Some(path) = paths.get(identifier.as_str()) {
+                    *identifier = path.clone();
+                }
+            }
+            for child in map.values_mut() {
+                rewrite_identifiers(child, paths);
+            }
+        }
+        JsonValue::Array(items) => {
+            for item in items {
+                rewrite_identifiers(item, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Path written into the JSON: relative to the JSON file when possible
+fn reference_path(file: &Path, json_dir: &Path) -> String {
+    file.strip_prefix(json_dir)
+        .unwrap_or(file)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// Write every resource referenced by the manifest store into `resources_dir` and
+/// rewrite the references in `output_json` to point at the written files
+///
+/// `standard_json` is the standard c2pa-rs JSON of the same store, which lists the
+/// resources whatever format `output_json` is in. Returns the exported file paths.
+pub fn export_resources(
+    reader: &Reader,
+    standard_json: &JsonValue,
+    output_json: &mut JsonValue,
+    resources_dir: &Path,
+    json_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut refs = BTreeMap::new();
+    collect_resource_refs(standard_json, &mut refs);
+    if refs.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    fs::create_dir_all(resources_dir).context(format!(
+        "Failed to create resources directory: {:?}",
+        resources_dir
+    ))?;
+
+    let mut written = Vec::new();
+    let mut paths = BTreeMap::new();
+    for (identifier, format) in &refs {
+        let file_path = resources_dir.join(resource_file_name(identifier, format));
+        let file = fs::File::create(&file_path)
+            .context(format!("Failed to create resource file: {:?}", file_path))?;
+        reader
+            .resource_to_stream(identifier, file)
+            .context(format!("Failed to export resource: {}", identifier))?;
+
+        paths.insert(identifier.clone(), reference_path(&file_path, json_dir));
+        written.push(file_path);
+    }
+
+    rewrite_identifiers(output_json, &paths);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const THUMBNAIL: &str =
+        "self#jumbf=/c2pa/urn:c2pa:1234/c2pa.assertions/c2pa.thumbnail.claim.jpeg";
+    const ICON: &str = "self#jumbf=/c2pa/urn:c2pa:1234/c2pa.assertions/c2pa.icon";
+
+    #[test]
+    fn test_collect_resource_refs() {
+        let store = json!({
+            "manifests": {
+                "urn:c2pa:1234": {
+                    "thumbnail": { "format": "image/jpeg", "identifier": THUMBNAIL },
+                    "claim_generator_info": [{ "name": "tool", "icon": { "format": "image/svg+xml", "identifier": ICON } }],
+                    "ingredients": [{ "title": "a.jpg", "thumbnail": { "format": "image/jpeg", "identifier": THUMBNAIL } }]
+                }
+            }
+        });
+
+        let mut refs = BTreeMap::new();
+        collect_resource_refs(&store, &mut refs);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[THUMBNAIL], "image/jpeg");
+        assert_eq!(refs[ICON], "image/svg+xml");
+    }
+
+    #[test]
+    fn test_resource_file_name() {
+        assert_eq!(
+            resource_file_name(THUMBNAIL, "image/jpeg"),
+            "urn_c2pa_1234__c2pa.assertions_c2pa.thumbnail.claim.jpeg"
+        );
+        assert_eq!(
+            resource_file_name(ICON, "image/svg+xml"),
+            "urn_c2pa_1234__c2pa.assertions_c2pa.icon.svg"
+        );
+        assert_eq!(
+            resource_file_name(
+                "self#jumbf=/c2pa/urn:c2pa:1234/c2pa.databoxes/c2pa.data",
+                "application/octet-stream"
+            ),
+            "urn_c2pa_1234__c2pa.databoxes_c2pa.data.bin"
+        );
+        assert_eq!(resource_file_name("", "image/png"), "resource.png");
+    }
+
+    #[test]
+    fn test_rewrite_identifiers_only_touches_resource_refs() {
+        let mut json = json!({
+            "thumbnail": { "format": "image/jpeg", "identifier": THUMBNAIL },
+            "assertions": [{ "url": THUMBNAIL }]
+        });
+        let paths = BTreeMap::from([(THUMBNAIL.to_string(), "res/thumb.jpg".to_string())]);
+
+        rewrite_identifiers(&mut json, &paths);
+        assert_eq!(json["thumbnail"]["identifier"], "res/thumb.jpg");
+        assert_eq!(json["assertions"][0]["url"], THUMBNAIL);
+    }
+}
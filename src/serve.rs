@@ -0,0 +1,527 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Local HTTP service for signing, extracting and validating.
+//!
+//! Endpoints:
+//! - `POST /sign`: multipart form with an `asset` file and a `manifest` JSON part
+//!   (optional `format`); responds with the signed asset
+//! - `POST /extract[?jpt=true]`: the asset as a multipart `asset` part or as the raw body
+//!   (format from `?format=` or `Content-Type`); responds with the manifest store JSON
+//! - `POST /validate`: a JSON document (raw body or multipart `json` part); responds with
+//!   the indicators schema validation result
+//! - `GET /health`: readiness check
+//!
+//! Requests are handled one at a time. The service is meant for local test tooling and
+//! should only be bound to a loopback address.
+
+use anyhow::{Context, Result};
+use c2pa::SigningAlg;
+use serde_json::{json, Value as JsonValue};
+use std::io::Read;
+use std::path::Path;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::{KeySource, ProcessingConfig};
+
+/// Largest request body accepted
+const MAX_REQUEST_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Signing credentials used by `/sign`
+pub struct SigningSetup<'a> {
+    pub cert: &'a Path,
+    pub key: KeySource<'a>,
+    pub signing_alg: SigningAlg,
+    /// DER OCSP response stapled to each signature
+    pub ocsp_response: Option<Vec<u8>>,
+}
+
+/// Configuration for the HTTP service
+pub struct ServeConfig<'a> {
+    pub bind: &'a str,
+    /// `/sign` is unavailable without signing credentials
+    pub signing: Option<SigningSetup<'a>>,
+    pub allow_self_signed: bool,
+    pub thumbnail_asset: bool,
+    pub thumbnail_ingredients: bool,
+    /// Base directory for file-based ingredients referenced by posted manifests
+    pub ingredients_base_dir: &'a Path,
+    pub deterministic_seed: Option<&'a str>,
+    /// Key and certificate that sign CAWG identity assertions
+    pub identity: Option<crate::identity::IdentityKey<'a>>,
+    pub strict_regions: bool,
+    /// What happens to a manifest store already in a posted asset
+    pub existing: crate::existing::ExistingPolicy,
+}
+
+/// HTTP response produced by a handler
+struct Reply {
+    status: u16,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(status: u16, value: &JsonValue) -> Self {
+        Reply {
+            status,
+            content_type: "application/json".to_string(),
+            body: serde_json::to_vec_pretty(value).unwrap_or_default(),
+        }
+    }
+
+    fn error(status: u16, message: impl std::fmt::Display) -> Self {
+        Self::json(status, &json!({ "error": message.to_string() }))
+    }
+}
+
+/// One part of a multipart/form-data body
+#[derive(Debug)]
+struct Part {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|pos| pos + from)
+}
+
+/// Value of a `key=value` parameter in a header such as Content-Type or Content-Disposition
+fn header_param(header: &str, key: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let (name, value) = param.trim().split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case(key)
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Parse a multipart/form-data body
+fn parse_multipart(content_type: &str, body: &[u8]) -> Result<Vec<Part>> {
+    let boundary =
+        header_param(content_type, "boundary").context("Multipart body has no boundary")?;
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let next_delimiter = format!("\r\n--{}", boundary).into_bytes();
+
+    let mut parts = Vec::new();
+    let mut pos =
+        find(body, &delimiter, 0).context("Multipart boundary not found")? + delimiter.len();
+
+    // Each part: CRLF, headers, blank line, data, CRLF--boundary
+    while !body[pos..].starts_with(b"--") {
+        let headers_start = pos + 2;
+        let headers_end =
+            find(body, b"\r\n\r\n", headers_start).context("Malformed multipart part headers")?;
+        let headers = String::from_utf8_lossy(&body[headers_start..headers_end]);
+
+        let data_start = headers_end + 4;
+        let data_end =
+            find(body, &next_delimiter, data_start).context("Unterminated multipart part")?;
+
+        let mut part = Part {
+            name: String::new(),
+            filename: None,
+            content_type: None,
+            data: body[data_start..data_end].to_vec(),
+        };
+        for line in headers.lines() {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            if name.trim().eq_ignore_ascii_case("content-disposition") {
+                part.name = header_param(value, "name").unwrap_or_default();
+                part.filename = header_param(value, "filename");
+            } else if name.trim().eq_ignore_ascii_case("content-type") {
+                part.content_type = Some(value.trim().to_string());
+            }
+        }
+        parts.push(part);
+
+        pos = data_end + next_delimiter.len();
+        if pos >= body.len() {
+            break;
+        }
+    }
+
+    Ok(parts)
+}
+
+/// Decode `%XX` escapes and `+` in a query string component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        out.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Split a request URL into its path and query parameters
+fn split_url(url: &str) -> (&str, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+    (path, params)
+}
+
+fn query_param<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    params
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+fn is_true(value: Option<&str>) -> bool {
+    matches!(value, Some("true") | Some("1") | Some("yes"))
+}
+
+/// Determine the asset format from an explicit format, a content type or a filename
+fn asset_format(
+    explicit: Option<&str>,
+    content_type: Option<&str>,
+    filename: Option<&str>,
+) -> Result<String> {
+    if explicit.is_some() {
+        return crate::resolve_format(explicit, Path::new(crate::STDIO_PATH));
+    }
+    if let Some(content_type) = content_type {
+        let mime = content_type.split(';').next().unwrap_or_default().trim();
+        if mime.contains('/')
+            && mime != "application/octet-stream"
+            && !mime.starts_with("multipart/")
+        {
+            return Ok(mime.to_string());
+        }
+    }
+    match filename {
+        Some(filename) => crate::resolve_format(None, Path::new(filename)),
+        None => anyhow::bail!(
+            "Cannot determine the asset format: pass a format, a Content-Type or a filename"
+        ),
+    }
+}
+
+/// Request data needed by the handlers
+struct IncomingRequest {
+    path: String,
+    params: Vec<(String, String)>,
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl IncomingRequest {
+    fn multipart(&self) -> Result<Option<Vec<Part>>> {
+        match &self.content_type {
+            Some(ct) if ct.starts_with("multipart/form-data") => {
+                parse_multipart(ct, &self.body).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+fn find_part<'a>(parts: &'a [Part], name: &str) -> Option<&'a Part> {
+    parts.iter().find(|part| part.name == name)
+}
+
+fn part_text(part: &Part) -> String {
+    String::from_utf8_lossy(&part.data).into_owned()
+}
+
+fn handle_sign(request: &IncomingRequest, config: &ServeConfig) -> Reply {
+    let Some(signing) = &config.signing else {
+        return Reply::error(
+            503,
+            "Signing is not configured: start the service with --cert and one of --key, --signer-cmd or --pkcs11-module",
+        );
+    };
+
+    let parts = match request.multipart() {
+        Ok(Some(parts)) => parts,
+        Ok(None) => return Reply::error(400, "POST /sign expects multipart/form-data"),
+        Err(e) => return Reply::error(400, format!("{:#}", e)),
+    };
+    let Some(asset) = find_part(&parts, "asset") else {
+        return Reply::error(400, "Missing 'asset' part");
+    };
+    let Some(manifest) = find_part(&parts, "manifest") else {
+        return Reply::error(400, "Missing 'manifest' part");
+    };
+
+    let explicit_format = find_part(&parts, "format")
+        .map(part_text)
+        .or_else(|| query_param(&request.params, "format").map(str::to_string));
+    let format = match asset_format(
+        explicit_format.as_deref(),
+        asset.content_type.as_deref(),
+        asset.filename.as_deref(),
+    ) {
+        Ok(format) => format,
+        Err(e) => return Reply::error(400, format!("{:#}", e)),
+    };
+
+    let manifest_json = part_text(manifest);
+    if let Err(e) = serde_json::from_str::<JsonValue>(&manifest_json) {
+        return Reply::error(400, format!("Invalid manifest JSON: {}", e));
+    }
+
+    let processing_config = ProcessingConfig {
+        allow_self_signed: config.allow_self_signed,
+        thumbnail_asset: config.thumbnail_asset,
+        thumbnail_ingredients: config.thumbnail_ingredients,
+        deterministic_seed: config.deterministic_seed,
+        ocsp_response: signing.ocsp_response.as_deref(),
+        identity: config.identity,
+        strict_regions: config.strict_regions,
+        existing: config.existing,
+        ..ProcessingConfig::new(
+            &manifest_json,
+            config.ingredients_base_dir,
+            signing.cert,
+            signing.key,
+            signing.signing_alg,
+        )
+    };
+    let context_name = asset.filename.as_deref().unwrap_or("asset");
+
+    match crate::sign_asset_bytes(
+        &processing_config,
+        &format,
+        context_name,
+        asset.data.clone(),
+    ) {
+        Ok(signed) => Reply {
+            status: 200,
+            content_type: format,
+            body: signed,
+        },
+        Err(e) => Reply::error(422, format!("{:#}", e)),
+    }
+}
+
+fn handle_extract(request: &IncomingRequest) -> Reply {
+    let parts = match request.multipart() {
+        Ok(parts) => parts,
+        Err(e) => return Reply::error(400, format!("{:#}", e)),
+    };
+
+    let explicit_format = query_param(&request.params, "format");
+    let (data, format, use_jpt_format) = match &parts {
+        Some(parts) => {
+            let Some(asset) = find_part(parts, "asset") else {
+                return Reply::error(400, "Missing 'asset' part");
+            };
+            let form_format = find_part(parts, "format").map(part_text);
+            let format = asset_format(
+                form_format.as_deref().or(explicit_format),
+                asset.content_type.as_deref(),
+                asset.filename.as_deref(),
+            );
+            let jpt = is_true(query_param(&request.params, "jpt"))
+                || find_part(parts, "jpt").is_some_and(|p| is_true(Some(&part_text(p))));
+            (asset.data.clone(), format, jpt)
+        }
+        None => (
+            request.body.clone(),
+            asset_format(explicit_format, request.content_type.as_deref(), None),
+            is_true(query_param(&request.params, "jpt")),
+        ),
+    };
+    let format = match format {
+        Ok(format) => format,
+        Err(e) => return Reply::error(400, format!("{:#}", e)),
+    };
+
+    let store = match crate::read_manifest_store_from_bytes(data, &format, use_jpt_format) {
+        Ok(store) => store,
+        Err(e) => return Reply::error(422, format!("{:#}", e)),
+    };
+    match serde_json::from_str::<JsonValue>(&store.json) {
+        Ok(value) => Reply::json(200, &value),
+        Err(e) => Reply::error(500, format!("Failed to parse manifest JSON: {}", e)),
+    }
+}
+
+fn handle_validate(request: &IncomingRequest, validator: &jsonschema::Validator) -> Reply {
+    let document = match request.multipart() {
+        Ok(Some(parts)) => match find_part(&parts, "json") {
+            Some(part) => part.data.clone(),
+            None => return Reply::error(400, "Missing 'json' part"),
+        },
+        Ok(None) => request.body.clone(),
+        Err(e) => return Reply::error(400, format!("{:#}", e)),
+    };
+
+    let json_value: JsonValue = match serde_json::from_slice(&document) {
+        Ok(value) => value,
+        Err(e) => return Reply::error(400, format!("Invalid JSON: {}", e)),
+    };
+
+    let errors = crate::schema_errors(validator, &json_value);
+    Reply::json(
+        200,
+        &json!({ "valid": errors.is_empty(), "errors": errors }),
+    )
+}
+
+/// Route a request to its handler
+fn handle(
+    method: &Method,
+    request: &IncomingRequest,
+    config: &ServeConfig,
+    validator: &jsonschema::Validator,
+) -> Reply {
+    match (method, request.path.as_str()) {
+        (Method::Get, "/health") => Reply::json(200, &json!({ "status": "ok" })),
+        (Method::Post, "/sign") => handle_sign(request, config),
+        (Method::Post, "/extract") => handle_extract(request),
+        (Method::Post, "/validate") => handle_validate(request, validator),
+        (_, "/sign" | "/extract" | "/validate") => Reply::error(405, "Use POST"),
+        _ => Reply::error(404, format!("Unknown endpoint: {}", request.path)),
+    }
+}
+
+/// Read the parts of a tiny_http request the handlers need
+fn read_request(request: &mut Request) -> Result<IncomingRequest> {
+    let (path, params) = split_url(request.url());
+    let path = path.to_string();
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Content-Type"))
+        .map(|h| h.value.as_str().to_string());
+
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_REQUEST_BYTES)
+        .read_to_end(&mut body)
+        .context("Failed to read request body")?;
+
+    Ok(IncomingRequest {
+        path,
+        params,
+        content_type,
+        body,
+    })
+}
+
+/// Run the HTTP service until the process is stopped
+pub fn serve(config: &ServeConfig) -> Result<()> {
+    println!("=== C2PA test file service ===");
+    let validator = crate::load_indicators_schema()?;
+    let server = Server::http(config.bind)
+        .map_err(|e| anyhow::anyhow!("Failed to bind to {}: {}", config.bind, e))?;
+
+    // Printed on its own line so harnesses binding to port 0 can find the address
+    println!("Listening on http://{}", server.server_addr());
+    if config.signing.is_none() {
+        println!("  Note: /sign is disabled (no --cert with --key, --signer-cmd or --pkcs11-module given)");
+    }
+
+    for mut request in server.incoming_requests() {
+        let method = request.method().clone();
+        let reply = match read_request(&mut request) {
+            Ok(incoming) => handle(&method, &incoming, config, &validator),
+            Err(e) => Reply::error(400, format!("{:#}", e)),
+        };
+        println!("  {} {} -> {}", method, request.url(), reply.status);
+
+        let mut response = Response::from_data(reply.body).with_status_code(reply.status);
+        if let Ok(header) = Header::from_bytes("Content-Type", reply.content_type.as_bytes()) {
+            response = response.with_header(header);
+        }
+        if let Err(e) = request.respond(response) {
+            eprintln!("  Failed to send response: {}", e);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multipart() {
+        let body = b"--XyZ\r\n\
+Content-Disposition: form-data; name=\"manifest\"\r\n\
+\r\n\
+{\"title\": \"t\"}\r\n\
+--XyZ\r\n\
+Content-Disposition: form-data; name=\"asset\"; filename=\"dog.jpg\"\r\n\
+Content-Type: image/jpeg\r\n\
+\r\n\
+\xFF\xD8\r\n\xFF\xD9\r\n\
+--XyZ--\r\n";
+
+        let parts = parse_multipart("multipart/form-data; boundary=XyZ", body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].name, "manifest");
+        assert_eq!(part_text(&parts[0]), "{\"title\": \"t\"}");
+        assert_eq!(parts[1].filename.as_deref(), Some("dog.jpg"));
+        assert_eq!(parts[1].content_type.as_deref(), Some("image/jpeg"));
+        // CRLF inside binary data is preserved
+        assert_eq!(parts[1].data, b"\xFF\xD8\r\n\xFF\xD9");
+    }
+
+    #[test]
+    fn test_split_url() {
+        let (path, params) = split_url("/extract?jpt=true&format=image%2Fpng");
+        assert_eq!(path, "/extract");
+        assert!(is_true(query_param(&params, "jpt")));
+        assert_eq!(query_param(&params, "format"), Some("image/png"));
+    }
+
+    #[test]
+    fn test_asset_format_sources() {
+        assert_eq!(asset_format(Some("png"), None, None).unwrap(), "image/png");
+        assert_eq!(
+            asset_format(None, Some("image/webp"), Some("x.jpg")).unwrap(),
+            "image/webp"
+        );
+        assert_eq!(
+            asset_format(None, Some("application/octet-stream"), Some("x.jpg")).unwrap(),
+            "image/jpeg"
+        );
+        assert!(asset_format(None, None, None).is_err());
+    }
+}
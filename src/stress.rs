@@ -0,0 +1,456 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Stress and size-limit corpus generation (`--stress`).
+//!
+//! Writes one asset per stress case: many ingredients, a very large custom assertion, a
+//! long action list and a deep chain of manifests, each opening the previous asset. A JPEG
+//! manifest store over 64 KiB is split across APP11 segments, so the report counts them.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::binding::{container_boxes, C2PA_BOX};
+use crate::{KeySource, ProcessingConfig};
+
+/// Report file written to the output directory
+const REPORT_FILE: &str = "stress_report.json";
+
+/// Size of each stress case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StressLimits {
+    /// Ingredients in the many-ingredients case
+    pub ingredients: usize,
+    /// Actions in the long-history case
+    pub actions: usize,
+    /// Manifests in the chain case
+    pub depth: usize,
+    /// Size of the large custom assertion in KiB
+    pub assertion_kib: usize,
+}
+
+impl Default for StressLimits {
+    fn default() -> Self {
+        Self {
+            ingredients: 300,
+            actions: 1000,
+            depth: 50,
+            assertion_kib: 1024,
+        }
+    }
+}
+
+impl StressLimits {
+    /// Parse `key=value` pairs separated by commas; missing keys keep their defaults
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut limits = Self::default();
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .context(format!("Stress limits are key=value pairs, got {:?}", pair))?;
+            let value: usize = value
+                .trim()
+                .parse()
+                .context(format!("Stress limit {} must be a number", key))?;
+            if value == 0 {
+                anyhow::bail!("Stress limit {} must be at least 1", key);
+            }
+            match key.trim() {
+                "ingredients" => limits.ingredients = value,
+                "actions" => limits.actions = value,
+                "depth" => limits.depth = value,
+                "assertion-kib" => limits.assertion_kib = value,
+                other => anyhow::bail!(
+                    "Unknown stress limit {:?} (use ingredients, actions, depth or assertion-kib)",
+                    other
+                ),
+            }
+        }
+        Ok(limits)
+    }
+}
+
+/// Options for a stress run
+pub struct StressOptions<'a> {
+    /// Asset every case is signed onto
+    pub asset: &'a Path,
+    pub output_dir: &'a Path,
+    pub limits: StressLimits,
+    pub cert: &'a Path,
+    pub key: KeySource<'a>,
+    pub signing_alg: c2pa::SigningAlg,
+    pub allow_self_signed: bool,
+    pub deterministic_seed: Option<&'a str>,
+}
+
+/// Outcome of one stress case in the report
+#[derive(Serialize)]
+struct StressResult {
+    case: String,
+    output: PathBuf,
+    signed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<u64>,
+    /// Manifests in the output's store
+    #[serde(skip_serializing_if = "Option::is_none")]
+    manifests: Option<usize>,
+    /// APP11 segments holding the manifest store (JPEG only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app11_segments: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn claim_generator_info() -> JsonValue {
+    json!([{ "name": "c2pa-testfile-maker", "version": env!("CARGO_PKG_VERSION") }])
+}
+
+fn created_action() -> JsonValue {
+    json!({
+        "action": "c2pa.created",
+        "digitalSourceType": "http://cv.iptc.org/newscodes/digitalsourcetype/digitalCapture",
+    })
+}
+
+/// Actions assertion with only the c2pa.created action
+fn created_assertion() -> JsonValue {
+    json!({
+        "label": "c2pa.actions.v2",
+        "data": { "actions": [created_action()] },
+        "created": true,
+    })
+}
+
+/// Manifest with `count` componentOf ingredients loaded from `ingredient`, each placed
+fn ingredients_manifest(count: usize, ingredient: &Path) -> JsonValue {
+    let labels: Vec<String> = (1..=count)
+        .map(|i| format!("ingredient-{:04}", i))
+        .collect();
+    let mut actions = vec![created_action()];
+    actions.extend(labels.iter().map(
+        |label| json!({ "action": "c2pa.placed", "parameters": { "ingredientIds": [label] } }),
+    ));
+    let ingredients: Vec<JsonValue> = labels
+        .iter()
+        .map(|label| {
+            json!({
+                "file_path": ingredient,
+                "title": format!("{}.{}", label, extension(ingredient)),
+                "label": label,
+                "relationship": "componentOf",
+            })
+        })
+        .collect();
+    json!({
+        "claim_generator_info": claim_generator_info(),
+        "title": format!("stress: {} ingredients", count),
+        "assertions": [{
+            "label": "c2pa.actions.v2",
+            "data": { "actions": actions },
+            "created": true,
+        }],
+        "ingredients_from_files": ingredients,
+    })
+}
+
+/// Manifest with a custom assertion of about `kib` KiB
+fn large_assertion_manifest(kib: usize) -> JsonValue {
+    json!({
+        "claim_generator_info": claim_generator_info(),
+        "title": format!("stress: {} KiB assertion", kib),
+        "assertions": [
+            created_assertion(),
+            {
+                "label": "com.example.stress.large",
+                "data": {
+                    "description": "Padding to exceed size limits",
+                    "payload": "0123456789abcdef".repeat(kib * 64),
+                },
+            },
+        ],
+    })
+}
+
+/// Manifest with `count` actions, c2pa.created first
+fn actions_manifest(count: usize) -> JsonValue {
+    const EDITS: &[&str] = &[
+        "c2pa.edited",
+        "c2pa.cropped",
+        "c2pa.color_adjustments",
+        "c2pa.filtered",
+    ];
+    let mut actions = vec![created_action()];
+    actions.extend((1..count).map(|i| {
+        json!({
+            "action": EDITS[i % EDITS.len()],
+            "description": format!("Edit {} of {}", i, count - 1),
+        })
+    }));
+    json!({
+        "claim_generator_info": claim_generator_info(),
+        "title": format!("stress: {} actions", count),
+        "assertions": [{
+            "label": "c2pa.actions.v2",
+            "data": { "actions": actions },
+            "created": true,
+        }],
+    })
+}
+
+/// Manifest for one step of the chain, opening the previous step's asset
+fn chain_manifest(step: usize, depth: usize, parent: Option<&Path>) -> JsonValue {
+    let title = format!("stress: chain step {} of {}", step, depth);
+    let Some(parent) = parent else {
+        return json!({
+            "claim_generator_info": claim_generator_info(),
+            "title": title,
+            "assertions": [created_assertion()],
+        });
+    };
+    json!({
+        "claim_generator_info": claim_generator_info(),
+        "title": title,
+        "assertions": [{
+            "label": "c2pa.actions.v2",
+            "data": { "actions": [
+                { "action": "c2pa.opened", "parameters": { "ingredientIds": ["parent"] } },
+                { "action": "c2pa.edited" },
+            ]},
+            "created": true,
+        }],
+        "ingredients_from_files": [{
+            "file_path": parent,
+            "label": "parent",
+            "relationship": "parentOf",
+        }],
+    })
+}
+
+fn extension(path: &Path) -> &str {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+}
+
+/// Sign `manifest` onto `input`
+fn sign(options: &StressOptions, manifest: &JsonValue, input: &Path, output: &Path) -> Result<()> {
+    let manifest_json = serde_json::to_string(manifest)?;
+    // Ingredient paths are the asset and chain steps as given, relative to the working directory
+    let base_dir = std::env::current_dir().context("Failed to read the working directory")?;
+    let config = ProcessingConfig {
+        allow_self_signed: options.allow_self_signed,
+        deterministic_seed: options.deterministic_seed,
+        ..ProcessingConfig::new(
+            &manifest_json,
+            &base_dir,
+            options.cert,
+            options.key,
+            options.signing_alg,
+        )
+    };
+    crate::process_single_file(input, output, &config)
+}
+
+/// Sign every step of the chain; each step is kept in the `chain` directory
+fn sign_chain(options: &StressOptions, output: &Path) -> Result<()> {
+    let depth = options.limits.depth;
+    let chain_dir = options.output_dir.join("chain");
+    fs::create_dir_all(&chain_dir).context("Failed to create chain directory")?;
+    let mut parent: Option<PathBuf> = None;
+    for step in 1..=depth {
+        let step_output = if step == depth {
+            output.to_path_buf()
+        } else {
+            chain_dir.join(format!("step-{:04}.{}", step, extension(options.asset)))
+        };
+        let input = parent.as_deref().unwrap_or(options.asset);
+        let manifest = chain_manifest(step, depth, parent.as_deref());
+        sign(options, &manifest, input, &step_output)
+            .context(format!("Chain step {} of {}", step, depth))?;
+        parent = Some(step_output);
+    }
+    Ok(())
+}
+
+/// Read back a signed case: size, manifest count, APP11 segments and validation state
+fn inspect(output: &Path, result: &mut StressResult) -> Result<()> {
+    let data = fs::read(output).context(format!("Failed to read {:?}", output))?;
+    result.bytes = Some(data.len() as u64);
+    if extension(output).eq_ignore_ascii_case("jpg")
+        || extension(output).eq_ignore_ascii_case("jpeg")
+    {
+        let boxes = container_boxes("image/jpeg", &data)?.unwrap_or_default();
+        result.app11_segments = Some(boxes.iter().filter(|(name, _, _)| name == C2PA_BOX).count());
+    }
+    let store = crate::read_manifest_store(output, false)?;
+    let store: JsonValue =
+        serde_json::from_str(&store.json).context("Failed to parse manifest JSON")?;
+    result.manifests = store
+        .get("manifests")
+        .and_then(|v| v.as_object())
+        .map(|m| m.len());
+    result.validation_state = store
+        .get("validation_state")
+        .and_then(|v| v.as_str())
+        .map(str::to_string);
+    Ok(())
+}
+
+/// Sign every stress case onto the asset and write the report
+pub fn run_stress(options: &StressOptions) -> Result<()> {
+    let limits = options.limits;
+    let ext = extension(options.asset);
+    fs::create_dir_all(options.output_dir).context("Failed to create output directory")?;
+    println!("=== Stress corpus ===");
+    println!("  Asset: {:?}", options.asset);
+    println!("  Limits: {:?}", limits);
+
+    let cases = [
+        format!("stress-ingredients-{}", limits.ingredients),
+        format!("stress-assertion-{}kib", limits.assertion_kib),
+        format!("stress-actions-{}", limits.actions),
+        format!("stress-chain-{}", limits.depth),
+    ];
+    let mut results = Vec::new();
+    for (index, case) in cases.iter().enumerate() {
+        let output = options.output_dir.join(format!("{}.{}", case, ext));
+        let signed = match index {
+            0 => sign(
+                options,
+                &ingredients_manifest(limits.ingredients, options.asset),
+                options.asset,
+                &output,
+            ),
+            1 => sign(
+                options,
+                &large_assertion_manifest(limits.assertion_kib),
+                options.asset,
+                &output,
+            ),
+            2 => sign(
+                options,
+                &actions_manifest(limits.actions),
+                options.asset,
+                &output,
+            ),
+            _ => sign_chain(options, &output),
+        };
+        let mut result = StressResult {
+            case: case.clone(),
+            output: output.clone(),
+            signed: signed.is_ok(),
+            bytes: None,
+            manifests: None,
+            app11_segments: None,
+            validation_state: None,
+            error: None,
+        };
+        if let Err(e) = signed.and_then(|()| inspect(&output, &mut result)) {
+            result.error = Some(format!("{:#}", e));
+        }
+        results.push(result);
+    }
+
+    println!("\n=== Stress Summary ===");
+    let mut failed = 0;
+    for result in &results {
+        if let Some(error) = &result.error {
+            failed += 1;
+            println!("  ✗ {}: {}", result.case, error);
+            continue;
+        }
+        let segments = result
+            .app11_segments
+            .map(|n| format!(", {} APP11 segment(s)", n))
+            .unwrap_or_default();
+        println!(
+            "  ✓ {}: {} bytes, {} manifest(s){} ({})",
+            result.case,
+            result.bytes.unwrap_or_default(),
+            result.manifests.unwrap_or_default(),
+            segments,
+            result.validation_state.as_deref().unwrap_or("Unknown")
+        );
+    }
+
+    let report_path = options.output_dir.join(REPORT_FILE);
+    let report = serde_json::to_string_pretty(&results).context("Failed to format report")?;
+    fs::write(&report_path, report + "\n").context("Failed to write stress report")?;
+    println!("  Report: {:?}", report_path);
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} stress case(s) failed", failed, results.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_limits() -> Result<()> {
+        assert_eq!(StressLimits::parse("")?, StressLimits::default());
+        let limits = StressLimits::parse("ingredients=5, depth=3,assertion-kib=70")?;
+        assert_eq!(
+            limits,
+            StressLimits {
+                ingredients: 5,
+                depth: 3,
+                assertion_kib: 70,
+                ..StressLimits::default()
+            }
+        );
+        assert!(StressLimits::parse("depth=0").is_err());
+        assert!(StressLimits::parse("width=3").is_err());
+        assert!(StressLimits::parse("depth").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_manifest_sizes() {
+        let manifest = ingredients_manifest(3, Path::new("/assets/Dog.jpg"));
+        assert_eq!(
+            manifest["ingredients_from_files"].as_array().unwrap().len(),
+            3
+        );
+        let actions = &manifest["assertions"][0]["data"]["actions"];
+        assert_eq!(actions.as_array().unwrap().len(), 4);
+        assert_eq!(
+            actions[3]["parameters"]["ingredientIds"][0],
+            "ingredient-0003"
+        );
+
+        let payload = &large_assertion_manifest(2)["assertions"][1]["data"]["payload"];
+        assert_eq!(payload.as_str().unwrap().len(), 2048);
+
+        let actions = &actions_manifest(10)["assertions"][0]["data"]["actions"];
+        assert_eq!(actions.as_array().unwrap().len(), 10);
+        assert_eq!(actions[0]["action"], "c2pa.created");
+
+        let first = chain_manifest(1, 2, None);
+        assert_eq!(
+            first["assertions"][0]["data"]["actions"][0]["action"],
+            "c2pa.created"
+        );
+        let second = chain_manifest(2, 2, Some(Path::new("step-0001.jpg")));
+        assert_eq!(
+            second["ingredients_from_files"][0]["relationship"],
+            "parentOf"
+        );
+    }
+}
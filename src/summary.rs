@@ -0,0 +1,277 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Human-readable validation summary of a manifest store.
+//!
+//! The summary is built from the standard c2pa-rs JSON (`Reader::json()`), which carries
+//! the validation results, signature info and the ingredient graph for every format.
+
+use serde_json::Value as JsonValue;
+use std::fmt::Write;
+
+/// Maximum ingredient nesting shown in the ingredient tree
+const MAX_INGREDIENT_DEPTH: usize = 16;
+
+/// Status code categories reported by c2pa-rs, in display order
+const STATUS_CATEGORIES: &[(&str, &str)] = &[
+    ("success", "Success"),
+    ("informational", "Informational"),
+    ("failure", "Failure"),
+];
+
+/// Build the validation summary text for a manifest store in standard JSON format
+pub fn build_summary(store: &JsonValue) -> String {
+    let mut out = String::new();
+    let active_label = store.get("active_manifest").and_then(|v| v.as_str());
+    let active = active_label.and_then(|label| store.pointer(&manifest_pointer(label)));
+
+    let state = store
+        .get("validation_state")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown");
+    let _ = writeln!(out, "Validation state: {}", state);
+    let _ = writeln!(out, "Active manifest: {}", active_label.unwrap_or("none"));
+
+    // Signer identity and signing time
+    let signature = active.and_then(|m| m.get("signature_info"));
+    let field = |name: &str| {
+        signature
+            .and_then(|s| s.get(name))
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown")
+    };
+    let _ = writeln!(out, "Signer: {}", field("common_name"));
+    let _ = writeln!(out, "  Issuer: {}", field("issuer"));
+    let _ = writeln!(out, "  Algorithm: {}", field("alg"));
+    let _ = writeln!(out, "  Certificate serial: {}", field("cert_serial_number"));
+    let _ = writeln!(
+        out,
+        "Signing time: {}",
+        signature
+            .and_then(|s| s.get("time"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("none (no timestamp)")
+    );
+
+    // Status codes of the active manifest, split by category
+    for (key, title) in STATUS_CATEGORIES {
+        let codes = active_status_codes(store, key);
+        let _ = writeln!(out, "{} codes ({}):", title, codes.len());
+        for code in codes {
+            let _ = writeln!(out, "  - {}", format_status(code));
+        }
+    }
+
+    // Ingredient tree with per-ingredient status
+    let _ = writeln!(out, "Ingredients:");
+    match active {
+        Some(manifest) if has_ingredients(manifest) => {
+            write_ingredients(&mut out, store, manifest, 1);
+        }
+        _ => {
+            let _ = writeln!(out, "  (none)");
+        }
+    }
+
+    out
+}
+
+/// JSON pointer to a manifest in the `manifests` object (labels may contain '/' or '~')
+fn manifest_pointer(label: &str) -> String {
+    format!("/manifests/{}", label.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Status codes of the active manifest for one category
+///
+/// Uses `validation_results` when present. Older output only has the flat
+/// `validation_status` list, which holds failures.
+pub fn active_status_codes<'a>(store: &'a JsonValue, category: &str) -> Vec<&'a JsonValue> {
+    if let Some(results) = store.pointer("/validation_results/activeManifest") {
+        return array_items(results.get(category));
+    }
+    if category == "failure" {
+        return array_items(store.get("validation_status"));
+    }
+    Vec::new()
+}
+
+fn array_items(value: Option<&JsonValue>) -> Vec<&JsonValue> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().collect())
+        .unwrap_or_default()
+}
+
+/// Format a status entry as "code (url): explanation"
+fn format_status(status: &JsonValue) -> String {
+    let code = status
+        .get("code")
+        .and_then(|v| v.as_str())
+        .unwrap_or("unknown");
+    let mut line = code.to_string();
+    if let Some(url) = status.get("url").and_then(|v| v.as_str()) {
+        let _ = write!(line, " ({})", url);
+    }
+    if let Some(explanation) = status.get("explanation").and_then(|v| v.as_str()) {
+        let _ = write!(line, ": {}", explanation);
+    }
+    line
+}
+
+fn has_ingredients(manifest: &JsonValue) -> bool {
+    manifest
+        .get("ingredients")
+        .and_then(|v| v.as_array())
+        .is_some_and(|items| !items.is_empty())
+}
+
+/// Failure codes reported for an ingredient
+fn ingredient_failures(ingredient: &JsonValue) -> Vec<&JsonValue> {
+    if let Some(results) = ingredient.pointer("/validation_results/activeManifest") {
+        return array_items(results.get("failure"));
+    }
+    array_items(ingredient.get("validation_status"))
+        .into_iter()
+        .filter(|status| {
+            // The flat list can also contain success codes in newer output
+            !status
+                .get("code")
+                .and_then(|c| c.as_str())
+                .is_some_and(|c| c.ends_with(".validated") || c.ends_with(".match"))
+        })
+        .collect()
+}
+
+fn write_ingredients(out: &mut String, store: &JsonValue, manifest: &JsonValue, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let ingredients = array_items(manifest.get("ingredients"));
+
+    for ingredient in ingredients {
+        let title = ingredient
+            .get("title")
+            .and_then(|v| v.as_str())
+            .unwrap_or("untitled");
+        let relationship = ingredient
+            .get("relationship")
+            .and_then(|v| v.as_str())
+            .unwrap_or("unknown");
+        let ingredient_manifest = ingredient.get("active_manifest").and_then(|v| v.as_str());
+        let failures = ingredient_failures(ingredient);
+
+        let status = match (ingredient_manifest, failures.is_empty()) {
+            (None, _) => "no manifest".to_string(),
+            (Some(_), true) => "valid".to_string(),
+            (Some(_), false) => format!("{} failure(s)", failures.len()),
+        };
+        let _ = writeln!(out, "{}- {} [{}]: {}", indent, title, relationship, status);
+        if let Some(label) = ingredient_manifest {
+            let _ = writeln!(out, "{}    manifest: {}", indent, label);
+        }
+        for failure in failures {
+            let _ = writeln!(out, "{}    ✗ {}", indent, format_status(failure));
+        }
+
+        // Recurse into the ingredient's own manifest
+        if depth < MAX_INGREDIENT_DEPTH {
+            if let Some(nested) =
+                ingredient_manifest.and_then(|l| store.pointer(&manifest_pointer(l)))
+            {
+                write_ingredients(out, store, nested, depth + 1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample_store() -> JsonValue {
+        json!({
+            "active_manifest": "urn:c2pa:active",
+            "manifests": {
+                "urn:c2pa:active": {
+                    "signature_info": {
+                        "alg": "Ed25519",
+                        "issuer": "C2PA Test Intermediate Root CA",
+                        "common_name": "C2PA Signer",
+                        "cert_serial_number": "123"
+                    },
+                    "ingredients": [
+                        {
+                            "title": "parent.jpg",
+                            "relationship": "parentOf",
+                            "active_manifest": "urn:c2pa:parent",
+                            "validation_results": { "activeManifest": {
+                                "success": [],
+                                "informational": [],
+                                "failure": [{ "code": "assertion.dataHash.mismatch", "url": "self#jumbf=c2pa.hash.data" }]
+                            } }
+                        },
+                        { "title": "logo.png", "relationship": "componentOf" }
+                    ]
+                },
+                "urn:c2pa:parent": {
+                    "ingredients": [{ "title": "original.jpg", "relationship": "parentOf" }]
+                }
+            },
+            "validation_results": { "activeManifest": {
+                "success": [{ "code": "claimSignature.validated", "url": "self#jumbf=c2pa.signature", "explanation": "claim signature valid" }],
+                "informational": [],
+                "failure": [{ "code": "signingCredential.untrusted", "explanation": "signing certificate untrusted" }]
+            } },
+            "validation_state": "Valid"
+        })
+    }
+
+    #[test]
+    fn test_summary_lists_state_signer_and_codes() {
+        let summary = build_summary(&sample_store());
+
+        assert!(summary.contains("Validation state: Valid"));
+        assert!(summary.contains("Signer: C2PA Signer"));
+        assert!(summary.contains("Issuer: C2PA Test Intermediate Root CA"));
+        assert!(summary.contains("Signing time: none (no timestamp)"));
+        assert!(summary.contains("Success codes (1):"));
+        assert!(summary.contains(
+            "claimSignature.validated (self#jumbf=c2pa.signature): claim signature valid"
+        ));
+        assert!(summary.contains("Informational codes (0):"));
+        assert!(summary.contains("Failure codes (1):"));
+        assert!(summary.contains("signingCredential.untrusted: signing certificate untrusted"));
+    }
+
+    #[test]
+    fn test_summary_ingredient_tree() {
+        let summary = build_summary(&sample_store());
+
+        assert!(summary.contains("  - parent.jpg [parentOf]: 1 failure(s)"));
+        assert!(summary.contains("✗ assertion.dataHash.mismatch"));
+        assert!(summary.contains("    - original.jpg [parentOf]: no manifest"));
+        assert!(summary.contains("  - logo.png [componentOf]: no manifest"));
+    }
+
+    #[test]
+    fn test_summary_falls_back_to_validation_status() {
+        let store = json!({
+            "active_manifest": "urn:c2pa:a",
+            "manifests": { "urn:c2pa:a": {} },
+            "validation_status": [{ "code": "claimSignature.mismatch" }]
+        });
+
+        let summary = build_summary(&store);
+        assert!(summary.contains("Failure codes (1):"));
+        assert!(summary.contains("  - claimSignature.mismatch"));
+        assert!(summary.contains("Ingredients:\n  (none)"));
+    }
+}
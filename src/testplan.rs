@@ -0,0 +1,487 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Test plan driven corpus generation (`--plan`).
+//!
+//! A test plan is a table (CSV, or the first sheet of an XLSX workbook) whose header row
+//! names the columns. Each row with a manifest is signed onto its source asset, read back,
+//! and its validation state compared with the expected result. The table is written back
+//! with `Result` and `Output` columns as `<plan>_results.csv`.
+
+use anyhow::{Context, Result};
+use calamine::Reader as _;
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{KeySource, ProcessingConfig};
+
+/// Accepted header names (lowercase) for each column
+const ID_HEADERS: &[&str] = &["test id", "id", "test"];
+const ASSET_HEADERS: &[&str] = &["asset", "source asset", "input"];
+const MANIFEST_HEADERS: &[&str] = &["manifest", "manifest file"];
+const EXPECTED_HEADERS: &[&str] = &["expected", "expected result", "test type"];
+
+/// Validation outcome a row expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expected {
+    Valid,
+    Invalid,
+}
+
+impl Expected {
+    fn parse(text: &str) -> Result<Option<Self>> {
+        Ok(match text.to_lowercase().as_str() {
+            "" => None,
+            "positive" | "valid" | "pass" => Some(Self::Valid),
+            "negative" | "invalid" | "fail" => Some(Self::Invalid),
+            other => anyhow::bail!(
+                "Unknown expected result {:?} (use positive/valid or negative/invalid)",
+                other
+            ),
+        })
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Invalid => "invalid",
+        }
+    }
+}
+
+/// Column indexes of the plan
+#[derive(Debug)]
+struct Columns {
+    id: Option<usize>,
+    asset: Option<usize>,
+    manifest: usize,
+    expected: Option<usize>,
+}
+
+fn find_columns(header: &[String]) -> Result<Columns> {
+    let find = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.contains(&h.trim().to_lowercase().as_str()))
+    };
+    let manifest = find(MANIFEST_HEADERS).context(format!(
+        "The test plan has no Manifest column (columns: {}); \
+         add Test ID, Asset, Manifest and Expected Result columns",
+        header.join(", ")
+    ))?;
+    Ok(Columns {
+        id: find(ID_HEADERS),
+        asset: find(ASSET_HEADERS),
+        manifest,
+        expected: find(EXPECTED_HEADERS),
+    })
+}
+
+/// All rows of the table as trimmed strings, the header first
+fn read_table(path: &Path) -> Result<Vec<Vec<String>>> {
+    let extension = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match extension.as_str() {
+        "csv" => {
+            let mut reader = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(path)
+                .context(format!("Failed to open test plan: {:?}", path))?;
+            reader
+                .records()
+                .map(|record| {
+                    let record = record.context("Failed to read test plan row")?;
+                    Ok(record.iter().map(|cell| cell.trim().to_string()).collect())
+                })
+                .collect()
+        }
+        "xlsx" | "xlsm" | "xls" | "ods" => {
+            let mut workbook = calamine::open_workbook_auto(path)
+                .context(format!("Failed to open test plan: {:?}", path))?;
+            let range = workbook
+                .worksheet_range_at(0)
+                .context("The test plan workbook has no sheets")?
+                .context("Failed to read the first sheet of the test plan")?;
+            Ok(range
+                .rows()
+                .map(|row| {
+                    row.iter()
+                        .map(|cell| cell.to_string().trim().to_string())
+                        .collect()
+                })
+                .collect())
+        }
+        _ => anyhow::bail!("Test plans must be CSV or XLSX files, got {:?}", path),
+    }
+}
+
+/// A row to sign
+#[derive(Debug)]
+struct PlanRow {
+    /// Index into the table
+    index: usize,
+    id: String,
+    asset: PathBuf,
+    manifest: PathBuf,
+    expected: Option<Expected>,
+}
+
+/// File name stem for a test ID
+fn output_stem(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "-_.".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// The signable rows of the table; rows without a manifest are left out
+fn plan_rows(
+    table: &[Vec<String>],
+    columns: &Columns,
+    base_dir: &Path,
+    default_asset: &Path,
+) -> Result<Vec<PlanRow>> {
+    let cell = |row: &[String], column: Option<usize>| {
+        column
+            .and_then(|c| row.get(c))
+            .map(String::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut rows = Vec::new();
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    for (index, row) in table.iter().enumerate().skip(1) {
+        let manifest = cell(row, Some(columns.manifest));
+        if manifest.is_empty() {
+            continue;
+        }
+        let manifest = base_dir.join(manifest);
+        let id = match cell(row, columns.id) {
+            id if id.is_empty() => output_stem(
+                manifest
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default(),
+            ),
+            id => output_stem(&id),
+        };
+        // Row numbers as shown in a spreadsheet, the header being row 1
+        if let Some(previous) = ids.insert(id.clone(), index) {
+            anyhow::bail!(
+                "Rows {} and {} have the same test ID {:?}",
+                previous + 1,
+                index + 1,
+                id
+            );
+        }
+        let asset = match cell(row, columns.asset) {
+            asset if asset.is_empty() => default_asset.to_path_buf(),
+            asset => base_dir.join(asset),
+        };
+        let expected =
+            Expected::parse(&cell(row, columns.expected)).context(format!("Row {}", index + 1))?;
+        rows.push(PlanRow {
+            index,
+            id,
+            asset,
+            manifest,
+            expected,
+        });
+    }
+    Ok(rows)
+}
+
+/// Signing options shared by every row
+pub struct PlanOptions<'a> {
+    pub output_dir: &'a Path,
+    pub cert: &'a Path,
+    pub key: KeySource<'a>,
+    pub signing_alg: c2pa::SigningAlg,
+    pub allow_self_signed: bool,
+    pub deterministic_seed: Option<&'a str>,
+}
+
+/// Sign a row and read it back; returns the output, validation state and failure codes
+fn sign_row(row: &PlanRow, options: &PlanOptions) -> Result<(PathBuf, String, Vec<String>)> {
+    let manifest_json = fs::read_to_string(&row.manifest)
+        .context(format!("Failed to read manifest: {:?}", row.manifest))?;
+    let config = ProcessingConfig {
+        allow_self_signed: options.allow_self_signed,
+        deterministic_seed: options.deterministic_seed,
+        ..ProcessingConfig::new(
+            &manifest_json,
+            row.manifest.parent().unwrap_or(Path::new(".")),
+            options.cert,
+            options.key,
+            options.signing_alg,
+        )
+    };
+    let extension = row
+        .asset
+        .extension()
+        .and_then(|s| s.to_str())
+        .context(format!("Asset has no extension: {:?}", row.asset))?;
+    let output = options.output_dir.join(format!("{}.{}", row.id, extension));
+    crate::process_single_file(&row.asset, &output, &config)?;
+
+    let store = crate::read_manifest_store(&output, false)?;
+    let store: JsonValue =
+        serde_json::from_str(&store.json).context("Failed to parse manifest JSON")?;
+    let state = store
+        .get("validation_state")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Unknown")
+        .to_string();
+    let codes = crate::summary::active_status_codes(&store, "failure")
+        .iter()
+        .filter_map(|status| status.get("code").and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .collect();
+    Ok((output, state, codes))
+}
+
+/// Result column text for a signed row, and whether it met the expectation
+fn result_text(expected: Option<Expected>, state: &str, codes: &[String]) -> (bool, String) {
+    let observed = if state == "Invalid" {
+        Expected::Invalid
+    } else {
+        Expected::Valid
+    };
+    let detail = if codes.is_empty() {
+        state.to_string()
+    } else {
+        format!("{}: {}", state, codes.join(" "))
+    };
+    match expected {
+        None => (true, format!("SIGNED ({})", detail)),
+        Some(expected) if expected == observed => (true, format!("PASS ({})", detail)),
+        Some(expected) => (
+            false,
+            format!("MISMATCH: expected {}, got {}", expected.name(), detail),
+        ),
+    }
+}
+
+/// Sign every row of a test plan and write the results table
+pub fn run_plan(plan_path: &Path, options: &PlanOptions) -> Result<()> {
+    println!("=== Test plan: {:?} ===", plan_path);
+    let mut table = read_table(plan_path)?;
+    let header = table.first().context("The test plan is empty")?;
+    let columns = find_columns(header)?;
+    let base_dir = plan_path.parent().unwrap_or(Path::new("."));
+    let default_asset = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("testfiles")
+        .join("Dog.jpg");
+    let rows = plan_rows(&table, &columns, base_dir, &default_asset)?;
+    println!("  Rows to sign: {}", rows.len());
+    fs::create_dir_all(options.output_dir).context("Failed to create output directory")?;
+
+    // Result and Output cells per table row
+    let mut results: HashMap<usize, (String, String)> = HashMap::new();
+    let mut mismatches = Vec::new();
+    let mut errors = Vec::new();
+    for row in &rows {
+        let (result, output) = match sign_row(row, options) {
+            Ok((output, state, codes)) => {
+                let (matched, text) = result_text(row.expected, &state, &codes);
+                if !matched {
+                    mismatches.push(format!("{}: {}", row.id, text));
+                }
+                (text, output.display().to_string())
+            }
+            Err(e) => {
+                eprintln!("Error processing test {}: {:#}", row.id, e);
+                errors.push(row.id.clone());
+                (format!("ERROR: {:#}", e), String::new())
+            }
+        };
+        results.insert(row.index, (result, output));
+    }
+
+    // The plan's table with the Result and Output columns appended
+    let width = table.iter().map(Vec::len).max().unwrap_or_default();
+    let stem = plan_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("plan");
+    let results_path = options.output_dir.join(format!("{}_results.csv", stem));
+    let mut writer = csv::Writer::from_path(&results_path)
+        .context(format!("Failed to create {:?}", results_path))?;
+    let mut skipped = 0;
+    for (index, row) in table.iter_mut().enumerate() {
+        let blank = row.iter().all(String::is_empty);
+        row.resize(width, String::new());
+        let (result, output) = match (index, results.remove(&index)) {
+            (0, _) => ("Result".to_string(), "Output".to_string()),
+            (_, Some(cells)) => cells,
+            (_, None) if blank => Default::default(),
+            (_, None) => {
+                skipped += 1;
+                ("SKIPPED: no manifest".to_string(), String::new())
+            }
+        };
+        row.push(result);
+        row.push(output);
+        writer
+            .write_record(row.iter())
+            .context("Failed to write test plan results")?;
+    }
+    writer
+        .flush()
+        .context("Failed to write test plan results")?;
+
+    println!("\n=== Test Plan Summary ===");
+    println!("  Passed: {}", rows.len() - mismatches.len() - errors.len());
+    println!("  Mismatched: {}", mismatches.len());
+    println!("  Errors: {}", errors.len());
+    println!("  Skipped (no manifest): {}", skipped);
+    println!("  Results: {:?}", results_path);
+    if !mismatches.is_empty() {
+        println!("\n=== Tests Not Matching Their Expected Result ===");
+        for mismatch in &mismatches {
+            println!("  ✗ {}", mismatch);
+        }
+    }
+
+    if !errors.is_empty() {
+        anyhow::bail!(
+            "{} test(s) failed to sign: {}",
+            errors.len(),
+            errors.join(", ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(rows: &[&[&str]]) -> Vec<Vec<String>> {
+        rows.iter()
+            .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_columns_by_header_name() -> Result<()> {
+        let header = table(&[&["Notes", "Manifest File", "Test ID", "Expected Result"]]);
+        let columns = find_columns(&header[0])?;
+        assert_eq!(
+            (
+                columns.id,
+                columns.asset,
+                columns.manifest,
+                columns.expected
+            ),
+            (Some(2), None, 1, Some(3))
+        );
+
+        // The spreadsheet layout without a manifest column is rejected with a hint
+        let header = table(&[&["EXISTS", "Test Type", "Assertion", "Description"]]);
+        let error = find_columns(&header[0]).unwrap_err().to_string();
+        assert!(error.contains("Test Type") && error.contains("Manifest column"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plan_rows() -> Result<()> {
+        let plan = table(&[
+            &["Test ID", "Asset", "Manifest", "Expected"],
+            &["t 1", "", "m/p-one.json", "POSITIVE"],
+            &["", "img/Cat.png", "m/n-two.json", "negative"],
+            &["t3", "", "", "POSITIVE"],
+            &[],
+        ]);
+        let columns = find_columns(&plan[0])?;
+        let rows = plan_rows(&plan, &columns, Path::new("base"), Path::new("Dog.jpg"))?;
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].id, "t_1");
+        assert_eq!(rows[0].asset, Path::new("Dog.jpg"));
+        assert_eq!(rows[0].manifest, Path::new("base/m/p-one.json"));
+        assert_eq!(rows[0].expected, Some(Expected::Valid));
+        assert_eq!(rows[1].id, "n-two");
+        assert_eq!(rows[1].asset, Path::new("base/img/Cat.png"));
+        assert_eq!(rows[1].expected, Some(Expected::Invalid));
+
+        let duplicate = table(&[&["ID", "Manifest"], &["a", "x.json"], &["a", "y.json"]]);
+        let error = plan_rows(
+            &duplicate,
+            &find_columns(&duplicate[0])?,
+            Path::new("."),
+            Path::new("Dog.jpg"),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("Rows 2 and 3"), "{}", error);
+
+        let unknown = table(&[&["Manifest", "Expected"], &["x.json", "maybe"]]);
+        assert!(plan_rows(
+            &unknown,
+            &find_columns(&unknown[0])?,
+            Path::new("."),
+            Path::new("Dog.jpg")
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_result_text() {
+        let codes = vec!["assertion.dataHash.mismatch".to_string()];
+        assert_eq!(
+            result_text(Some(Expected::Valid), "Valid", &[]),
+            (true, "PASS (Valid)".to_string())
+        );
+        assert_eq!(
+            result_text(Some(Expected::Invalid), "Invalid", &codes),
+            (
+                true,
+                "PASS (Invalid: assertion.dataHash.mismatch)".to_string()
+            )
+        );
+        assert_eq!(
+            result_text(Some(Expected::Invalid), "Valid", &[]),
+            (false, "MISMATCH: expected invalid, got Valid".to_string())
+        );
+        assert_eq!(
+            result_text(None, "Trusted", &[]),
+            (true, "SIGNED (Trusted)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_sample_plans() -> Result<()> {
+        let xlsx = read_table(Path::new("testset/C2PA-JPT Testfiles.xlsx"))?;
+        let columns = find_columns(&xlsx[0])?;
+        assert_eq!(xlsx[0][columns.manifest], "Manifest");
+        assert_eq!(columns.expected, Some(1));
+        assert!(columns.id.is_none());
+
+        // Rows without a manifest are skipped
+        let rows = plan_rows(&xlsx, &columns, Path::new("testset"), Path::new("Dog.jpg"))?;
+        assert!(rows.len() > 30);
+        assert!(rows.iter().all(|row| row.manifest.exists()));
+        Ok(())
+    }
+}
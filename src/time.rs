@@ -0,0 +1,65 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! UTC time helpers shared by the OCSP responder stand-in, identity assertions and
+//! generated action timestamps.
+
+use anyhow::{Context, Result};
+
+/// Current time in seconds since the Unix epoch
+pub fn unix_time_now() -> Result<i64> {
+    Ok(std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .context("System clock is before 1970")?
+        .as_secs() as i64)
+}
+
+/// UTC calendar fields of a Unix time: [year, month, day, hour, minute, second]
+pub fn utc_datetime(unix_secs: i64) -> [i64; 6] {
+    let days = unix_secs.div_euclid(86_400);
+    let secs = unix_secs.rem_euclid(86_400);
+
+    // Civil date from days since 1970-01-01 (H. Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    [year, month, day, secs / 3600, secs % 3600 / 60, secs % 60]
+}
+
+/// Format a Unix time as an RFC 3339 UTC timestamp
+pub fn rfc3339(unix_secs: i64) -> String {
+    let [year, month, day, hour, minute, second] = utc_datetime(unix_secs);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day, hour, minute, second
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_datetime() {
+        assert_eq!(utc_datetime(0), [1970, 1, 1, 0, 0, 0]);
+        assert_eq!(utc_datetime(951_825_599), [2000, 2, 29, 11, 59, 59]);
+        assert_eq!(rfc3339(1_709_210_096), "2024-02-29T12:34:56Z");
+        assert_eq!(rfc3339(-1), "1969-12-31T23:59:59Z");
+    }
+}
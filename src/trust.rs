@@ -0,0 +1,144 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Trust configuration for reading manifests.
+//!
+//! c2pa-rs reads trust anchors, allowed certificates and the allowed EKU list from its
+//! global settings. Without them every signer is reported as untrusted, so files signed
+//! with a private test PKI can only ever reach the `Valid` state.
+
+use anyhow::{Context, Result};
+use c2pa::settings::Settings;
+use std::fs;
+use std::path::Path;
+
+/// Trust-related command line options
+#[derive(Default)]
+pub struct TrustOptions<'a> {
+    /// c2pa settings file (TOML), applied before the individual trust options
+    pub settings: Option<&'a Path>,
+    /// PEM bundle of trust anchor (root/intermediate CA) certificates
+    pub trust_anchors: Option<&'a Path>,
+    /// PEM bundle of end-entity certificates that are trusted directly
+    pub allowed_list: Option<&'a Path>,
+    /// Allowed extended key usage OIDs, one per line
+    pub trust_config: Option<&'a Path>,
+}
+
+impl TrustOptions<'_> {
+    /// Whether any trust option was given
+    pub fn is_configured(&self) -> bool {
+        self.settings.is_some()
+            || self.trust_anchors.is_some()
+            || self.allowed_list.is_some()
+            || self.trust_config.is_some()
+    }
+}
+
+/// Format a string as a TOML multi-line literal string
+fn toml_literal(value: &str) -> Result<String> {
+    if value.contains("'''") {
+        anyhow::bail!("Trust file content cannot contain three consecutive single quotes");
+    }
+    // A newline directly after the opening delimiter is trimmed by TOML
+    Ok(format!("'''\n{}'''", value))
+}
+
+/// Build the TOML settings that enable trust verification with the given PEM/OID contents
+fn trust_settings_toml(
+    trust_anchors: Option<&str>,
+    allowed_list: Option<&str>,
+    trust_config: Option<&str>,
+) -> Result<String> {
+    let mut toml = String::from("[verify]\nverify_trust = true\n\n[trust]\n");
+    for (key, value) in [
+        ("trust_anchors", trust_anchors),
+        ("allowed_list", allowed_list),
+        ("trust_config", trust_config),
+    ] {
+        if let Some(value) = value {
+            toml.push_str(&format!("{} = {}\n", key, toml_literal(value)?));
+        }
+    }
+    Ok(toml)
+}
+
+fn read_trust_file(path: Option<&Path>, description: &str) -> Result<Option<String>> {
+    path.map(|p| {
+        status!("  {}: {:?}", description, p);
+        fs::read_to_string(p).context(format!("Failed to read {} file: {:?}", description, p))
+    })
+    .transpose()
+}
+
+/// Load the c2pa settings file and trust options into the c2pa settings
+///
+/// Must be called before any `Reader` is created.
+pub fn apply_trust_options(options: &TrustOptions) -> Result<()> {
+    status!("Loading trust configuration...");
+
+    if let Some(settings_path) = options.settings {
+        status!("  Settings: {:?}", settings_path);
+        let settings = fs::read_to_string(settings_path)
+            .context(format!("Failed to read settings file: {:?}", settings_path))?;
+        Settings::from_toml(&settings)
+            .map_err(|e| anyhow::anyhow!("Failed to load settings file: {}", e))?;
+    }
+
+    let trust_anchors = read_trust_file(options.trust_anchors, "Trust anchors")?;
+    let allowed_list = read_trust_file(options.allowed_list, "Allowed list")?;
+    let trust_config = read_trust_file(options.trust_config, "Trust config")?;
+
+    if trust_anchors.is_some() || allowed_list.is_some() || trust_config.is_some() {
+        let toml = trust_settings_toml(
+            trust_anchors.as_deref(),
+            allowed_list.as_deref(),
+            trust_config.as_deref(),
+        )?;
+        Settings::from_toml(&toml)
+            .map_err(|e| anyhow::anyhow!("Failed to apply trust settings: {}", e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trust_settings_toml_includes_only_given_options() {
+        let toml = trust_settings_toml(Some("ANCHORS\n"), None, Some("1.3.6.1.5.5.7.3.4\n"))
+            .expect("Failed to build settings");
+
+        assert!(toml.contains("verify_trust = true"));
+        assert!(toml.contains("trust_anchors = '''\nANCHORS\n'''"));
+        assert!(toml.contains("trust_config = '''\n1.3.6.1.5.5.7.3.4\n'''"));
+        assert!(!toml.contains("allowed_list"));
+    }
+
+    #[test]
+    fn test_toml_literal_rejects_delimiter() {
+        assert!(toml_literal("bad ''' content").is_err());
+    }
+
+    #[test]
+    fn test_is_configured() {
+        assert!(!TrustOptions::default().is_configured());
+
+        let options = TrustOptions {
+            allowed_list: Some(Path::new("allowed.pem")),
+            ..Default::default()
+        };
+        assert!(options.is_configured());
+    }
+}
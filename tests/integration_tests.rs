@@ -1983,44 +1983,30 @@ fn test_multi_file_requires_directory_output() -> Result<()> {
 // Run the TestSet files
 #[test]
 fn test_testset_manifests() -> Result<()> {
+    use calamine::{DataType as _, Reader as _};
     use std::process::Command;
 
-    let manifest_names = vec![
-        "n-actions-created-gathered",
-        "n-actions-inception",
-        "n-actions-inception-multiple",
-        "n-actions-created-nodst",
-        "n-actions-opened",
-        "n-actions-placed",
-        "n-actions-placed-parent",
-        "n-actions-removed",
-        "n-actions-removed-same-manifest",
-        "n-actions-translated",
-        "n-actions-redacted",
-        "n-actions-redacted-bad-uri",
-        "n-actions-redacted-bad-reason",
-        "n-actions-redacted-no-reason",
-        "n-actions-watermarked-bound",
-        "n-actions-softwareAgent-missing",
-        "n-actions-softwareAgent-and-index",
-        "p-actions-created",
-        "p-actions-opened-manifest",
-        "p-actions-opened-no-manifest",
-        "p-actions-opened-no-manifest-metadata",
-        "p-actions-placed",
-        "p-actions-placed-manifest",
-        "p-actions-placed-manifest-metadata",
-        "p-actions-translated",
-        // "p-actions-redacted",
-        "p-actions-softwareAgents",
-        "p-actions-template",
-        "p-actions-template-all",
-        "p-actions-related",
-        "p-actions-changes-spatial",
-        "p-actions-watermarked-unbound",
-        "p-actions-watermarked-bound",
-        "p-soft-binding",
-    ];
+    // The manifests come from the test plan; rows without a manifest are disabled
+    let mut plan = calamine::open_workbook_auto(testset_dir().join("C2PA-JPT Testfiles.xlsx"))?;
+    let sheet = plan
+        .worksheet_range_at(0)
+        .expect("the test plan has a sheet")?;
+    let mut rows = sheet.rows();
+    let manifest_column = rows
+        .next()
+        .and_then(|header| {
+            header
+                .iter()
+                .position(|h| h.get_string() == Some("Manifest"))
+        })
+        .expect("the test plan has a Manifest column");
+    let mut manifest_names = Vec::new();
+    for row in rows {
+        let manifest = row.get(manifest_column).map(|cell| cell.to_string());
+        if let Some(manifest) = manifest.as_deref().and_then(|m| m.strip_suffix(".json")) {
+            manifest_names.push(manifest.to_string());
+        }
+    }
 
     let mut success_count = 0;
     let mut total_count = 0;
@@ -0,0 +1,120 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Assertions loaded from CBOR, JSON and binary files (`assertions_from_files`), and
+//! hashed URI references between them.
+
+use anyhow::Result;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+#[test]
+fn test_assertions_from_files() -> Result<()> {
+    let dir = output_dir().join("assertion_files_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("Dog_assertion_files.jpg");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("assertion_files_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(&output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let reader = verify_signed_file(&output)?;
+    let manifest = reader.active_manifest().expect("active manifest");
+    let labels: Vec<&str> = manifest.assertions().iter().map(|a| a.label()).collect();
+    for label in [
+        "c2pa.depthmap.gdepth",
+        "com.example.depthmap.image",
+        "com.example.camera-settings",
+    ] {
+        assert!(
+            labels.contains(&label),
+            "{} missing from {:?}",
+            label,
+            labels
+        );
+    }
+    let json = reader.json();
+    assert!(json.contains("RangeInverse") && json.contains("ProCam 3000"));
+    assert!(!json.contains("assertion.hashedURI.mismatch"), "{}", json);
+
+    // The reference carries the hash the claim lists for the depth map assertion
+    let settings = manifest
+        .assertions()
+        .iter()
+        .find(|a| a.label() == "com.example.camera-settings")
+        .expect("camera settings assertion")
+        .value()?;
+    let reference = &settings["references"][0];
+    assert_eq!(
+        reference["url"],
+        "self#jumbf=c2pa.assertions/c2pa.depthmap.gdepth"
+    );
+    let hash = STANDARD.decode(reference["hash"].as_str().expect("base64 hash"))?;
+    let hex: String = hash.iter().map(|b| format!("{:02x}", b)).collect();
+    let dump = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--inspect")
+        .arg(&output)
+        .output()?;
+    let dump = String::from_utf8_lossy(&dump.stdout);
+    assert!(dump.contains(&format!("h'{}'", hex)), "{}", dump);
+    println!("✓ CBOR, JSON and binary assertions loaded from files");
+    Ok(())
+}
+
+#[test]
+fn test_assertion_file_must_exist() -> Result<()> {
+    let dir = output_dir().join("assertion_files_tests");
+    fs::create_dir_all(&dir)?;
+    let manifest = dir.join("missing_file_manifest.json");
+    fs::write(
+        &manifest,
+        r#"{
+            "title": "Missing assertion file",
+            "assertions_from_files": [{ "label": "com.example.missing", "file_path": "nope.cbor" }]
+        }"#,
+    )?;
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(&manifest)
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(dir.join("missing.jpg"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Failed to read assertion file"));
+    Ok(())
+}
@@ -0,0 +1,111 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Hard binding selection: data and box hashes written by the tool, and deliberately bad
+//! exclusions and box lists that validate with hash errors.
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, output_dir, testfiles_dir, testset_dir, verify_signed_file};
+
+/// Sign `input` with a testset manifest and return the validation report JSON
+fn sign_and_read(manifest: &str, input: &str) -> Result<String> {
+    let dir = output_dir().join("binding_tests");
+    fs::create_dir_all(&dir)?;
+    let extension = Path::new(input).extension().unwrap().to_str().unwrap();
+    let output: PathBuf = dir.join(format!("{}.{}", manifest, extension));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(testset_dir().join(format!("{}.json", manifest)))
+        .arg(testfiles_dir().join(input))
+        .arg("--output")
+        .arg(&output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing {} failed: {}",
+        manifest,
+        String::from_utf8_lossy(&result.stderr)
+    );
+    Ok(verify_signed_file(&output)?.json())
+}
+
+#[test]
+fn test_data_and_box_hash_bindings_validate() -> Result<()> {
+    for input in ["Dog.jpg", "Dog.png"] {
+        for manifest in ["p-binding-data-hash", "p-binding-box-hash"] {
+            let report = sign_and_read(manifest, input)?;
+            assert!(
+                !report.contains("dataHash.mismatch") && !report.contains("boxesHash"),
+                "{} on {} should validate: {}",
+                manifest,
+                input,
+                report
+            );
+        }
+    }
+    println!("✓ Data and box hash bindings validate on JPEG and PNG");
+    Ok(())
+}
+
+#[test]
+fn test_bad_exclusions_and_box_lists_fail_validation() -> Result<()> {
+    for (manifest, code) in [
+        ("n-binding-data-hash-overlap", "assertion.dataHash"),
+        ("n-binding-data-hash-out-of-range", "assertion.dataHash"),
+        ("n-binding-box-hash-missing-box", "assertion.boxesHash"),
+        ("n-binding-box-hash-unknown-box", "assertion.boxesHash"),
+    ] {
+        let report = sign_and_read(manifest, "Dog.jpg")?;
+        assert!(
+            report.contains(code),
+            "{} should report {}: {}",
+            manifest,
+            code,
+            report
+        );
+    }
+    println!("✓ Bad exclusions and box lists are signed and fail validation");
+    Ok(())
+}
+
+#[test]
+fn test_bmff_hash_needs_bmff_asset() -> Result<()> {
+    let dir = output_dir().join("binding_tests");
+    fs::create_dir_all(&dir)?;
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(common::manifests_dir().join("bmff_hash_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(dir.join("bmff_on_jpeg.jpg"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("needs a BMFF asset"));
+    Ok(())
+}
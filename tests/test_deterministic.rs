@@ -0,0 +1,131 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+/// Get the binary path for the CLI tool
+fn get_binary_path() -> String {
+    env!("CARGO_BIN_EXE_c2pa-testfile-maker").to_string()
+}
+
+/// Sign an input with the CLI in deterministic mode
+fn sign_deterministic(
+    input: &Path,
+    manifest_name: &str,
+    seed: &str,
+    output_name: &str,
+) -> Result<PathBuf> {
+    let dir = output_dir().join("deterministic_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join(output_name);
+
+    let result = Command::new(get_binary_path())
+        .arg("--manifest")
+        .arg(manifests_dir().join(manifest_name))
+        .arg(input)
+        .arg("--output")
+        .arg(&output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--deterministic")
+        .arg(seed)
+        .output()?;
+
+    assert!(
+        result.status.success(),
+        "Deterministic signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    Ok(output)
+}
+
+#[test]
+fn test_deterministic_outputs_are_byte_identical() -> Result<()> {
+    for input in common::get_test_images() {
+        let ext = input.extension().unwrap().to_str().unwrap();
+        let first = sign_deterministic(
+            &input,
+            "full_manifest.json",
+            "golden",
+            &format!("first.{}", ext),
+        )?;
+        let second = sign_deterministic(
+            &input,
+            "full_manifest.json",
+            "golden",
+            &format!("second.{}", ext),
+        )?;
+
+        assert_eq!(
+            fs::read(&first)?,
+            fs::read(&second)?,
+            "Deterministic runs should produce identical {} files",
+            ext
+        );
+        verify_signed_file(&first)?;
+
+        println!("✓ Deterministic {} output is reproducible", ext);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_deterministic_with_ingredients_from_files() -> Result<()> {
+    let input = testfiles_dir().join("Dog.jpg");
+    let first = sign_deterministic(
+        &input,
+        "with_ingredients_from_files.json",
+        "golden",
+        "ingredients_first.jpg",
+    )?;
+    let second = sign_deterministic(
+        &input,
+        "with_ingredients_from_files.json",
+        "golden",
+        "ingredients_second.jpg",
+    )?;
+
+    assert_eq!(fs::read(&first)?, fs::read(&second)?);
+
+    println!("✓ Deterministic output with file ingredients is reproducible");
+    Ok(())
+}
+
+#[test]
+fn test_deterministic_seed_changes_output() -> Result<()> {
+    let input = testfiles_dir().join("Dog.jpg");
+    let first = sign_deterministic(&input, "simple_manifest.json", "seed-a", "seed_a.jpg")?;
+    let second = sign_deterministic(&input, "simple_manifest.json", "seed-b", "seed_b.jpg")?;
+
+    assert_ne!(fs::read(&first)?, fs::read(&second)?);
+
+    // The manifest label is derived from the seed
+    let first_label = verify_signed_file(&first)?.active_label().map(String::from);
+    let second_label = verify_signed_file(&second)?
+        .active_label()
+        .map(String::from);
+    assert_ne!(first_label, second_label);
+
+    println!("✓ Different seeds produce different outputs");
+    Ok(())
+}
@@ -0,0 +1,139 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+mod common;
+
+use common::{
+    extract_manifest_to_file, manifests_dir, output_dir, sign_file_with_manifest, testfiles_dir,
+};
+
+/// Get the binary path for the CLI tool
+fn get_binary_path() -> String {
+    env!("CARGO_BIN_EXE_c2pa-testfile-maker").to_string()
+}
+
+/// Sign Dog.jpg with the given example manifest into the diff test directory
+fn sign_for_diff(manifest_name: &str, output_name: &str) -> Result<PathBuf> {
+    let dir = output_dir().join("diff_tests");
+    fs::create_dir_all(&dir)?;
+
+    let input = testfiles_dir().join("Dog.jpg");
+    let output = dir.join(output_name);
+    sign_file_with_manifest(&input, &output, &manifests_dir().join(manifest_name))?;
+
+    Ok(output)
+}
+
+#[test]
+fn test_diff_same_manifest_signed_twice() -> Result<()> {
+    let first = sign_for_diff("simple_manifest.json", "same_first.jpg")?;
+    let second = sign_for_diff("simple_manifest.json", "same_second.jpg")?;
+
+    let result = Command::new(get_binary_path())
+        .arg("--diff")
+        .arg(&first)
+        .arg(&second)
+        .output()?;
+
+    assert!(
+        result.status.success(),
+        "Signing the same manifest twice should not produce differences: {}",
+        String::from_utf8_lossy(&result.stdout)
+    );
+
+    println!("✓ Same manifest signed twice has no differences");
+    Ok(())
+}
+
+#[test]
+fn test_diff_different_manifests() -> Result<()> {
+    let simple = sign_for_diff("simple_manifest.json", "different_simple.jpg")?;
+    let full = sign_for_diff("full_manifest.json", "different_full.jpg")?;
+    let report = output_dir().join("diff_tests/different_report.json");
+
+    let result = Command::new(get_binary_path())
+        .arg("--diff")
+        .arg(&simple)
+        .arg(&full)
+        .arg("--output")
+        .arg(&report)
+        .output()?;
+
+    assert!(
+        !result.status.success(),
+        "Different manifests should be reported as differing"
+    );
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(stdout.contains("Assertion differences"));
+
+    // The report lists every difference with its section
+    let report_json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&report)?)?;
+    let differences = report_json["differences"]
+        .as_array()
+        .expect("Report should contain a differences array");
+    assert!(!differences.is_empty());
+    assert!(differences.iter().all(|d| d.get("section").is_some()));
+
+    println!("✓ Different manifests are reported");
+    Ok(())
+}
+
+#[test]
+fn test_diff_asset_against_extracted_json() -> Result<()> {
+    let signed = sign_for_diff("full_manifest.json", "asset_vs_json.jpg")?;
+    let extracted = output_dir().join("diff_tests/asset_vs_json_manifest.json");
+    extract_manifest_to_file(&signed, &extracted)?;
+
+    let result = Command::new(get_binary_path())
+        .arg("--diff")
+        .arg(&signed)
+        .arg(&extracted)
+        .output()?;
+
+    assert!(
+        result.status.success(),
+        "An asset and its extracted JSON should be equivalent: {}",
+        String::from_utf8_lossy(&result.stdout)
+    );
+
+    println!("✓ Asset and extracted JSON compare as equivalent");
+    Ok(())
+}
+
+#[test]
+fn test_diff_requires_two_inputs() -> Result<()> {
+    let input = testfiles_dir().join("Dog.jpg");
+
+    let result = Command::new(get_binary_path())
+        .arg("--diff")
+        .arg(&input)
+        .output()?;
+
+    assert!(
+        !result.status.success(),
+        "--diff with one input should fail"
+    );
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("exactly two inputs"),
+        "Error should explain the input requirement. Got: {}",
+        stderr
+    );
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use c2pa::Relationship;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+/// Sign Dog.jpg with the tool (edits are applied by the binary, not the c2pa library)
+fn sign(manifest: &Path, output: &Path) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifest)
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?)
+}
+
+#[test]
+fn test_edits_are_applied_and_recorded() -> Result<()> {
+    let dir = output_dir().join("edits_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("edited.jpg");
+
+    let result = sign(&manifests_dir().join("edits_manifest.json"), &output)?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // Dog.jpg is 710x430: cropped to 600x400, then resized to a width of 480
+    assert_eq!(image::image_dimensions(&output)?, (480, 320));
+
+    let reader = verify_signed_file(&output)?;
+    let manifest = reader.active_manifest().expect("active manifest");
+    assert_eq!(manifest.ingredients().len(), 1);
+    assert_eq!(
+        manifest.ingredients()[0].relationship(),
+        &Relationship::ParentOf
+    );
+
+    let actions = manifest
+        .assertions()
+        .iter()
+        .find(|assertion| assertion.label().starts_with("c2pa.actions"))
+        .expect("actions assertion")
+        .value()?;
+    let names: Vec<&str> = actions["actions"]
+        .as_array()
+        .expect("actions array")
+        .iter()
+        .filter_map(|action| action["action"].as_str())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "c2pa.opened",
+            "c2pa.cropped",
+            "c2pa.resized",
+            "c2pa.color_adjustments",
+            "c2pa.filtered",
+            "c2pa.deleted"
+        ]
+    );
+    assert_eq!(
+        actions["actions"][1]["changes"][0]["region"][0]["shape"]["width"],
+        600
+    );
+
+    println!("✓ Edits applied to the pixels and recorded as actions");
+    Ok(())
+}
+
+#[test]
+fn test_composite_places_components() -> Result<()> {
+    let dir = output_dir().join("edits_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("composite.jpg");
+
+    let result = sign(&manifests_dir().join("composite_manifest.json"), &output)?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(image::image_dimensions(&output)?, (710, 430));
+
+    let reader = verify_signed_file(&output)?;
+    let manifest = reader.active_manifest().expect("active manifest");
+    let relationships: Vec<&Relationship> = manifest
+        .ingredients()
+        .iter()
+        .map(|ingredient| ingredient.relationship())
+        .collect();
+    assert_eq!(
+        relationships,
+        [
+            &Relationship::ParentOf,
+            &Relationship::ComponentOf,
+            &Relationship::ComponentOf
+        ]
+    );
+
+    let actions = manifest
+        .assertions()
+        .iter()
+        .find(|assertion| assertion.label().starts_with("c2pa.actions"))
+        .expect("actions assertion")
+        .value()?;
+    let placed: Vec<&serde_json::Value> = actions["actions"]
+        .as_array()
+        .expect("actions array")
+        .iter()
+        .filter(|action| action["action"] == "c2pa.placed")
+        .collect();
+    assert_eq!(placed.len(), 2);
+
+    // Dog.webp is 2560x1708, placed at 8% scale
+    let shape = &placed[1]["changes"][0]["region"][0]["shape"];
+    assert_eq!(shape["origin"]["x"], 480);
+    assert_eq!(shape["origin"]["y"], 270);
+    assert_eq!(shape["width"], 205);
+    assert_eq!(shape["height"], 137);
+
+    println!("✓ Components composited and recorded as c2pa.placed actions");
+    Ok(())
+}
+
+#[test]
+fn test_edit_outside_image_fails() -> Result<()> {
+    let dir = output_dir().join("edits_tests");
+    fs::create_dir_all(&dir)?;
+    let manifest = dir.join("bad_crop_manifest.json");
+    fs::write(
+        &manifest,
+        r#"{
+            "title": "Bad crop",
+            "edits": [{ "type": "crop", "region": { "x": 700, "y": 0, "width": 100, "height": 100 } }]
+        }"#,
+    )?;
+
+    let result = sign(&manifest, &dir.join("unused.jpg"))?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("outside the 710x430 image"));
+    Ok(())
+}
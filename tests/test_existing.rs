@@ -0,0 +1,134 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Re-signing assets that already have a manifest store with each `--existing` policy.
+
+use anyhow::Result;
+use c2pa::Relationship;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{
+    certs_dir, manifests_dir, output_dir, sign_file_with_manifest, testfiles_dir,
+    verify_signed_file,
+};
+
+/// Dog.jpg signed once with the simple manifest
+fn signed_input(dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let input = dir.join("already_signed.jpg");
+    if !input.exists() {
+        sign_file_with_manifest(
+            &testfiles_dir().join("Dog.jpg"),
+            &input,
+            &manifests_dir().join("simple_manifest.json"),
+        )?;
+    }
+    Ok(input)
+}
+
+fn resign(input: &Path, output: &Path, policy: &str) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("resign_manifest.json"))
+        .arg(input)
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .arg(format!("--existing={}", policy))
+        .output()?)
+}
+
+fn assert_success(result: &Output) {
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+}
+
+#[test]
+fn test_existing_strip_and_replace_drop_previous_provenance() -> Result<()> {
+    let dir = output_dir().join("existing_tests");
+    let input = signed_input(&dir)?;
+
+    for policy in ["strip", "replace"] {
+        let output = dir.join(format!("{}.jpg", policy));
+        let result = resign(&input, &output, policy)?;
+        assert_success(&result);
+        let stdout = String::from_utf8_lossy(&result.stdout);
+        assert!(stdout.contains("Existing manifest store:"), "{}", stdout);
+
+        let reader = verify_signed_file(&output)?;
+        assert_eq!(
+            reader.manifests().len(),
+            1,
+            "{}: only the new manifest",
+            policy
+        );
+    }
+    println!("✓ strip and replace keep only the new manifest");
+    Ok(())
+}
+
+#[test]
+fn test_existing_parent_keeps_previous_manifest() -> Result<()> {
+    let dir = output_dir().join("existing_tests");
+    let input = signed_input(&dir)?;
+    let output = dir.join("parent.jpg");
+
+    let result = resign(&input, &output, "parent")?;
+    assert_success(&result);
+
+    let reader = verify_signed_file(&output)?;
+    assert_eq!(reader.manifests().len(), 2);
+    let manifest = reader.active_manifest().expect("active manifest");
+    let parent = manifest
+        .ingredients()
+        .iter()
+        .find(|ingredient| *ingredient.relationship() == Relationship::ParentOf)
+        .expect("parentOf ingredient");
+    assert!(
+        parent.active_manifest().is_some(),
+        "parent keeps its manifest"
+    );
+    assert!(reader.json().contains("c2pa.opened"));
+
+    println!("✓ parent keeps the previous manifest in a parentOf ingredient");
+    Ok(())
+}
+
+#[test]
+fn test_existing_error_rejects_signed_input() -> Result<()> {
+    let dir = output_dir().join("existing_tests");
+    let input = signed_input(&dir)?;
+
+    let result = resign(&input, &dir.join("error.jpg"), "error")?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("already has a C2PA manifest"));
+
+    // Unsigned inputs are signed under any policy
+    let result = resign(
+        &testfiles_dir().join("Dog.jpg"),
+        &dir.join("unsigned.jpg"),
+        "error",
+    )?;
+    assert_success(&result);
+    Ok(())
+}
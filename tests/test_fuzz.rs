@@ -0,0 +1,98 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Fuzz mode: seeded random manifests signed into a reproducible corpus.
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, output_dir, testfiles_dir};
+
+fn run_fuzz(dir: &Path, seed: &str, assets: &[PathBuf]) -> Result<JsonValue> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--fuzz")
+        .arg("12")
+        .arg("--deterministic")
+        .arg(seed)
+        .args(assets)
+        .arg("--output")
+        .arg(dir)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Fuzz failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    Ok(serde_json::from_str(&fs::read_to_string(
+        dir.join("fuzz_report.json"),
+    )?)?)
+}
+
+#[test]
+fn test_fuzz_corpus_is_reproducible() -> Result<()> {
+    let first_dir = output_dir().join("fuzz_tests_first");
+    let second_dir = output_dir().join("fuzz_tests_second");
+    let first = run_fuzz(&first_dir, "fuzz-seed", &[])?;
+    let second = run_fuzz(&second_dir, "fuzz-seed", &[])?;
+    assert_eq!(first, second);
+
+    let results = first.as_array().expect("report is a list");
+    assert_eq!(results.len(), 12);
+    for result in results {
+        assert_eq!(result["signed"], true, "{:#}", result);
+        for key in ["manifest", "output"] {
+            let name = result[key].as_str().unwrap();
+            assert_eq!(
+                fs::read(first_dir.join(name))?,
+                fs::read(second_dir.join(name))?,
+                "{} differs between runs",
+                name
+            );
+        }
+    }
+
+    let other = run_fuzz(&output_dir().join("fuzz_tests_other"), "other-seed", &[])?;
+    assert_ne!(first, other);
+    println!("✓ The same seed gives a byte-identical fuzz corpus");
+    Ok(())
+}
+
+#[test]
+fn test_fuzz_onto_provided_assets() -> Result<()> {
+    let dir = output_dir().join("fuzz_tests_assets");
+    let report = run_fuzz(
+        &dir,
+        "assets",
+        &[
+            testfiles_dir().join("Dog.jpg"),
+            testfiles_dir().join("Dog.png"),
+        ],
+    )?;
+    for result in report.as_array().unwrap() {
+        let asset = result["asset"].as_str().unwrap();
+        assert!(asset.ends_with("Dog.jpg") || asset.ends_with("Dog.png"));
+    }
+    Ok(())
+}
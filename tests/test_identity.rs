@@ -0,0 +1,117 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! CAWG identity assertions. The identity certificate is generated with OpenSSL so it
+//! differs from the claim signer; tests that need it are skipped without OpenSSL.
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+/// Create a self-signed Ed25519 identity certificate and key in `dir`, or None if OpenSSL
+/// is not available
+fn create_identity(dir: &Path) -> Result<Option<(PathBuf, PathBuf)>> {
+    fs::create_dir_all(dir)?;
+    let created = [
+        "genpkey -algorithm ed25519 -out identity.key",
+        "req -x509 -new -key identity.key -subj /CN=Identity-Test -days 30 -out identity.pem",
+    ]
+    .iter()
+    .all(|args| {
+        Command::new("openssl")
+            .args(args.split_whitespace())
+            .current_dir(dir)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    });
+    Ok(created.then(|| (dir.join("identity.pem"), dir.join("identity.key"))))
+}
+
+fn sign(output: &Path, extra_args: &[&std::ffi::OsStr]) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("identity_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .args(extra_args)
+        .output()?)
+}
+
+#[test]
+fn test_sign_with_identity_assertions() -> Result<()> {
+    let dir = output_dir().join("identity_tests");
+    let Some((identity_cert, identity_key)) = create_identity(&dir.join("identity"))? else {
+        println!("Skipping: openssl with Ed25519 support not available");
+        return Ok(());
+    };
+
+    let output = dir.join("identity_signed.jpg");
+    let result = sign(
+        &output,
+        &[
+            "--identity-cert".as_ref(),
+            identity_cert.as_os_str(),
+            "--identity-key".as_ref(),
+            identity_key.as_os_str(),
+        ],
+    )?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let reader = verify_signed_file(&output)?;
+    let manifest = reader.active_manifest().expect("active manifest");
+    let identities = manifest
+        .assertions()
+        .iter()
+        .filter(|assertion| assertion.label().starts_with("cawg.identity"))
+        .count();
+    assert_eq!(identities, 2, "Both identity assertions should be embedded");
+
+    println!("✓ Signed with X.509 and identity claims aggregation identities");
+    Ok(())
+}
+
+#[test]
+fn test_identity_assertions_need_identity_key() -> Result<()> {
+    let dir = output_dir().join("identity_tests");
+    fs::create_dir_all(&dir)?;
+
+    let result = sign(&dir.join("unused.jpg"), &[])?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--identity-cert"));
+
+    // The certificate and key are only accepted together
+    let result = sign(
+        &dir.join("unused.jpg"),
+        &[
+            "--identity-cert".as_ref(),
+            certs_dir().join("ed25519.pub").as_os_str(),
+        ],
+    )?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--identity-key"));
+    Ok(())
+}
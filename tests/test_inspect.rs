@@ -0,0 +1,94 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{manifests_dir, output_dir, sign_file_with_manifest, testfiles_dir};
+
+/// Get the binary path for the CLI tool
+fn get_binary_path() -> String {
+    env!("CARGO_BIN_EXE_c2pa-testfile-maker").to_string()
+}
+
+#[test]
+fn test_inspect_signed_jpeg() -> Result<()> {
+    let dir = output_dir().join("inspect_tests");
+    fs::create_dir_all(&dir)?;
+    let signed = dir.join("signed.jpg");
+    sign_file_with_manifest(
+        &testfiles_dir().join("Dog.jpg"),
+        &signed,
+        &manifests_dir().join("simple_manifest.json"),
+    )?;
+
+    let result = Command::new(get_binary_path())
+        .arg("--inspect")
+        .arg(&signed)
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Inspect failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(stdout.contains("Container: JPEG"));
+    assert!(stdout.contains("APP11 segment"));
+    assert!(stdout.contains("[c2pa.assertions] type c2as"));
+    assert!(stdout.contains("[c2pa.signature] type c2cs"));
+    assert!(stdout.contains("COSE_Sign1 (tag 18)"));
+    assert!(stdout.contains("x5chain"));
+    assert!(stdout.contains("C2PA Signer"));
+
+    println!("✓ Inspect dump shows the JUMBF tree and COSE signature");
+    Ok(())
+}
+
+#[test]
+fn test_inspect_writes_dump_to_directory() -> Result<()> {
+    let dir = output_dir().join("inspect_tests/dumps");
+    fs::create_dir_all(&dir)?;
+    let signed = output_dir().join("inspect_tests/dumped.jpg");
+    sign_file_with_manifest(
+        &testfiles_dir().join("Dog.jpg"),
+        &signed,
+        &manifests_dir().join("simple_manifest.json"),
+    )?;
+
+    let result = Command::new(get_binary_path())
+        .arg("--inspect")
+        .arg(&signed)
+        .arg("--output")
+        .arg(&dir)
+        .output()?;
+    assert!(result.status.success());
+
+    let dump = fs::read_to_string(dir.join("dumped_inspect.txt"))?;
+    assert!(dump.contains("JUMBF boxes:"));
+    Ok(())
+}
+
+#[test]
+fn test_inspect_unsigned_file_fails() -> Result<()> {
+    let result = Command::new(get_binary_path())
+        .arg("--inspect")
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .output()?;
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("No JUMBF manifest store"));
+    Ok(())
+}
@@ -0,0 +1,98 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Test matrix mode: assets x manifests x algorithms x formats from a config file.
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{manifests_dir, output_dir, verify_signed_file};
+
+#[test]
+fn test_matrix_example_config() -> Result<()> {
+    let dir = output_dir().join("matrix_tests");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--matrix")
+        .arg(manifests_dir().join("matrix.json"))
+        .arg("--output")
+        .arg(&dir)
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Matrix failed: {}\n{}",
+        String::from_utf8_lossy(&result.stdout),
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // Dog.jpg, Dog.png and Dog.webp x 2 manifests x 1 algorithm x 2 formats
+    let report: JsonValue =
+        serde_json::from_str(&fs::read_to_string(dir.join("matrix_report.json"))?)?;
+    let results = report.as_array().expect("report is a list");
+    assert_eq!(results.len(), 12);
+    assert!(results.iter().all(|r| r["signed"] == true), "{:#}", report);
+
+    for name in [
+        "Dog-simple_manifest-ed25519.jpg",
+        "Dog-full_manifest-ed25519.png",
+    ] {
+        verify_signed_file(&dir.join(name))?;
+    }
+    // Each asset is also converted to the other format
+    assert!(dir.join("Dog-simple_manifest-ed25519.png").exists());
+
+    println!("✓ Matrix signed all 12 combinations");
+    Ok(())
+}
+
+#[test]
+fn test_matrix_rejects_ambiguous_names() -> Result<()> {
+    let dir = output_dir().join("matrix_tests_ambiguous");
+    fs::create_dir_all(&dir)?;
+    let config = dir.join("matrix.json");
+    let examples = manifests_dir();
+    fs::write(
+        &config,
+        serde_json::json!({
+            "assets": [examples.join("../testfiles/Dog.jpg")],
+            "manifests": [
+                examples.join("simple_manifest.json"),
+                examples.join("full_manifest.json")
+            ],
+            "name": "{stem}.{ext}"
+        })
+        .to_string(),
+    )?;
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--matrix")
+        .arg(&config)
+        .arg("--output")
+        .arg(dir.join("out"))
+        .arg("--cert")
+        .arg(common::certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(common::certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("Dog.jpg"));
+    Ok(())
+}
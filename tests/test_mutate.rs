@@ -0,0 +1,83 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Negative variant mode: n-* manifests generated from p-* manifests, then signed.
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, output_dir, testset_dir};
+
+#[test]
+fn test_mutate_testset_manifests() -> Result<()> {
+    let dir = output_dir().join("mutate_tests");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--mutate")
+        .arg(testset_dir().join("p-actions-redacted.json"))
+        .arg(testset_dir().join("p-actions-placed-manifest.json"))
+        .arg("--output")
+        .arg(&dir)
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Mutation failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let variant: JsonValue = serde_json::from_str(&fs::read_to_string(
+        dir.join("n-actions-redacted-bad-reason.json"),
+    )?)?;
+    assert_eq!(variant["title"], "n-actions-redacted-bad-reason");
+    assert_eq!(
+        variant["assertions"][0]["data"]["actions"][1]["reason"],
+        "c2pa.not-a-reason"
+    );
+    // Ingredient files still resolve from the output directory
+    let placed: JsonValue = serde_json::from_str(&fs::read_to_string(
+        dir.join("n-actions-placed-manifest-missing-ingredient.json"),
+    )?)?;
+    let ingredient = placed["ingredients_from_files"][0]["file_path"]
+        .as_str()
+        .unwrap();
+    assert!(std::path::Path::new(ingredient).is_file(), "{}", ingredient);
+
+    // The recorded test plan signs every variant
+    let plan = fs::read_to_string(dir.join("variants.csv"))?;
+    assert!(plan.starts_with("Test ID,Manifest,Expected Result,Source,Rule,Notes"));
+    assert!(plan.contains("c2pa.redacted action without its reason"));
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--plan")
+        .arg(dir.join("variants.csv"))
+        .arg("--output")
+        .arg(dir.join("signed"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing the variants failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    println!("✓ Negative variants generated and signed");
+    Ok(())
+}
@@ -0,0 +1,182 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! OCSP stapling with the local responder stand-in. The test CA is generated with
+//! OpenSSL (the fixture chain has no CA key); tests are skipped without it.
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir};
+
+/// Throwaway CA and Ed25519 signer issued by it
+struct TestPki {
+    chain: PathBuf,
+    signer_key: PathBuf,
+    ca_cert: PathBuf,
+    ca_key: PathBuf,
+}
+
+/// Run an OpenSSL command (arguments separated by whitespace) in `dir`
+fn openssl(dir: &Path, args: &str) -> bool {
+    Command::new("openssl")
+        .args(args.split_whitespace())
+        .current_dir(dir)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Create the test CA and signer in `dir`, or None if OpenSSL is not available
+fn create_pki(dir: &Path) -> Result<Option<TestPki>> {
+    fs::create_dir_all(dir)?;
+    fs::write(
+        dir.join("signer_ext.cnf"),
+        "basicConstraints=critical,CA:FALSE\n\
+         keyUsage=critical,digitalSignature,nonRepudiation\n\
+         extendedKeyUsage=critical,emailProtection\n",
+    )?;
+
+    let created = [
+        "genpkey -algorithm ed25519 -out ca.key",
+        "req -x509 -new -key ca.key -subj /CN=OCSP-Test-CA -days 30 -out ca.pem",
+        "genpkey -algorithm ed25519 -out signer.key",
+        "req -new -key signer.key -subj /CN=OCSP-Test-Signer -out signer.csr",
+        "x509 -req -in signer.csr -CA ca.pem -CAkey ca.key -CAcreateserial -days 30 \
+         -extfile signer_ext.cnf -out signer.pem",
+    ]
+    .iter()
+    .all(|args| openssl(dir, args));
+    if !created {
+        return Ok(None);
+    }
+
+    let chain = dir.join("chain.pem");
+    fs::write(
+        &chain,
+        [
+            fs::read(dir.join("signer.pem"))?,
+            fs::read(dir.join("ca.pem"))?,
+        ]
+        .concat(),
+    )?;
+    Ok(Some(TestPki {
+        chain,
+        signer_key: dir.join("signer.key"),
+        ca_cert: dir.join("ca.pem"),
+        ca_key: dir.join("ca.key"),
+    }))
+}
+
+fn sign(cert: &Path, key: &Path, output: &Path, extra_args: &[&std::ffi::OsStr]) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(cert)
+        .arg("--key")
+        .arg(key)
+        .arg("--allow-self-signed")
+        .args(extra_args)
+        .output()?)
+}
+
+#[test]
+fn test_local_responder_staples_ocsp_response() -> Result<()> {
+    let dir = output_dir().join("ocsp_tests");
+    let Some(pki) = create_pki(&dir.join("pki"))? else {
+        println!("Skipping: openssl with Ed25519 support not available");
+        return Ok(());
+    };
+
+    for status in ["good", "revoked"] {
+        let output = dir.join(format!("ocsp_{}.jpg", status));
+        let result = sign(
+            &pki.chain,
+            &pki.signer_key,
+            &output,
+            &[
+                "--ocsp-status".as_ref(),
+                status.as_ref(),
+                "--ocsp-responder-cert".as_ref(),
+                pki.ca_cert.as_os_str(),
+                "--ocsp-responder-key".as_ref(),
+                pki.ca_key.as_os_str(),
+            ],
+        )?;
+        assert!(
+            result.status.success(),
+            "Signing with OCSP status {} failed: {}",
+            status,
+            String::from_utf8_lossy(&result.stderr)
+        );
+
+        // The response is stored in the rVals header of the COSE signature
+        let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+            .arg("--inspect")
+            .arg(&output)
+            .output()?;
+        assert!(result.status.success());
+        let dump = String::from_utf8_lossy(&result.stdout);
+        assert!(
+            dump.contains("rVals") && dump.contains("ocspVals"),
+            "Signature should carry the OCSP response ({})",
+            status
+        );
+    }
+
+    println!("✓ OCSP responses stapled for good and revoked signers");
+    Ok(())
+}
+
+#[test]
+fn test_ocsp_needs_issuer_and_responder() -> Result<()> {
+    let dir = output_dir().join("ocsp_tests");
+    fs::create_dir_all(&dir)?;
+
+    // The responder certificate and key must be given together with the status
+    let result = sign(
+        &certs_dir().join("ed25519.pub"),
+        &certs_dir().join("ed25519.pem"),
+        &dir.join("unused.jpg"),
+        &["--ocsp-status".as_ref(), "good".as_ref()],
+    )?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--ocsp-responder"));
+
+    // A certificate without its issuer cannot be identified in an OCSP request
+    let Some(pki) = create_pki(&dir.join("pki_no_issuer"))? else {
+        return Ok(());
+    };
+    let result = sign(
+        &pki.ca_cert,
+        &pki.ca_key,
+        &dir.join("unused.jpg"),
+        &[
+            "--ocsp-status".as_ref(),
+            "good".as_ref(),
+            "--ocsp-responder-cert".as_ref(),
+            pki.ca_cert.as_os_str(),
+            "--ocsp-responder-key".as_ref(),
+            pki.ca_key.as_os_str(),
+        ],
+    )?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("issuer"));
+    Ok(())
+}
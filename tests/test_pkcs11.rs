@@ -0,0 +1,181 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! PKCS#11 signing against SoftHSM. Skipped when SoftHSM is not installed; set
+//! SOFTHSM2_MODULE to the libsofthsm2 path if it is not in a standard location.
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+const USER_PIN: &str = "1234";
+const KEY_LABEL: &str = "c2pa-test-key";
+
+/// Locate the SoftHSM PKCS#11 module
+fn softhsm_module() -> Option<PathBuf> {
+    if let Ok(module) = std::env::var("SOFTHSM2_MODULE") {
+        return Some(PathBuf::from(module));
+    }
+    [
+        "/usr/lib/softhsm/libsofthsm2.so",
+        "/usr/lib/x86_64-linux-gnu/softhsm/libsofthsm2.so",
+        "/usr/lib/aarch64-linux-gnu/softhsm/libsofthsm2.so",
+        "/usr/local/lib/softhsm/libsofthsm2.so",
+        "/opt/homebrew/lib/softhsm/libsofthsm2.so",
+    ]
+    .iter()
+    .map(PathBuf::from)
+    .find(|path| path.exists())
+}
+
+/// Create a fresh SoftHSM token in `dir` holding the Ed25519 test key.
+/// Returns the SOFTHSM2_CONF path, or None if SoftHSM cannot be set up here.
+fn init_token(dir: &Path) -> Result<Option<PathBuf>> {
+    let token_dir = dir.join("tokens");
+    if token_dir.exists() {
+        fs::remove_dir_all(&token_dir)?;
+    }
+    fs::create_dir_all(&token_dir)?;
+    let conf = dir.join("softhsm2.conf");
+    fs::write(
+        &conf,
+        format!(
+            "directories.tokendir = {}\nobjectstore.backend = file\n",
+            token_dir.display()
+        ),
+    )?;
+
+    let softhsm = |args: &[&str]| {
+        Command::new("softhsm2-util")
+            .args(args)
+            .env("SOFTHSM2_CONF", &conf)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+
+    let key = certs_dir().join("ed25519.pem");
+    let imported = softhsm(&[
+        "--init-token",
+        "--free",
+        "--label",
+        "c2pa-test",
+        "--pin",
+        USER_PIN,
+        "--so-pin",
+        "5678",
+    ]) && softhsm(&[
+        "--import",
+        &key.to_string_lossy(),
+        "--token",
+        "c2pa-test",
+        "--label",
+        KEY_LABEL,
+        "--id",
+        "01",
+        "--pin",
+        USER_PIN,
+    ]);
+
+    Ok(imported.then_some(conf))
+}
+
+#[test]
+fn test_sign_with_softhsm_key() -> Result<()> {
+    let Some(module) = softhsm_module() else {
+        println!("Skipping: SoftHSM module not found");
+        return Ok(());
+    };
+    let dir = output_dir().join("pkcs11_tests");
+    fs::create_dir_all(&dir)?;
+    let Some(conf) = init_token(&dir)? else {
+        println!("Skipping: could not create a SoftHSM token with the Ed25519 test key");
+        return Ok(());
+    };
+
+    let output = dir.join("softhsm_signed.jpg");
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(&output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--pkcs11-module")
+        .arg(&module)
+        .arg("--key-label")
+        .arg(KEY_LABEL)
+        .env("SOFTHSM2_CONF", &conf)
+        .env("C2PA_PKCS11_PIN", USER_PIN)
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let reader = verify_signed_file(&output)?;
+    assert!(reader.active_manifest().is_some());
+
+    // An unknown label is reported before anything is signed
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(dir.join("missing_key.jpg"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--pkcs11-module")
+        .arg(&module)
+        .arg("--key-label")
+        .arg("no-such-key")
+        .env("SOFTHSM2_CONF", &conf)
+        .env("C2PA_PKCS11_PIN", USER_PIN)
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("no-such-key"));
+
+    println!("✓ Signed with a key held in SoftHSM");
+    Ok(())
+}
+
+#[test]
+fn test_pkcs11_options_need_module_and_label() -> Result<()> {
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--key-label")
+        .arg(KEY_LABEL)
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--pkcs11-module"));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output_dir().join("pkcs11_tests/unused.jpg"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--pkcs11-module")
+        .arg("/nonexistent/libpkcs11.so")
+        .output()?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--key-label"));
+    Ok(())
+}
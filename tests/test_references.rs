@@ -0,0 +1,177 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Cloud-data and external-reference assertions hashed from local files, and the
+//! `--serve-references` stand-in that serves those files.
+
+use anyhow::{Context, Result};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+fn sign_command(output: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"));
+    command
+        .arg("--manifest")
+        .arg(manifests_dir().join("referenced_data_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed");
+    command
+}
+
+/// Data of the active manifest's assertion with this label
+fn assertion_data(output: &Path, label: &str) -> Result<JsonValue> {
+    let reader = verify_signed_file(output)?;
+    let manifest = reader.active_manifest().context("No active manifest")?;
+    let assertion = manifest
+        .assertions()
+        .iter()
+        .find(|a| a.label() == label)
+        .context(format!("{} missing", label))?;
+    Ok(assertion.value()?.clone())
+}
+
+/// Hash bytes as the reader reports them, if they are a list of byte values
+fn hash_bytes(value: &JsonValue) -> Option<Vec<u8>> {
+    value
+        .as_array()?
+        .iter()
+        .map(|b| b.as_u64().map(|b| b as u8))
+        .collect()
+}
+
+/// GET a path from the stand-in, returning the status code and body
+fn get(addr: &str, path: &str) -> Result<(u16, Vec<u8>)> {
+    let mut stream = TcpStream::connect(addr)?;
+    write!(
+        stream,
+        "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+        path, addr
+    )?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let header_end = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .context("Malformed HTTP response")?;
+    let status = String::from_utf8_lossy(&response[..header_end])
+        .split_whitespace()
+        .nth(1)
+        .context("Missing status code")?
+        .parse()?;
+    Ok((status, response[header_end + 4..].to_vec()))
+}
+
+#[test]
+fn test_reference_hashes_from_files() -> Result<()> {
+    let dir = output_dir().join("references_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("Dog_references.jpg");
+
+    let result = sign_command(&output).output()?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let file = fs::read(manifests_dir().join("referenced_data/cloud-actions.json"))?;
+    let cloud = assertion_data(&output, "c2pa.cloud-data")?;
+    assert_eq!(cloud["size"], file.len());
+    assert_eq!(cloud["content_type"], "application/json");
+    assert_eq!(cloud["location"]["alg"], "sha256");
+    assert_eq!(
+        cloud["location"]["url"],
+        "https://cloud.example.com/assertions/cloud-actions.json"
+    );
+    assert!(cloud.get("file_path").is_none());
+    if let Some(hash) = hash_bytes(&cloud["location"]["hash"]) {
+        assert_eq!(hash, Sha256::digest(&file).to_vec());
+    }
+
+    let metadata = fs::read(manifests_dir().join("referenced_data/asset-metadata.json"))?;
+    let external = assertion_data(&output, "c2pa.external-reference")?;
+    assert_eq!(external["location"]["size"], metadata.len());
+    assert_eq!(external["location"]["dc:format"], "application/json");
+    assert!(!external["location"]["hash"].is_null());
+    println!("✓ Reference hashes computed from local files");
+    Ok(())
+}
+
+#[test]
+fn test_serve_references() -> Result<()> {
+    let dir = output_dir().join("references_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("Dog_served_references.jpg");
+
+    let mut child = sign_command(&output)
+        .arg("--serve-references")
+        .arg("127.0.0.1:0")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+
+    // The stand-in prints its address once signing is done and it is serving
+    let stdout = child.stdout.take().expect("stdout should be piped");
+    let mut lines = BufReader::new(stdout).lines();
+    let started = Instant::now();
+    let base_url = loop {
+        let line = lines.next().context("Signer exited before serving")??;
+        if let Some(url) = line.strip_prefix("Serving references on ") {
+            break url.trim().to_string();
+        }
+        assert!(started.elapsed() < Duration::from_secs(60));
+    };
+    std::thread::spawn(move || lines.for_each(drop));
+
+    let result = (|| -> Result<()> {
+        let cloud = assertion_data(&output, "c2pa.cloud-data")?;
+        let url = cloud["location"]["url"].as_str().context("No url")?;
+        assert_eq!(url, format!("{}/cloud-actions.json", base_url));
+
+        // Fetch the data the way a validator would and check it against the manifest
+        let addr = base_url.trim_start_matches("http://");
+        let (status, body) = get(addr, "/cloud-actions.json")?;
+        assert_eq!(status, 200);
+        assert_eq!(body.len() as u64, cloud["size"].as_u64().unwrap());
+        if let Some(hash) = hash_bytes(&cloud["location"]["hash"]) {
+            assert_eq!(hash, Sha256::digest(&body).to_vec());
+        }
+
+        let (status, _) = get(addr, "/asset-metadata.json")?;
+        assert_eq!(status, 200);
+        let (status, _) = get(addr, "/not-referenced.json")?;
+        assert_eq!(status, 404);
+        Ok(())
+    })();
+
+    let _ = child.kill();
+    let _ = child.wait();
+    result?;
+    println!("✓ Referenced files served from the local stand-in");
+    Ok(())
+}
@@ -0,0 +1,86 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, output_dir, testfiles_dir};
+
+/// The spatial changes region of this manifest (100,150 400x300) does not fit Dog.jpg (710x430)
+fn spatial_manifest() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("testset/p-actions-changes-spatial.json")
+}
+
+fn sign(output_name: &str, extra_args: &[&str]) -> Result<Output> {
+    let dir = output_dir().join("regions_tests");
+    fs::create_dir_all(&dir)?;
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(spatial_manifest())
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(dir.join(output_name))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .args(extra_args)
+        .output()?)
+}
+
+#[test]
+fn test_region_outside_image_warns() -> Result<()> {
+    let result = sign("region_warning.jpg", &[])?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(stdout.contains("1 invalid region(s) for the 710x430 image"));
+    assert!(stdout.contains("c2pa.actions.v2/actions/1/changes/0/region/0"));
+    Ok(())
+}
+
+#[test]
+fn test_strict_regions_rejects_region_outside_image() -> Result<()> {
+    let result = sign("region_strict.jpg", &["--strict-regions"])?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("extends beyond the 710x430 image"));
+
+    // Dog.png (1024x683) is large enough for the same region
+    let dir = output_dir().join("regions_tests");
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(spatial_manifest())
+        .arg(testfiles_dir().join("Dog.png"))
+        .arg("--output")
+        .arg(dir.join("region_fits.png"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .arg("--strict-regions")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    Ok(())
+}
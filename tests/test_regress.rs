@@ -0,0 +1,113 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, output_dir, testset_dir};
+
+/// Testset manifests used for the regression round trip
+const MANIFESTS: &[&str] = &[
+    "p-actions-created",
+    "p-actions-placed",
+    "n-actions-softwareAgent-and-index",
+];
+
+/// Run the CLI in regression mode over the test manifests
+/// Signed assets are written next to the goldens directory
+fn run_regress(goldens: &Path, update: bool) -> Result<Output> {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"));
+    command.arg("--regress");
+    for name in MANIFESTS {
+        command.arg(testset_dir().join(format!("{}.json", name)));
+    }
+    command
+        .arg("--goldens")
+        .arg(goldens)
+        .arg("--output")
+        .arg(goldens.with_extension("signed"))
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed");
+    if update {
+        command.arg("--update-goldens");
+    }
+
+    Ok(command.output()?)
+}
+
+#[test]
+fn test_regress_roundtrip() -> Result<()> {
+    let goldens = output_dir().join("regress_tests/goldens");
+    if goldens.exists() {
+        fs::remove_dir_all(&goldens)?;
+    }
+
+    // Without goldens every manifest fails
+    let result = run_regress(&goldens, false)?;
+    assert!(!result.status.success(), "Missing goldens should fail");
+
+    // Record the goldens
+    let result = run_regress(&goldens, true)?;
+    assert!(
+        result.status.success(),
+        "Updating goldens failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    for name in MANIFESTS {
+        assert!(goldens.join(format!("{}.json", name)).exists());
+        assert!(goldens.join(format!("{}_jpt.json", name)).exists());
+    }
+
+    // A fresh signing run (new URNs, new signatures) still matches
+    let result = run_regress(&goldens, false)?;
+    assert!(
+        result.status.success(),
+        "Re-signing should match the goldens: {}",
+        String::from_utf8_lossy(&result.stdout)
+    );
+
+    println!("✓ Regression goldens round trip");
+    Ok(())
+}
+
+#[test]
+fn test_regress_detects_changed_golden() -> Result<()> {
+    let goldens = output_dir().join("regress_tests/changed_goldens");
+    if goldens.exists() {
+        fs::remove_dir_all(&goldens)?;
+    }
+
+    let result = run_regress(&goldens, true)?;
+    assert!(result.status.success());
+
+    // Tamper with one golden so it no longer matches what c2pa-rs produces
+    let golden = goldens.join("p-actions-created.json");
+    let mut value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&golden)?)?;
+    value["validation_state"] = serde_json::json!("Tampered");
+    fs::write(&golden, serde_json::to_string_pretty(&value)?)?;
+
+    let result = run_regress(&goldens, false)?;
+    assert!(!result.status.success(), "Changed golden should fail");
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    assert!(stdout.contains("p-actions-created"));
+    assert!(stdout.contains("Tampered"));
+
+    println!("✓ Regression detects changed goldens");
+    Ok(())
+}
@@ -0,0 +1,105 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir};
+
+/// Get the binary path for the CLI tool
+fn get_binary_path() -> String {
+    env!("CARGO_BIN_EXE_c2pa-testfile-maker").to_string()
+}
+
+#[test]
+fn test_extract_exports_thumbnail_resource() -> Result<()> {
+    let dir = output_dir().join("resources_tests");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    let signed = dir.join("thumbnail.jpg");
+
+    // Sign with a generated claim thumbnail
+    let result = Command::new(get_binary_path())
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(&signed)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .arg("--thumbnail-asset")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let output = dir.join("thumbnail_manifest.json");
+    let result = Command::new(get_binary_path())
+        .arg("--extract")
+        .arg(&signed)
+        .arg("--output")
+        .arg(&output)
+        .arg("--resources")
+        .arg(dir.join("resources"))
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Extraction failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // The thumbnail reference now points at a JPEG written next to the JSON
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(&output)?)?;
+    let active = json["active_manifest"].as_str().unwrap();
+    let identifier = json["manifests"][active]["thumbnail"]["identifier"]
+        .as_str()
+        .expect("Active manifest should have a thumbnail");
+    assert!(
+        identifier.starts_with("resources/thumbnail/"),
+        "Thumbnail should point at the exported file. Got: {}",
+        identifier
+    );
+
+    let thumbnail = fs::read(dir.join(identifier))?;
+    assert!(
+        thumbnail.starts_with(&[0xFF, 0xD8]),
+        "Thumbnail should be a JPEG"
+    );
+
+    println!("✓ Claim thumbnail exported to {}", identifier);
+    Ok(())
+}
+
+#[test]
+fn test_resources_requires_extract() -> Result<()> {
+    let result = Command::new(get_binary_path())
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--resources")
+        .arg(output_dir().join("resources_tests/unused"))
+        .output()?;
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--resources"));
+    Ok(())
+}
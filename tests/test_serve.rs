@@ -0,0 +1,206 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, testfiles_dir};
+
+/// A running `--serve` process, stopped when dropped
+struct Service {
+    child: Child,
+    addr: String,
+}
+
+impl Service {
+    fn start(with_signing: bool) -> Result<Self> {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"));
+        command.arg("--serve").arg("--bind").arg("127.0.0.1:0");
+        if with_signing {
+            command
+                .arg("--cert")
+                .arg(certs_dir().join("ed25519.pub"))
+                .arg("--key")
+                .arg(certs_dir().join("ed25519.pem"))
+                .arg("--allow-self-signed");
+        }
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        // The service prints its address once it is listening
+        let stdout = child.stdout.take().expect("stdout should be piped");
+        let mut lines = BufReader::new(stdout).lines();
+        let addr = loop {
+            let line = lines.next().context("Service exited before listening")??;
+            if let Some(addr) = line.strip_prefix("Listening on http://") {
+                break addr.trim().to_string();
+            }
+        };
+        // Keep draining the request log so the service never blocks on a full pipe
+        std::thread::spawn(move || lines.for_each(drop));
+
+        Ok(Service { child, addr })
+    }
+
+    /// Send a request and return the status code and body
+    fn request(
+        &self,
+        method: &str,
+        path: &str,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Result<(u16, Vec<u8>)> {
+        let mut stream = TcpStream::connect(&self.addr)?;
+        let mut head = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: {}\r\n",
+            method,
+            path,
+            self.addr,
+            body.len()
+        );
+        if let Some(content_type) = content_type {
+            head.push_str(&format!("Content-Type: {}\r\n", content_type));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(body)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let header_end = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .context("Malformed HTTP response")?;
+        let status_line = String::from_utf8_lossy(&response[..header_end]);
+        let status = status_line
+            .split_whitespace()
+            .nth(1)
+            .context("Missing status code")?
+            .parse()?;
+        Ok((status, response[header_end + 4..].to_vec()))
+    }
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// One form part: name, filename, content type, data
+type FormPart<'a> = (&'a str, Option<&'a str>, Option<&'a str>, &'a [u8]);
+
+/// Build a multipart/form-data body, returning its Content-Type and bytes
+fn multipart(parts: &[FormPart]) -> (String, Vec<u8>) {
+    let boundary = "c2paTestBoundary7MA4YWxk";
+    let mut body = Vec::new();
+    for (name, filename, content_type, data) in parts {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        let mut disposition = format!("Content-Disposition: form-data; name=\"{}\"", name);
+        if let Some(filename) = filename {
+            disposition.push_str(&format!("; filename=\"{}\"", filename));
+        }
+        body.extend_from_slice(disposition.as_bytes());
+        body.extend_from_slice(b"\r\n");
+        if let Some(content_type) = content_type {
+            body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+        }
+        body.extend_from_slice(b"\r\n");
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+#[test]
+fn test_serve_sign_then_extract() -> Result<()> {
+    let service = Service::start(true)?;
+
+    let (status, body) = service.request("GET", "/health", None, b"")?;
+    assert_eq!(status, 200);
+    assert!(String::from_utf8_lossy(&body).contains("ok"));
+
+    let asset = fs::read(testfiles_dir().join("Dog.jpg"))?;
+    let manifest = fs::read(manifests_dir().join("simple_manifest.json"))?;
+    let (content_type, form) = multipart(&[
+        ("manifest", None, None, &manifest),
+        ("asset", Some("Dog.jpg"), Some("image/jpeg"), &asset),
+    ]);
+    let (status, signed) = service.request("POST", "/sign", Some(&content_type), &form)?;
+    assert_eq!(status, 200, "{}", String::from_utf8_lossy(&signed));
+    assert!(
+        signed.starts_with(&[0xFF, 0xD8]),
+        "Response should be a JPEG"
+    );
+
+    // Raw body extraction in both formats
+    let (status, json) = service.request("POST", "/extract", Some("image/jpeg"), &signed)?;
+    assert_eq!(status, 200, "{}", String::from_utf8_lossy(&json));
+    let json: serde_json::Value = serde_json::from_slice(&json)?;
+    assert!(json["active_manifest"].is_string());
+
+    let (status, json) = service.request(
+        "POST",
+        "/extract?jpt=true&format=jpg",
+        Some("application/octet-stream"),
+        &signed,
+    )?;
+    assert_eq!(status, 200, "{}", String::from_utf8_lossy(&json));
+    let json: serde_json::Value = serde_json::from_slice(&json)?;
+    assert!(
+        json["manifests"].is_array(),
+        "JPEG Trust manifests is an array"
+    );
+
+    println!("✓ Signed and extracted over HTTP");
+    Ok(())
+}
+
+#[test]
+fn test_serve_errors_and_validate() -> Result<()> {
+    let service = Service::start(false)?;
+
+    // Signing is disabled without credentials
+    let (content_type, form) = multipart(&[("asset", Some("a.jpg"), None, b"")]);
+    let (status, _) = service.request("POST", "/sign", Some(&content_type), &form)?;
+    assert_eq!(status, 503);
+
+    // Unknown format
+    let (status, body) = service.request("POST", "/extract", None, b"data")?;
+    assert_eq!(status, 400);
+    assert!(String::from_utf8_lossy(&body).contains("error"));
+
+    let (status, _) = service.request("GET", "/nowhere", None, b"")?;
+    assert_eq!(status, 404);
+
+    let (status, body) = service.request("POST", "/validate", Some("application/json"), b"{}")?;
+    assert_eq!(status, 200);
+    let result: serde_json::Value = serde_json::from_slice(&body)?;
+    assert!(result["valid"].is_boolean());
+    assert!(result["errors"].is_array());
+
+    let (status, _) =
+        service.request("POST", "/validate", Some("application/json"), b"not json")?;
+    assert_eq!(status, 400);
+
+    println!("✓ Error responses and validation over HTTP");
+    Ok(())
+}
@@ -0,0 +1,95 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir, verify_signed_file};
+
+/// Sign Dog.jpg with the simple manifest, using `signer_cmd` instead of a key file
+fn sign_with_command(signer_cmd: &str, output: &Path) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--output")
+        .arg(output)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--signer-cmd")
+        .arg(signer_cmd)
+        .output()?)
+}
+
+#[test]
+fn test_signer_cmd_with_openssl() -> Result<()> {
+    // OpenSSL stands in for a KMS or signing daemon: it signs stdin with the Ed25519 key
+    // (one-shot Ed25519 signing from stdin needs OpenSSL 3)
+    let key = certs_dir().join("ed25519.pem");
+    let signer_cmd = format!("openssl dgst -sign {}", key.display());
+    let probe = Command::new("openssl")
+        .args(["dgst", "-sign"])
+        .arg(&key)
+        .stdin(Stdio::null())
+        .output();
+    if !probe.is_ok_and(|output| output.status.success() && output.stdout.len() == 64) {
+        println!("Skipping: openssl with Ed25519 stdin signing not available");
+        return Ok(());
+    }
+
+    let dir = output_dir().join("signer_cmd_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("openssl_signed.jpg");
+    let result = sign_with_command(&signer_cmd, &output)?;
+    assert!(
+        result.status.success(),
+        "Signing failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let reader = verify_signed_file(&output)?;
+    assert!(reader.active_manifest().is_some());
+
+    println!("✓ Signed through an external signer command");
+    Ok(())
+}
+
+#[test]
+fn test_signer_cmd_failure_is_reported() -> Result<()> {
+    let dir = output_dir().join("signer_cmd_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join("missing_signer.jpg");
+
+    let result = sign_with_command("c2pa-no-such-signer-program", &output)?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("c2pa-no-such-signer-program"));
+    Ok(())
+}
+
+#[test]
+fn test_signer_cmd_conflicts_with_key() -> Result<()> {
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--signer-cmd")
+        .arg("openssl")
+        .output()?;
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--signer-cmd"));
+    Ok(())
+}
@@ -0,0 +1,133 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, testfiles_dir};
+
+/// Run the CLI with the given arguments, feeding `stdin` to it
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> Result<Output> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    child
+        .stdin
+        .take()
+        .expect("stdin should be piped")
+        .write_all(stdin)?;
+    Ok(child.wait_with_output()?)
+}
+
+/// Arguments for signing Dog.jpg with the simple manifest
+fn signing_args() -> Vec<String> {
+    vec![
+        "--manifest".to_string(),
+        manifests_dir()
+            .join("simple_manifest.json")
+            .to_string_lossy()
+            .into_owned(),
+        "--cert".to_string(),
+        certs_dir()
+            .join("ed25519.pub")
+            .to_string_lossy()
+            .into_owned(),
+        "--key".to_string(),
+        certs_dir()
+            .join("ed25519.pem")
+            .to_string_lossy()
+            .into_owned(),
+        "--allow-self-signed".to_string(),
+    ]
+}
+
+#[test]
+fn test_sign_and_extract_through_pipes() -> Result<()> {
+    let asset = fs::read(testfiles_dir().join("Dog.jpg"))?;
+
+    // Sign: stdin -> stdout
+    let mut args = signing_args();
+    args.extend(["-", "--output", "-", "--format", "jpg"].map(String::from));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let signed = run_with_stdin(&args, &asset)?;
+    assert!(
+        signed.status.success(),
+        "Signing through pipes failed: {}",
+        String::from_utf8_lossy(&signed.stderr)
+    );
+    assert!(
+        signed.stdout.starts_with(&[0xFF, 0xD8]),
+        "stdout should contain only the signed JPEG"
+    );
+    assert!(String::from_utf8_lossy(&signed.stderr).contains("Successfully created"));
+
+    // Extract: stdin -> stdout
+    let extracted = run_with_stdin(
+        &["--extract", "-", "--output", "-", "--format", "image/jpeg"],
+        &signed.stdout,
+    )?;
+    assert!(
+        extracted.status.success(),
+        "Extraction through pipes failed: {}",
+        String::from_utf8_lossy(&extracted.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&extracted.stdout)?;
+    assert!(json["active_manifest"].is_string());
+
+    println!("✓ Signed and extracted through stdin/stdout");
+    Ok(())
+}
+
+#[test]
+fn test_sign_file_to_stdout_uses_input_extension() -> Result<()> {
+    let mut args = signing_args();
+    let input = testfiles_dir()
+        .join("Dog.jpg")
+        .to_string_lossy()
+        .into_owned();
+    args.extend([input, "--output".to_string(), "-".to_string()]);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = run_with_stdin(&args, &[])?;
+    assert!(
+        result.status.success(),
+        "Signing to stdout failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // The signed asset can be written anywhere by the caller
+    let output = output_dir().join("stdio_tests/from_stdout.jpg");
+    fs::create_dir_all(output.parent().unwrap())?;
+    fs::write(&output, &result.stdout)?;
+    assert!(result.stdout.starts_with(&[0xFF, 0xD8]));
+    Ok(())
+}
+
+#[test]
+fn test_stdin_requires_format() -> Result<()> {
+    let mut args = signing_args();
+    args.extend(["-", "--output", "-"].map(String::from));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let result = run_with_stdin(&args, b"not an image")?;
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--format"));
+    Ok(())
+}
@@ -0,0 +1,117 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Stress mode: many ingredients, large assertions, long histories and deep chains.
+
+use anyhow::Result;
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, output_dir, verify_signed_file};
+
+#[test]
+fn test_stress_corpus_small_limits() -> Result<()> {
+    let dir = output_dir().join("stress_tests");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    // Small counts keep the test quick; 200 KiB still needs several APP11 segments
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--stress")
+        .arg("ingredients=20,actions=100,depth=5,assertion-kib=200")
+        .arg("--output")
+        .arg(&dir)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Stress failed: {}\n{}",
+        String::from_utf8_lossy(&result.stdout),
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let report: JsonValue =
+        serde_json::from_str(&fs::read_to_string(dir.join("stress_report.json"))?)?;
+    let case = |name: &str| {
+        report
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|r| r["case"] == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("no case {}", name))
+    };
+    assert!(
+        case("stress-assertion-200kib")["app11_segments"]
+            .as_u64()
+            .unwrap()
+            >= 4
+    );
+    assert_eq!(case("stress-chain-5")["manifests"], 5);
+
+    for name in [
+        "stress-ingredients-20.jpg",
+        "stress-assertion-200kib.jpg",
+        "stress-actions-100.jpg",
+        "stress-chain-5.jpg",
+    ] {
+        verify_signed_file(&dir.join(name))?;
+    }
+    println!("✓ Stress corpus signed and read back");
+    Ok(())
+}
+
+#[test]
+fn test_stress_relative_paths() -> Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let relative = output_dir()
+        .join("stress_relative_tests")
+        .strip_prefix(root)?
+        .to_path_buf();
+    if root.join(&relative).exists() {
+        fs::remove_dir_all(root.join(&relative))?;
+    }
+
+    // Ingredient paths and chain steps stay relative to the working directory
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .current_dir(root)
+        .arg("--stress")
+        .arg("ingredients=2,actions=2,depth=3,assertion-kib=1")
+        .arg("testfiles/Dog.jpg")
+        .arg("--output")
+        .arg(&relative)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?;
+    assert!(
+        result.status.success(),
+        "Stress failed: {}\n{}",
+        String::from_utf8_lossy(&result.stdout),
+        String::from_utf8_lossy(&result.stderr)
+    );
+    verify_signed_file(&root.join(&relative).join("stress-ingredients-2.jpg"))?;
+    verify_signed_file(&root.join(&relative).join("stress-chain-3.jpg"))?;
+    println!("✓ Stress corpus signed with a relative asset and output directory");
+    Ok(())
+}
@@ -0,0 +1,83 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::process::Command;
+
+mod common;
+
+use common::{manifests_dir, output_dir, sign_file_with_manifest, testfiles_dir};
+
+#[test]
+fn test_extract_prints_and_writes_summary() -> Result<()> {
+    let dir = output_dir().join("summary_tests");
+    fs::create_dir_all(&dir)?;
+    let signed = dir.join("summary.jpg");
+    sign_file_with_manifest(
+        &testfiles_dir().join("Dog.jpg"),
+        &signed,
+        &manifests_dir().join("simple_manifest.json"),
+    )?;
+
+    for (jpt, json_name) in [(false, "summary.json"), (true, "summary_jpt.json")] {
+        let output = dir.join(json_name);
+        let mut command = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"));
+        command
+            .arg("--extract")
+            .arg(&signed)
+            .arg("--output")
+            .arg(&output)
+            .arg("--summary");
+        if jpt {
+            command.arg("--jpt");
+        }
+        let result = command.output()?;
+
+        assert!(
+            result.status.success(),
+            "Extraction failed: {}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+
+        let stdout = String::from_utf8_lossy(&result.stdout);
+        assert!(stdout.contains("Validation summary"));
+        assert!(stdout.contains("Signer: C2PA Signer"));
+        assert!(stdout.contains("Success codes"));
+
+        let summary_path = output.with_file_name(format!(
+            "{}_summary.txt",
+            output.file_stem().unwrap().to_str().unwrap()
+        ));
+        let summary = fs::read_to_string(&summary_path)?;
+        assert!(summary.contains("Validation state:"));
+        assert!(summary.contains("Failure codes"));
+        assert!(summary.contains("Ingredients:"));
+    }
+
+    println!("✓ Validation summary printed and written for both formats");
+    Ok(())
+}
+
+#[test]
+fn test_summary_requires_extract() -> Result<()> {
+    let result = Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg(testfiles_dir().join("Dog.jpg"))
+        .arg("--manifest")
+        .arg(manifests_dir().join("simple_manifest.json"))
+        .arg("--summary")
+        .output()?;
+
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--summary"));
+    Ok(())
+}
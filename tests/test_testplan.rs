@@ -0,0 +1,141 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+//! Test plan mode: rows of a CSV or XLSX plan signed into a corpus with a results column.
+
+use anyhow::Result;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+mod common;
+
+use common::{certs_dir, output_dir, testset_dir, verify_signed_file};
+
+fn run_plan(plan: &Path, dir: &Path) -> Result<Output> {
+    Ok(Command::new(env!("CARGO_BIN_EXE_c2pa-testfile-maker"))
+        .arg("--plan")
+        .arg(plan)
+        .arg("--output")
+        .arg(dir)
+        .arg("--cert")
+        .arg(certs_dir().join("ed25519.pub"))
+        .arg("--key")
+        .arg(certs_dir().join("ed25519.pem"))
+        .arg("--allow-self-signed")
+        .output()?)
+}
+
+/// Result column of the results table, keyed by the first column
+fn results(path: &Path) -> Result<Vec<(String, String)>> {
+    let mut reader = csv::Reader::from_path(path)?;
+    let column = reader
+        .headers()?
+        .iter()
+        .position(|h| h == "Result")
+        .expect("results have a Result column");
+    reader
+        .records()
+        .map(|row| {
+            let row = row?;
+            Ok((row[0].to_string(), row[column].to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn test_plan_results_table() -> Result<()> {
+    let dir = output_dir().join("testplan_tests");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+    let plan = dir.join("plan.csv");
+    let testset = testset_dir();
+    fs::write(
+        &plan,
+        format!(
+            "Test ID,Asset,Manifest,Expected Result,Notes\n\
+             created,,{0}/p-actions-created.json,POSITIVE,signs cleanly\n\
+             overlap,,{0}/n-binding-data-hash-overlap.json,NEGATIVE,hash mismatch\n\
+             wrong,,{0}/n-binding-data-hash-overlap.json,POSITIVE,deliberately wrong\n\
+             disabled,,,POSITIVE,no manifest yet\n",
+            testset.display()
+        ),
+    )?;
+
+    let output = run_plan(&plan, &dir)?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "Plan failed: {}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("=== Test Plan Summary ==="));
+
+    verify_signed_file(&dir.join("created.jpg"))?;
+    let results = results(&dir.join("plan_results.csv"))?;
+    let result = |id: &str| {
+        results
+            .iter()
+            .find(|(row, _)| row == id)
+            .map(|(_, result)| result.clone())
+            .unwrap_or_default()
+    };
+    assert!(result("created").starts_with("PASS"), "{:?}", results);
+    assert!(
+        result("overlap").starts_with("PASS (Invalid"),
+        "{:?}",
+        results
+    );
+    assert!(result("wrong").starts_with("MISMATCH"), "{:?}", results);
+    assert_eq!(result("disabled"), "SKIPPED: no manifest");
+    println!("✓ Test plan rows signed and results written");
+    Ok(())
+}
+
+#[test]
+fn test_plan_requires_manifest_column() -> Result<()> {
+    let dir = output_dir().join("testplan_tests_no_manifest");
+    fs::create_dir_all(&dir)?;
+    let plan = dir.join("plan.csv");
+    fs::write(
+        &plan,
+        "Test Type,Assertion,Description\nPOSITIVE,actions,no manifest\n",
+    )?;
+    let output = run_plan(&plan, &dir)?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no Manifest column"));
+    Ok(())
+}
+
+#[test]
+fn test_testset_plan() -> Result<()> {
+    let dir = output_dir().join("testplan_tests_xlsx");
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    let output = run_plan(&testset_dir().join("C2PA-JPT Testfiles.xlsx"), &dir)?;
+    assert!(
+        output.status.success(),
+        "Plan failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    verify_signed_file(&dir.join("p-actions-created.jpg"))?;
+    let results = results(&dir.join("C2PA-JPT Testfiles_results.csv"))?;
+    assert!(results
+        .iter()
+        .any(|(_, result)| result == "SKIPPED: no manifest"));
+    println!("✓ Testset spreadsheet signed as a test plan");
+    Ok(())
+}
@@ -0,0 +1,126 @@
+/*
+Copyright 2025 Adobe. All rights reserved.
+This file is licensed to you under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License. You may obtain a copy
+of the License at http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software distributed under
+the License is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR REPRESENTATIONS
+OF ANY KIND, either express or implied. See the License for the specific language
+governing permissions and limitations under the License.
+*/
+
+use anyhow::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+mod common;
+
+use common::{certs_dir, manifests_dir, output_dir, sign_file_with_manifest, testfiles_dir};
+
+/// Get the binary path for the CLI tool
+fn get_binary_path() -> String {
+    env!("CARGO_BIN_EXE_c2pa-testfile-maker").to_string()
+}
+
+/// Sign Dog.jpg with the simple manifest for the trust tests
+fn signed_test_file(name: &str) -> Result<PathBuf> {
+    let dir = output_dir().join("trust_tests");
+    fs::create_dir_all(&dir)?;
+    let output = dir.join(name);
+    sign_file_with_manifest(
+        &testfiles_dir().join("Dog.jpg"),
+        &output,
+        &manifests_dir().join("simple_manifest.json"),
+    )?;
+    Ok(output)
+}
+
+/// Write the signing (end-entity) certificate of the test chain to its own PEM file
+fn write_signer_cert(path: &Path) -> Result<()> {
+    let chain = fs::read(certs_dir().join("ed25519.pub"))?;
+    let certs = pem::parse_many(chain)?;
+    fs::write(path, pem::encode(&certs[0]))?;
+    Ok(())
+}
+
+/// Extract a manifest with extra CLI arguments and return its validation state
+fn extract_validation_state(input: &Path, output: &Path, extra_args: &[&str]) -> Result<String> {
+    let result = Command::new(get_binary_path())
+        .arg("--extract")
+        .arg(input)
+        .arg("--output")
+        .arg(output)
+        .args(extra_args)
+        .output()?;
+
+    assert!(
+        result.status.success(),
+        "Extraction failed: {}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_str(&fs::read_to_string(output)?)?;
+    Ok(json["validation_state"]
+        .as_str()
+        .unwrap_or_default()
+        .to_string())
+}
+
+#[test]
+fn test_extract_without_trust_is_not_trusted() -> Result<()> {
+    let signed = signed_test_file("untrusted.jpg")?;
+    let output = output_dir().join("trust_tests/untrusted_manifest.json");
+
+    let state = extract_validation_state(&signed, &output, &[])?;
+    assert_ne!(
+        state, "Trusted",
+        "Test signer should not be trusted by default"
+    );
+
+    println!("✓ Default extraction reports {}", state);
+    Ok(())
+}
+
+#[test]
+fn test_extract_with_allowed_list_is_trusted() -> Result<()> {
+    let signed = signed_test_file("allowed.jpg")?;
+    let allowed = output_dir().join("trust_tests/allowed_list.pem");
+    write_signer_cert(&allowed)?;
+    let output = output_dir().join("trust_tests/allowed_manifest.json");
+
+    let state = extract_validation_state(
+        &signed,
+        &output,
+        &["--allowed-list", allowed.to_str().unwrap()],
+    )?;
+    assert_eq!(state, "Trusted");
+
+    println!("✓ Allowed-list extraction reports Trusted");
+    Ok(())
+}
+
+#[test]
+fn test_extract_with_missing_trust_file_fails() -> Result<()> {
+    let signed = signed_test_file("missing_anchors.jpg")?;
+
+    let result = Command::new(get_binary_path())
+        .arg("--extract")
+        .arg(&signed)
+        .arg("--output")
+        .arg(output_dir().join("trust_tests/missing_anchors_manifest.json"))
+        .arg("--trust-anchors")
+        .arg("/nonexistent/anchors.pem")
+        .output()?;
+
+    assert!(!result.status.success());
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(
+        stderr.contains("Trust anchors"),
+        "Error should name the trust file. Got: {}",
+        stderr
+    );
+
+    Ok(())
+}